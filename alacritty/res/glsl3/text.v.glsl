@@ -26,6 +26,9 @@ uniform vec4 projection;
 uniform int renderingPass;
 
 #define WIDE_CHAR 2
+#define DOUBLE_WIDTH 4
+#define DOUBLE_TOP 8
+#define DOUBLE_BOTTOM 16
 
 void main() {
     vec2 projectionOffset = projection.xy;
@@ -42,6 +45,25 @@ void main() {
     fg = vec4(textColor.rgb / 255.0, textColor.a);
     bg = backgroundColor / 255.0;
 
+    // Glyphs of double-height lines are scaled vertically and moved up by one cell for the bottom
+    // half, so only the part inside the cell is visible.
+    vec2 glyphScale = vec2(1.);
+    float glyphShift = 0.;
+    if (int(fg.a) >= DOUBLE_BOTTOM) {
+        glyphScale.y = 2.;
+        glyphShift = cellDim.y;
+        fg.a = round(fg.a - DOUBLE_BOTTOM);
+    } else if (int(fg.a) >= DOUBLE_TOP) {
+        glyphScale.y = 2.;
+        fg.a = round(fg.a - DOUBLE_TOP);
+    }
+
+    // Glyphs of double-width lines are scaled horizontally.
+    if (int(fg.a) >= DOUBLE_WIDTH) {
+        glyphScale.x = 2.;
+        fg.a = round(fg.a - DOUBLE_WIDTH);
+    }
+
     float occupiedCells = 1;
     if ((int(fg.a) >= WIDE_CHAR)) {
         // Update wide char x dimension so it'll cover the following spacer.
@@ -54,7 +76,7 @@ void main() {
 
     if (renderingPass == 0) {
         vec2 backgroundDim = cellDim;
-        backgroundDim.x *= occupiedCells;
+        backgroundDim.x *= occupiedCells * glyphScale.x;
 
         vec2 finalPosition = cellPosition + backgroundDim * position;
         gl_Position =
@@ -62,16 +84,25 @@ void main() {
 
         TexCoords = vec2(0, 0);
     } else {
-        vec2 glyphSize = glyph.zw;
-        vec2 glyphOffset = glyph.xy;
-        glyphOffset.y = cellDim.y - glyphOffset.y;
+        vec2 glyphSize = glyph.zw * glyphScale;
+        vec2 glyphOffset = glyph.xy * glyphScale;
+        glyphOffset.y = cellDim.y * glyphScale.y - glyphOffset.y - glyphShift;
 
         vec2 finalPosition = cellPosition + glyphSize * position + glyphOffset;
+
+        // Clip double-height glyphs to their half, adjusting the texture coordinates to match.
+        vec2 texturePosition = position;
+        if (glyphScale.y > 1. && glyphSize.y > 0.) {
+            float glyphTop = cellPosition.y + glyphOffset.y;
+            finalPosition.y = clamp(finalPosition.y, cellPosition.y, cellPosition.y + cellDim.y);
+            texturePosition.y = (finalPosition.y - glyphTop) / glyphSize.y;
+        }
+
         gl_Position =
             vec4(projectionOffset + projectionScale * finalPosition, 0.0, 1.0);
 
         vec2 uvOffset = uv.xy;
         vec2 uvSize = uv.zw;
-        TexCoords = uvOffset + position * uvSize;
+        TexCoords = uvOffset + texturePosition * uvSize;
     }
 }
@@ -0,0 +1,19 @@
+#if defined(GLES2_RENDERER)
+#define FRAG_COLOR gl_FragColor
+#define TEXTURE texture2D
+
+varying mediump vec2 texCoords;
+#else
+#define TEXTURE texture
+
+out vec4 FragColor;
+#define FRAG_COLOR FragColor
+
+in vec2 texCoords;
+#endif
+
+uniform sampler2D graphicTexture;
+
+void main() {
+    FRAG_COLOR = TEXTURE(graphicTexture, texCoords);
+}
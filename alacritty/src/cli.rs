@@ -9,7 +9,7 @@ use log::{self, error, LevelFilter};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 
-use alacritty_terminal::config::{Program, PtyConfig};
+use alacritty_terminal::config::{Hold, Program, PtyConfig};
 
 use crate::config::window::{Class, Identity};
 use crate::config::{serde_utils, UiConfig};
@@ -220,7 +220,9 @@ impl TerminalOptions {
             pty_config.shell = Some(command);
         }
 
-        pty_config.hold |= self.hold;
+        if self.hold {
+            pty_config.hold = Hold::Always;
+        }
     }
 }
 
@@ -229,7 +231,7 @@ impl From<TerminalOptions> for PtyConfig {
         PtyConfig {
             working_directory: options.working_directory.take(),
             shell: options.command(),
-            hold: options.hold,
+            hold: if options.hold { Hold::Always } else { Hold::default() },
         }
     }
 }
@@ -287,6 +289,30 @@ pub enum SocketMessage {
 
     /// Update the Alacritty configuration.
     Config(IpcConfig),
+
+    /// List all windows with their IDs, titles, sizes and shell PIDs.
+    ListWindows,
+
+    /// Focus a window.
+    FocusWindow(IpcWindow),
+
+    /// Close a window.
+    CloseWindow(IpcWindow),
+
+    /// Resize a window to fit the requested terminal dimensions.
+    ResizeWindow(IpcResize),
+
+    /// Write text to the PTY of a window.
+    SendText(IpcSendText),
+
+    /// Print the text content of a window's terminal.
+    GetText(IpcGetText),
+
+    /// Print terminal events as newline-delimited JSON until Alacritty exits.
+    Subscribe,
+
+    /// Terminate the shell of a window and start a new one.
+    RestartShell(IpcRestartShell),
 }
 
 /// Subset of options that we pass to 'create-window' IPC subcommand.
@@ -320,6 +346,71 @@ pub struct IpcConfig {
     pub reset: bool,
 }
 
+/// Window targeted by an IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcWindow {
+    /// Window ID targeted by the message.
+    ///
+    /// This can be omitted if only a single window is open.
+    #[clap(short, long, env = "ALACRITTY_WINDOW_ID")]
+    pub window_id: Option<i128>,
+}
+
+/// Parameters to the `resize-window` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcResize {
+    /// Target window.
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// Width of the terminal in character columns.
+    pub columns: usize,
+
+    /// Height of the terminal in character lines.
+    pub lines: usize,
+}
+
+/// Parameters to the `send-text` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcSendText {
+    /// Target window.
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// Text which should be written to the PTY.
+    #[clap(allow_hyphen_values = true)]
+    pub text: String,
+}
+
+/// Parameters to the `restart-shell` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcRestartShell {
+    /// Target window.
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// Keep the scrollback history of the old shell.
+    #[clap(short, long)]
+    pub keep_scrollback: bool,
+}
+
+/// Parameters to the `get-text` IPC subcommand.
+#[cfg(unix)]
+#[derive(Args, Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct IpcGetText {
+    /// Target window.
+    #[clap(flatten)]
+    pub window: IpcWindow,
+
+    /// Include the scrollback history instead of just the visible screen.
+    #[clap(short, long)]
+    pub scrollback: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -13,6 +13,9 @@ use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
 
 use alacritty_terminal::config::Program;
 use alacritty_terminal::term::TermMode;
+use alacritty_terminal::vi_mode::ViMotion;
+
+use crate::config::ui_config::Hint;
 
 /// Describes a state and action to take in that state.
 ///
@@ -89,6 +92,22 @@ pub enum Action {
     #[config(skip)]
     Command(Program),
 
+    /// Perform vi mode action.
+    #[config(skip)]
+    Vi(ViAction),
+
+    /// Perform vi mode motion.
+    #[config(skip)]
+    ViMotion(ViMotion),
+
+    /// Regex keyboard hints.
+    #[config(skip)]
+    Hint(Hint),
+
+    /// Perform search mode action.
+    #[config(skip)]
+    Search(SearchAction),
+
     /// Perform mouse binding exclusive action.
     #[config(skip)]
     Mouse(MouseAction),
@@ -99,6 +118,9 @@ pub enum Action {
     /// Store current selection into clipboard.
     Copy,
 
+    /// Store the URI of the hyperlink below the cursor into clipboard.
+    CopyHyperlink,
+
     #[cfg(not(any(target_os = "macos", windows)))]
     /// Store current selection into selection buffer.
     CopySelection,
@@ -139,6 +161,15 @@ pub enum Action {
     /// Scroll all the way to the bottom.
     ScrollToBottom,
 
+    /// Scroll to the previous shell prompt.
+    ScrollToPreviousPrompt,
+
+    /// Scroll to the next shell prompt.
+    ScrollToNextPrompt,
+
+    /// Select the output of the last command.
+    SelectLastCommandOutput,
+
     /// Clear the display buffer(s) to remove history.
     ClearHistory,
 
@@ -164,6 +195,9 @@ pub enum Action {
     /// Create a new Alacritty window.
     CreateNewWindow,
 
+    /// Replace the shell with a new one.
+    RestartShell,
+
     /// Toggle fullscreen.
     ToggleFullscreen,
 
@@ -177,6 +211,15 @@ pub enum Action {
     /// Clear active selection.
     ClearSelection,
 
+    /// Toggle vi mode.
+    ToggleViMode,
+
+    /// Start a forward buffer search.
+    SearchForward,
+
+    /// Start a backward buffer search.
+    SearchBackward,
+
     /// Allow receiving char input.
     ReceiveChar,
 
@@ -190,6 +233,24 @@ impl From<&'static str> for Action {
     }
 }
 
+impl From<ViAction> for Action {
+    fn from(action: ViAction) -> Self {
+        Self::Vi(action)
+    }
+}
+
+impl From<ViMotion> for Action {
+    fn from(motion: ViMotion) -> Self {
+        Self::ViMotion(motion)
+    }
+}
+
+impl From<SearchAction> for Action {
+    fn from(action: SearchAction) -> Self {
+        Self::Search(action)
+    }
+}
+
 impl From<MouseAction> for Action {
     fn from(action: MouseAction) -> Self {
         Self::Mouse(action)
@@ -200,12 +261,78 @@ impl From<MouseAction> for Action {
 impl Display for Action {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Action::ViMotion(motion) => motion.fmt(f),
+            Action::Vi(action) => action.fmt(f),
+            Action::Search(action) => action.fmt(f),
             Action::Mouse(action) => action.fmt(f),
             _ => write!(f, "{:?}", self),
         }
     }
 }
 
+/// Vi mode specific actions.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ViAction {
+    /// Toggle normal vi selection.
+    ToggleNormalSelection,
+
+    /// Toggle line vi selection.
+    ToggleLineSelection,
+
+    /// Toggle block vi selection.
+    ToggleBlockSelection,
+
+    /// Toggle semantic vi selection.
+    ToggleSemanticSelection,
+
+    /// Centers the screen around the vi mode cursor.
+    CenterAroundViCursor,
+
+    /// Launch the URL below the vi mode cursor.
+    Open,
+
+    /// Search forward within the buffer.
+    SearchNext,
+
+    /// Search backward within the buffer.
+    SearchPrevious,
+
+    /// Jump to the beginning of a search match.
+    SearchStart,
+
+    /// Jump to the end of a search match.
+    SearchEnd,
+}
+
+/// Search mode specific actions.
+#[allow(clippy::enum_variant_names)]
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SearchAction {
+    /// Move the focus to the next search match.
+    SearchFocusNext,
+
+    /// Move the focus to the previous search match.
+    SearchFocusPrevious,
+
+    /// Confirm the active search.
+    SearchConfirm,
+
+    /// Cancel the active search.
+    SearchCancel,
+
+    /// Reset the search regex.
+    SearchClear,
+
+    /// Delete the last word in the search regex.
+    SearchDeleteWord,
+
+    /// Go to the previous regex in the search history.
+    SearchHistoryPrevious,
+
+    /// Go to the next regex in the search history.
+    SearchHistoryNext,
+}
+
 /// Mouse binding specific actions.
 #[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum MouseAction {
@@ -275,7 +402,7 @@ pub fn default_mouse_bindings() -> Vec<MouseBinding> {
         MouseBinding;
         MouseButton::Right;                         MouseAction::ExpandSelection;
         MouseButton::Right,   ModifiersState::CTRL; MouseAction::ExpandSelection;
-        MouseButton::Middle; Action::PasteSelection;
+        MouseButton::Middle, ~BindingMode::VI;      Action::PasteSelection;
     )
 }
 
@@ -283,74 +410,167 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
     let mut bindings = bindings!(
         KeyBinding;
         Copy;  Action::Copy;
-        Paste; Action::Paste;
+        Copy,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ClearSelection;
+        Paste, ~BindingMode::VI; Action::Paste;
         L, ModifiersState::CTRL; Action::ClearLogNotice;
-        L,    ModifiersState::CTRL;
+        Return, +BindingMode::CHILD_EXITED; Action::RestartShell;
+        L,    ModifiersState::CTRL, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x0c".into());
-        Tab,  ModifiersState::SHIFT;
+        Tab,  ModifiersState::SHIFT, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[Z".into());
-        Back, ModifiersState::ALT;
+        Back, ModifiersState::ALT, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b\x7f".into());
-        Back, ModifiersState::SHIFT;
+        Back, ModifiersState::SHIFT, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x7f".into());
         Home,     ModifiersState::SHIFT, ~BindingMode::ALT_SCREEN; Action::ScrollToTop;
         End,      ModifiersState::SHIFT, ~BindingMode::ALT_SCREEN; Action::ScrollToBottom;
         PageUp,   ModifiersState::SHIFT, ~BindingMode::ALT_SCREEN; Action::ScrollPageUp;
         PageDown, ModifiersState::SHIFT, ~BindingMode::ALT_SCREEN; Action::ScrollPageDown;
-        Home,     ModifiersState::SHIFT, +BindingMode::ALT_SCREEN; Action::Esc("\x1b[1;2H".into());
-        End,      ModifiersState::SHIFT, +BindingMode::ALT_SCREEN; Action::Esc("\x1b[1;2F".into());
-        PageUp,   ModifiersState::SHIFT, +BindingMode::ALT_SCREEN; Action::Esc("\x1b[5;2~".into());
-        PageDown, ModifiersState::SHIFT, +BindingMode::ALT_SCREEN; Action::Esc("\x1b[6;2~".into());
-        Home,  +BindingMode::APP_CURSOR;
+        Home,     ModifiersState::SHIFT, +BindingMode::ALT_SCREEN, ~BindingMode::VI, ~BindingMode::SEARCH;
+            Action::Esc("\x1b[1;2H".into());
+        End,      ModifiersState::SHIFT, +BindingMode::ALT_SCREEN, ~BindingMode::VI, ~BindingMode::SEARCH;
+            Action::Esc("\x1b[1;2F".into());
+        PageUp,   ModifiersState::SHIFT, +BindingMode::ALT_SCREEN, ~BindingMode::VI, ~BindingMode::SEARCH;
+            Action::Esc("\x1b[5;2~".into());
+        PageDown, ModifiersState::SHIFT, +BindingMode::ALT_SCREEN, ~BindingMode::VI, ~BindingMode::SEARCH;
+            Action::Esc("\x1b[6;2~".into());
+        Home,  +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOH".into());
-        Home,  ~BindingMode::APP_CURSOR;
+        Home,  ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[H".into());
-        End,   +BindingMode::APP_CURSOR;
+        End,   +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOF".into());
-        End,   ~BindingMode::APP_CURSOR;
+        End,   ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[F".into());
-        Up,    +BindingMode::APP_CURSOR;
+        Up,    +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOA".into());
-        Up,    ~BindingMode::APP_CURSOR;
+        Up,    ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[A".into());
-        Down,  +BindingMode::APP_CURSOR;
+        Down,  +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOB".into());
-        Down,  ~BindingMode::APP_CURSOR;
+        Down,  ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[B".into());
-        Right, +BindingMode::APP_CURSOR;
+        Right, +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOC".into());
-        Right, ~BindingMode::APP_CURSOR;
+        Right, ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[C".into());
-        Left,  +BindingMode::APP_CURSOR;
+        Left,  +BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1bOD".into());
-        Left,  ~BindingMode::APP_CURSOR;
+        Left,  ~BindingMode::APP_CURSOR, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[D".into());
-        Back; Action::Esc("\x7f".into());
-        Insert; Action::Esc("\x1b[2~".into());
-        Delete; Action::Esc("\x1b[3~".into());
-        PageUp; Action::Esc("\x1b[5~".into());
-        PageDown; Action::Esc("\x1b[6~".into());
-        F1; Action::Esc("\x1bOP".into());
-        F2; Action::Esc("\x1bOQ".into());
-        F3; Action::Esc("\x1bOR".into());
-        F4; Action::Esc("\x1bOS".into());
-        F5; Action::Esc("\x1b[15~".into());
-        F6; Action::Esc("\x1b[17~".into());
-        F7; Action::Esc("\x1b[18~".into());
-        F8; Action::Esc("\x1b[19~".into());
-        F9; Action::Esc("\x1b[20~".into());
-        F10; Action::Esc("\x1b[21~".into());
-        F11; Action::Esc("\x1b[23~".into());
-        F12; Action::Esc("\x1b[24~".into());
-        F13; Action::Esc("\x1b[25~".into());
-        F14; Action::Esc("\x1b[26~".into());
-        F15; Action::Esc("\x1b[28~".into());
-        F16; Action::Esc("\x1b[29~".into());
-        F17; Action::Esc("\x1b[31~".into());
-        F18; Action::Esc("\x1b[32~".into());
-        F19; Action::Esc("\x1b[33~".into());
-        F20; Action::Esc("\x1b[34~".into());
-        NumpadEnter; Action::Esc("\n".into());
+        Back, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x7f".into());
+        Insert, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[2~".into());
+        Delete, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[3~".into());
+        PageUp, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[5~".into());
+        PageDown, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[6~".into());
+        F1, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1bOP".into());
+        F2, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1bOQ".into());
+        F3, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1bOR".into());
+        F4, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1bOS".into());
+        F5, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[15~".into());
+        F6, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[17~".into());
+        F7, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[18~".into());
+        F8, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[19~".into());
+        F9, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[20~".into());
+        F10, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[21~".into());
+        F11, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[23~".into());
+        F12, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[24~".into());
+        F13, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[25~".into());
+        F14, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[26~".into());
+        F15, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[28~".into());
+        F16, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[29~".into());
+        F17, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[31~".into());
+        F18, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[32~".into());
+        F19, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[33~".into());
+        F20, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\x1b[34~".into());
+        NumpadEnter, ~BindingMode::VI, ~BindingMode::SEARCH; Action::Esc("\n".into());
+        Space,  ModifiersState::SHIFT | ModifiersState::CTRL, ~BindingMode::SEARCH;
+            Action::ToggleViMode;
+        Space,  ModifiersState::SHIFT | ModifiersState::CTRL, +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollToBottom;
+        Escape,                        +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ClearSelection;
+        I,                             +BindingMode::VI, ~BindingMode::SEARCH; Action::ToggleViMode;
+        I,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollToBottom;
+        C,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ToggleViMode;
+        Y,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollLineUp;
+        E,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollLineDown;
+        G,                             +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollToTop;
+        G,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollToBottom;
+        B,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH; Action::ScrollPageUp;
+        F,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollPageDown;
+        U,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollHalfPageUp;
+        D,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ScrollHalfPageDown;
+        Y,                             +BindingMode::VI, ~BindingMode::SEARCH; Action::Copy;
+        Y,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ClearSelection;
+        V,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::ToggleNormalSelection;
+        V,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::ToggleLineSelection;
+        V,      ModifiersState::CTRL,  +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::ToggleBlockSelection;
+        V,      ModifiersState::ALT,   +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::ToggleSemanticSelection;
+        Z,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::CenterAroundViCursor;
+        Return,                        +BindingMode::VI, ~BindingMode::SEARCH; ViAction::Open;
+        K,                             +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Up;
+        J,                             +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Down;
+        H,                             +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Left;
+        L,                             +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Right;
+        Up,                            +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Up;
+        Down,                          +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Down;
+        Left,                          +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Left;
+        Right,                         +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Right;
+        Key0,                          +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::First;
+        Key4,   ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Last;
+        Key6,   ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::FirstOccupied;
+        H,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::High;
+        M,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Middle;
+        L,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Low;
+        B,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::SemanticLeft;
+        W,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::SemanticRight;
+        E,                             +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::SemanticRightEnd;
+        B,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordLeft;
+        W,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::WordRight;
+        E,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::WordRightEnd;
+        Key5,   ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH; ViMotion::Bracket;
+        LBracket, ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::ParagraphUp;
+        RBracket, ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViMotion::ParagraphDown;
+        Slash,                         +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::SearchForward;
+        Slash,  ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::SearchBackward;
+        N,                             +BindingMode::VI, ~BindingMode::SEARCH; ViAction::SearchNext;
+        N,      ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            ViAction::SearchPrevious;
+        Return, +BindingMode::SEARCH, +BindingMode::VI; SearchAction::SearchConfirm;
+        Escape,                        +BindingMode::SEARCH; SearchAction::SearchCancel;
+        C,      ModifiersState::CTRL,  +BindingMode::SEARCH; SearchAction::SearchCancel;
+        U,      ModifiersState::CTRL,  +BindingMode::SEARCH; SearchAction::SearchClear;
+        W,      ModifiersState::CTRL,  +BindingMode::SEARCH; SearchAction::SearchDeleteWord;
+        P,      ModifiersState::CTRL,  +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
+        N,      ModifiersState::CTRL,  +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        Up,                            +BindingMode::SEARCH; SearchAction::SearchHistoryPrevious;
+        Down,                          +BindingMode::SEARCH; SearchAction::SearchHistoryNext;
+        Return,                        +BindingMode::SEARCH, ~BindingMode::VI;
+            SearchAction::SearchFocusNext;
+        Return, ModifiersState::SHIFT, +BindingMode::SEARCH, ~BindingMode::VI;
+            SearchAction::SearchFocusPrevious;
     );
 
     //   Code     Modifiers
@@ -379,55 +599,55 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         let modifiers_code = index + 2;
         bindings.extend(bindings!(
             KeyBinding;
-            Delete, mods;
+            Delete, mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[3;{}~", modifiers_code));
-            Up,     mods;
+            Up,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}A", modifiers_code));
-            Down,   mods;
+            Down,   mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}B", modifiers_code));
-            Right,  mods;
+            Right,  mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}C", modifiers_code));
-            Left,   mods;
+            Left,   mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}D", modifiers_code));
-            F1,     mods;
+            F1,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}P", modifiers_code));
-            F2,     mods;
+            F2,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}Q", modifiers_code));
-            F3,     mods;
+            F3,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}R", modifiers_code));
-            F4,     mods;
+            F4,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[1;{}S", modifiers_code));
-            F5,     mods;
+            F5,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[15;{}~", modifiers_code));
-            F6,     mods;
+            F6,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[17;{}~", modifiers_code));
-            F7,     mods;
+            F7,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[18;{}~", modifiers_code));
-            F8,     mods;
+            F8,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[19;{}~", modifiers_code));
-            F9,     mods;
+            F9,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[20;{}~", modifiers_code));
-            F10,    mods;
+            F10,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[21;{}~", modifiers_code));
-            F11,    mods;
+            F11,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[23;{}~", modifiers_code));
-            F12,    mods;
+            F12,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[24;{}~", modifiers_code));
-            F13,    mods;
+            F13,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[25;{}~", modifiers_code));
-            F14,    mods;
+            F14,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[26;{}~", modifiers_code));
-            F15,    mods;
+            F15,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[28;{}~", modifiers_code));
-            F16,    mods;
+            F16,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[29;{}~", modifiers_code));
-            F17,    mods;
+            F17,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[31;{}~", modifiers_code));
-            F18,    mods;
+            F18,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[32;{}~", modifiers_code));
-            F19,    mods;
+            F19,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[33;{}~", modifiers_code));
-            F20,    mods;
+            F20,    mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                 Action::Esc(format!("\x1b[34;{}~", modifiers_code));
         ));
 
@@ -435,15 +655,15 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
         if modifiers_code != 2 {
             bindings.extend(bindings!(
                 KeyBinding;
-                Insert,   mods;
+                Insert,   mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                     Action::Esc(format!("\x1b[2;{}~", modifiers_code));
-                PageUp,   mods;
+                PageUp,   mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                     Action::Esc(format!("\x1b[5;{}~", modifiers_code));
-                PageDown, mods;
+                PageDown, mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                     Action::Esc(format!("\x1b[6;{}~", modifiers_code));
-                End,      mods;
+                End,      mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                     Action::Esc(format!("\x1b[1;{}F", modifiers_code));
-                Home,     mods;
+                Home,     mods, ~BindingMode::VI, ~BindingMode::SEARCH;
                     Action::Esc(format!("\x1b[1;{}H", modifiers_code));
             ));
         }
@@ -458,9 +678,14 @@ pub fn default_key_bindings() -> Vec<KeyBinding> {
 fn common_keybindings() -> Vec<KeyBinding> {
     bindings!(
         KeyBinding;
-        V,        ModifiersState::CTRL | ModifiersState::SHIFT; Action::Paste;
+        V,        ModifiersState::CTRL | ModifiersState::SHIFT, ~BindingMode::VI; Action::Paste;
         C,        ModifiersState::CTRL | ModifiersState::SHIFT; Action::Copy;
-        C,        ModifiersState::CTRL | ModifiersState::SHIFT; Action::ClearSelection;
+        F,        ModifiersState::CTRL | ModifiersState::SHIFT, ~BindingMode::SEARCH;
+            Action::SearchForward;
+        B,        ModifiersState::CTRL | ModifiersState::SHIFT, ~BindingMode::SEARCH;
+            Action::SearchBackward;
+        C,        ModifiersState::CTRL | ModifiersState::SHIFT, +BindingMode::VI, ~BindingMode::SEARCH;
+            Action::ClearSelection;
         Insert,   ModifiersState::SHIFT; Action::PasteSelection;
         Key0,     ModifiersState::CTRL;  Action::ResetFontSize;
         Equals,   ModifiersState::CTRL;  Action::IncreaseFontSize;
@@ -496,21 +721,23 @@ pub fn platform_key_bindings() -> Vec<KeyBinding> {
         NumpadAdd,      ModifiersState::LOGO; Action::IncreaseFontSize;
         Minus,          ModifiersState::LOGO; Action::DecreaseFontSize;
         NumpadSubtract, ModifiersState::LOGO; Action::DecreaseFontSize;
-        Insert, ModifiersState::SHIFT;
+        Insert, ModifiersState::SHIFT, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x1b[2;2~".into());
-        K, ModifiersState::LOGO;
+        K, ModifiersState::LOGO, ~BindingMode::VI, ~BindingMode::SEARCH;
             Action::Esc("\x0c".into());
-        K, ModifiersState::LOGO;  Action::ClearHistory;
-        V, ModifiersState::LOGO; Action::Paste;
+        K, ModifiersState::LOGO, ~BindingMode::VI, ~BindingMode::SEARCH; Action::ClearHistory;
+        V, ModifiersState::LOGO, ~BindingMode::VI; Action::Paste;
         N, ModifiersState::LOGO; Action::CreateNewWindow;
         F, ModifiersState::CTRL | ModifiersState::LOGO; Action::ToggleFullscreen;
         C, ModifiersState::LOGO; Action::Copy;
-        C, ModifiersState::LOGO; Action::ClearSelection;
+        C, ModifiersState::LOGO, +BindingMode::VI, ~BindingMode::SEARCH; Action::ClearSelection;
         H, ModifiersState::LOGO; Action::Hide;
         H, ModifiersState::LOGO | ModifiersState::ALT; Action::HideOtherApplications;
         M, ModifiersState::LOGO; Action::Minimize;
         Q, ModifiersState::LOGO; Action::Quit;
         W, ModifiersState::LOGO; Action::Quit;
+        F, ModifiersState::LOGO, ~BindingMode::SEARCH; Action::SearchForward;
+        B, ModifiersState::LOGO, ~BindingMode::SEARCH; Action::SearchBackward;
     )
 }
 
@@ -554,15 +781,21 @@ bitflags! {
         const APP_CURSOR          = 0b0000_0001;
         const APP_KEYPAD          = 0b0000_0010;
         const ALT_SCREEN          = 0b0000_0100;
+        const VI                  = 0b0000_1000;
+        const SEARCH              = 0b0001_0000;
+        const CHILD_EXITED        = 0b0010_0000;
     }
 }
 
 impl BindingMode {
-    pub fn new(mode: &TermMode) -> BindingMode {
+    pub fn new(mode: &TermMode, search: bool, child_exited: bool) -> BindingMode {
         let mut binding_mode = BindingMode::empty();
         binding_mode.set(BindingMode::APP_CURSOR, mode.contains(TermMode::APP_CURSOR));
         binding_mode.set(BindingMode::APP_KEYPAD, mode.contains(TermMode::APP_KEYPAD));
         binding_mode.set(BindingMode::ALT_SCREEN, mode.contains(TermMode::ALT_SCREEN));
+        binding_mode.set(BindingMode::VI, mode.contains(TermMode::VI));
+        binding_mode.set(BindingMode::SEARCH, search);
+        binding_mode.set(BindingMode::CHILD_EXITED, child_exited);
         binding_mode
     }
 }
@@ -585,7 +818,8 @@ impl<'a> Deserialize<'a> for ModeWrapper {
 
             fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
                 f.write_str(
-                    "a combination of AppCursor | AppKeypad | Alt | Vi, possibly with negation (~)",
+                    "a combination of AppCursor | AppKeypad | Alt | Vi | Search | ChildExited, \
+                     possibly with negation (~)",
                 )
             }
 
@@ -604,6 +838,12 @@ impl<'a> Deserialize<'a> for ModeWrapper {
                         "~appkeypad" => res.not_mode |= BindingMode::APP_KEYPAD,
                         "alt" => res.mode |= BindingMode::ALT_SCREEN,
                         "~alt" => res.not_mode |= BindingMode::ALT_SCREEN,
+                        "vi" => res.mode |= BindingMode::VI,
+                        "~vi" => res.not_mode |= BindingMode::VI,
+                        "search" => res.mode |= BindingMode::SEARCH,
+                        "~search" => res.not_mode |= BindingMode::SEARCH,
+                        "childexited" => res.mode |= BindingMode::CHILD_EXITED,
+                        "~childexited" => res.not_mode |= BindingMode::CHILD_EXITED,
                         _ => return Err(E::invalid_value(Unexpected::Str(modifier), &self)),
                     }
                 }
@@ -826,8 +1066,15 @@ impl<'a> Deserialize<'a> for RawBinding {
 
                             let value = map.next_value::<SerdeValue>()?;
 
-                            action = if let Ok(mouse_action) =
-                                MouseAction::deserialize(value.clone())
+                            action = if let Ok(vi_action) = ViAction::deserialize(value.clone()) {
+                                Some(vi_action.into())
+                            } else if let Ok(vi_motion) = ViMotion::deserialize(value.clone()) {
+                                Some(vi_motion.into())
+                            } else if let Ok(search_action) =
+                                SearchAction::deserialize(value.clone())
+                            {
+                                Some(search_action.into())
+                            } else if let Ok(mouse_action) = MouseAction::deserialize(value.clone())
                             {
                                 Some(mouse_action.into())
                             } else {
@@ -884,6 +1131,27 @@ impl<'a> Deserialize<'a> for RawBinding {
                 let mods = mods.unwrap_or_default();
 
                 let action = match (action, chars, command) {
+                    (Some(action @ Action::ViMotion(_)), None, None)
+                    | (Some(action @ Action::Vi(_)), None, None) => {
+                        if !mode.intersects(BindingMode::VI) || not_mode.intersects(BindingMode::VI)
+                        {
+                            return Err(V::Error::custom(format!(
+                                "action `{}` is only available in vi mode, try adding `mode: Vi`",
+                                action,
+                            )));
+                        }
+                        action
+                    },
+                    (Some(action @ Action::Search(_)), None, None) => {
+                        if !mode.intersects(BindingMode::SEARCH) {
+                            return Err(V::Error::custom(format!(
+                                "action `{}` is only available in search mode, try adding `mode: \
+                                 Search`",
+                                action,
+                            )));
+                        }
+                        action
+                    },
                     (Some(action @ Action::Mouse(_)), None, None) => {
                         if mouse.is_none() {
                             return Err(V::Error::custom(format!(
@@ -8,12 +8,75 @@ use alacritty_terminal::term::color::{CellRgb, Rgb};
 pub struct Colors {
     pub primary: PrimaryColors,
     pub cursor: InvertedCellColors,
+    pub vi_mode_cursor: InvertedCellColors,
     pub selection: InvertedCellColors,
     pub normal: NormalColors,
     pub bright: BrightColors,
     pub dim: Option<DimColors>,
     pub indexed_colors: Vec<IndexedColor>,
+    pub search: SearchColors,
+    pub line_indicator: LineIndicatorColors,
+    pub hints: HintColors,
     pub transparent_background_colors: bool,
+    pub footer_bar: BarColors,
+}
+
+impl Colors {
+    pub fn footer_bar_foreground(&self) -> Rgb {
+        self.footer_bar.foreground.unwrap_or(self.primary.background)
+    }
+
+    pub fn footer_bar_background(&self) -> Rgb {
+        self.footer_bar.background.unwrap_or(self.primary.foreground)
+    }
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct LineIndicatorColors {
+    pub foreground: Option<Rgb>,
+    pub background: Option<Rgb>,
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct HintColors {
+    pub start: HintStartColors,
+    pub end: HintEndColors,
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HintStartColors {
+    pub foreground: CellRgb,
+    pub background: CellRgb,
+}
+
+impl Default for HintStartColors {
+    fn default() -> Self {
+        Self {
+            foreground: CellRgb::Rgb(Rgb { r: 0x1d, g: 0x1f, b: 0x21 }),
+            background: CellRgb::Rgb(Rgb { r: 0xe9, g: 0xff, b: 0x5e }),
+        }
+    }
+}
+
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HintEndColors {
+    pub foreground: CellRgb,
+    pub background: CellRgb,
+}
+
+impl Default for HintEndColors {
+    fn default() -> Self {
+        Self {
+            foreground: CellRgb::Rgb(Rgb { r: 0xe9, g: 0xff, b: 0x5e }),
+            background: CellRgb::Rgb(Rgb { r: 0x1d, g: 0x1f, b: 0x21 }),
+        }
+    }
+}
+
+#[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BarColors {
+    foreground: Option<Rgb>,
+    background: Option<Rgb>,
 }
 
 #[derive(Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
@@ -64,6 +127,42 @@ impl Default for InvertedCellColors {
     }
 }
 
+#[derive(ConfigDeserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct SearchColors {
+    pub focused_match: FocusedMatchColors,
+    pub matches: MatchColors,
+}
+
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FocusedMatchColors {
+    pub foreground: CellRgb,
+    pub background: CellRgb,
+}
+
+impl Default for FocusedMatchColors {
+    fn default() -> Self {
+        Self {
+            background: CellRgb::Rgb(Rgb { r: 0x00, g: 0x00, b: 0x00 }),
+            foreground: CellRgb::Rgb(Rgb { r: 0xff, g: 0xff, b: 0xff }),
+        }
+    }
+}
+
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MatchColors {
+    pub foreground: CellRgb,
+    pub background: CellRgb,
+}
+
+impl Default for MatchColors {
+    fn default() -> Self {
+        Self {
+            background: CellRgb::Rgb(Rgb { r: 0xff, g: 0xff, b: 0xff }),
+            foreground: CellRgb::Rgb(Rgb { r: 0x00, g: 0x00, b: 0x00 }),
+        }
+    }
+}
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct PrimaryColors {
     pub foreground: Rgb,
@@ -9,10 +9,12 @@ use serde_yaml::Value;
 
 use alacritty_terminal::config::LOG_TARGET_CONFIG;
 
+pub mod bell;
 pub mod color;
 pub mod debug;
 pub mod font;
 pub mod monitor;
+pub mod notification;
 pub mod serde_utils;
 pub mod ui_config;
 pub mod window;
@@ -21,7 +23,9 @@ mod bindings;
 mod mouse;
 
 use crate::cli::Options;
-pub use crate::config::bindings::{Action, Binding, BindingMode, Key, MouseAction};
+pub use crate::config::bindings::{
+    Action, Binding, BindingMode, Key, MouseAction, SearchAction, ViAction,
+};
 #[cfg(test)]
 pub use crate::config::mouse::{ClickHandler, Mouse};
 pub use crate::config::ui_config::UiConfig;
@@ -137,6 +141,9 @@ pub fn reload(config_path: &Path, options: &Options) -> Result<UiConfig> {
 fn after_loading(config: &mut UiConfig, options: &Options) {
     // Override config with CLI options.
     options.override_config(config);
+
+    // Create key bindings for regex hints.
+    config.generate_hint_bindings();
 }
 
 /// Load configuration file and log errors.
@@ -0,0 +1,12 @@
+use alacritty_config_derive::ConfigDeserialize;
+
+use alacritty_terminal::config::Program;
+
+#[derive(ConfigDeserialize, Default, Clone, Debug, PartialEq, Eq)]
+pub struct NotificationConfig {
+    /// Command to run for desktop notifications.
+    pub command: Option<Program>,
+
+    /// Only show notifications while the window is unfocused.
+    pub only_when_unfocused: bool,
+}
@@ -1,18 +1,37 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::mem;
 use std::path::PathBuf;
+use std::rc::Rc;
 
-use log::error;
+use log::{error, warn};
+use serde::de::{Error as SerdeError, MapAccess, Visitor};
 use serde::{self, Deserialize, Deserializer};
+use unicode_width::UnicodeWidthChar;
+use winit::event::{ModifiersState, VirtualKeyCode};
 
 use alacritty_config_derive::{ConfigDeserialize, SerdeReplace};
-use alacritty_terminal::config::{Config as TerminalConfig, Percentage, LOG_TARGET_CONFIG};
-
-use crate::config::bindings::{self, Binding, KeyBinding, MouseBinding};
+use alacritty_terminal::config::{
+    Config as TerminalConfig, Percentage, Program, LOG_TARGET_CONFIG,
+};
+use alacritty_terminal::term::search::RegexSearch;
+
+use crate::config::bell::BellConfig;
+use crate::config::bindings::{
+    self, Action, Binding, Key, KeyBinding, ModeWrapper, ModsWrapper, MouseBinding,
+};
 use crate::config::color::Colors;
 use crate::config::debug::Debug;
 use crate::config::font::Font;
 use crate::config::mouse::Mouse;
+use crate::config::notification::NotificationConfig;
 use crate::config::window::WindowConfig;
 
+/// Regex used for the default URL hint.
+#[rustfmt::skip]
+const URL_REGEX: &str = "(ipfs:|ipns:|magnet:|mailto:|gemini:|gopher:|https:|http:|news:|file:|git:|ssh:|ftp:)\
+                         [^\u{0000}-\u{001F}\u{007F}-\u{009F}<>\"\\s{-}\\^⟨⟩`]+";
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq)]
 pub struct UiConfig {
     /// Font configuration.
@@ -26,6 +45,12 @@ pub struct UiConfig {
     /// Debug options.
     pub debug: Debug,
 
+    /// Bell configuration.
+    pub bell: BellConfig,
+
+    /// Desktop notification configuration.
+    pub notification: NotificationConfig,
+
     /// Send escape sequences using the alt key.
     #[config(removed = "It's now always set to 'true'. If you're on macOS use \
                         'window.option_as_alt' to alter behavior of Option")]
@@ -40,6 +65,9 @@ pub struct UiConfig {
     /// Should draw bold text with brighter colors instead of bold font.
     pub draw_bold_text_with_bright_colors: bool,
 
+    /// Regex hints for interacting with terminal content.
+    pub hints: Hints,
+
     /// Path where config was loaded from.
     #[config(skip)]
     pub config_paths: Vec<PathBuf>,
@@ -74,6 +102,8 @@ impl Default for UiConfig {
             window: Default::default(),
             mouse: Default::default(),
             debug: Default::default(),
+            bell: Default::default(),
+            notification: Default::default(),
             config_paths: Default::default(),
             key_bindings: Default::default(),
             mouse_bindings: Default::default(),
@@ -81,11 +111,32 @@ impl Default for UiConfig {
             background_opacity: Default::default(),
             colors: Default::default(),
             draw_bold_text_with_bright_colors: Default::default(),
+            hints: Default::default(),
         }
     }
 }
 
 impl UiConfig {
+    /// Generate key bindings for all keyboard hints.
+    pub fn generate_hint_bindings(&mut self) {
+        for hint in &self.hints.enabled {
+            let binding = match hint.binding {
+                Some(binding) => binding,
+                None => continue,
+            };
+
+            let binding = KeyBinding {
+                trigger: binding.key,
+                mods: binding.mods.0,
+                mode: binding.mode.mode,
+                notmode: binding.mode.not_mode,
+                action: Action::Hint(hint.clone()),
+            };
+
+            self.key_bindings.0.push(binding);
+        }
+    }
+
     #[inline]
     pub fn window_opacity(&self) -> f32 {
         self.background_opacity.unwrap_or(self.window.opacity).as_f32()
@@ -178,3 +229,306 @@ pub struct Delta<T: Default> {
     /// Vertical change.
     pub y: T,
 }
+
+#[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Hints {
+    /// Characters for the hint labels.
+    alphabet: HintsAlphabet,
+
+    /// All configured terminal hints.
+    pub enabled: Vec<Hint>,
+}
+
+impl Default for Hints {
+    fn default() -> Self {
+        // Add URL hint by default when no other hint is present.
+        let pattern = LazyRegexVariant::Pattern(String::from(URL_REGEX));
+        let regex = LazyRegex(Rc::new(RefCell::new(pattern)));
+        let content = HintContent::new(Some(regex), true);
+
+        #[cfg(not(any(target_os = "macos", windows)))]
+        let action = HintAction::Command(Program::Just(String::from("xdg-open")));
+        #[cfg(target_os = "macos")]
+        let action = HintAction::Command(Program::Just(String::from("open")));
+        #[cfg(windows)]
+        let action = HintAction::Command(Program::WithArgs {
+            program: String::from("cmd"),
+            args: vec!["/c".to_string(), "start".to_string(), "".to_string()],
+        });
+
+        Self {
+            enabled: vec![Hint {
+                content,
+                action,
+                post_processing: true,
+                mouse: Some(HintMouse { enabled: true, mods: Default::default() }),
+                binding: Some(HintBinding {
+                    key: Key::Keycode(VirtualKeyCode::U),
+                    mods: ModsWrapper(ModifiersState::SHIFT | ModifiersState::CTRL),
+                    mode: Default::default(),
+                }),
+            }],
+            alphabet: Default::default(),
+        }
+    }
+}
+
+impl Hints {
+    /// Characters for the hint labels.
+    pub fn alphabet(&self) -> &str {
+        &self.alphabet.0
+    }
+}
+
+#[derive(SerdeReplace, Clone, Debug, PartialEq, Eq)]
+struct HintsAlphabet(String);
+
+impl Default for HintsAlphabet {
+    fn default() -> Self {
+        Self(String::from("jfkdls;ahgurieowpq"))
+    }
+}
+
+impl<'de> Deserialize<'de> for HintsAlphabet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+
+        let mut character_count = 0;
+        for character in value.chars() {
+            if character.width() != Some(1) {
+                return Err(D::Error::custom("characters must be of width 1"));
+            }
+            character_count += 1;
+        }
+
+        if character_count < 2 {
+            return Err(D::Error::custom("must include at last 2 characters"));
+        }
+
+        Ok(Self(value))
+    }
+}
+
+/// Built-in actions for hint mode.
+#[derive(ConfigDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintInternalAction {
+    /// Copy the text to the clipboard.
+    Copy,
+    /// Write the text to the PTY/search.
+    Paste,
+    /// Select the text matching the hint.
+    Select,
+    /// Move the vi mode cursor to the beginning of the hint.
+    MoveViModeCursor,
+}
+
+/// Actions for hint bindings.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum HintAction {
+    /// Built-in hint action.
+    #[serde(rename = "action")]
+    Action(HintInternalAction),
+
+    /// Command the text will be piped to.
+    #[serde(rename = "command")]
+    Command(Program),
+}
+
+/// Hint configuration.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// Regex for finding matches.
+    #[serde(flatten)]
+    pub content: HintContent,
+
+    /// Action executed when this hint is triggered.
+    #[serde(flatten)]
+    pub action: HintAction,
+
+    /// Hint text post processing.
+    #[serde(default)]
+    pub post_processing: bool,
+
+    /// Hint mouse highlighting.
+    pub mouse: Option<HintMouse>,
+
+    /// Binding required to search for this hint.
+    binding: Option<HintBinding>,
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct HintContent {
+    /// Regex for finding matches.
+    pub regex: Option<LazyRegex>,
+
+    /// Escape sequence hyperlinks.
+    pub hyperlinks: bool,
+}
+
+impl HintContent {
+    pub fn new(regex: Option<LazyRegex>, hyperlinks: bool) -> Self {
+        Self { regex, hyperlinks }
+    }
+}
+
+impl<'de> Deserialize<'de> for HintContent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct HintContentVisitor;
+        impl<'a> Visitor<'a> for HintContentVisitor {
+            type Value = HintContent;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a mapping")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'a>,
+            {
+                let mut content = Self::Value::default();
+
+                while let Some((key, value)) = map.next_entry::<String, serde_yaml::Value>()? {
+                    match key.as_str() {
+                        "regex" => match Option::<LazyRegex>::deserialize(value) {
+                            Ok(regex) => content.regex = regex,
+                            Err(err) => {
+                                error!(
+                                    target: LOG_TARGET_CONFIG,
+                                    "Config error: hint's regex: {}", err
+                                );
+                            },
+                        },
+                        "hyperlinks" => match bool::deserialize(value) {
+                            Ok(hyperlink) => content.hyperlinks = hyperlink,
+                            Err(err) => {
+                                error!(
+                                    target: LOG_TARGET_CONFIG,
+                                    "Config error: hint's hyperlinks: {}", err
+                                );
+                            },
+                        },
+                        "command" | "action" => (),
+                        key => warn!(target: LOG_TARGET_CONFIG, "Unrecognized hint field: {}", key),
+                    }
+                }
+
+                // Require at least one of hyperlinks or regex trigger hint matches.
+                if content.regex.is_none() && !content.hyperlinks {
+                    return Err(M::Error::custom(
+                        "Config error: At least one of the hint's regex or hint's hyperlinks must \
+                         be set",
+                    ));
+                }
+
+                Ok(content)
+            }
+        }
+
+        deserializer.deserialize_any(HintContentVisitor)
+    }
+}
+
+/// Binding for triggering a keyboard hint.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HintBinding {
+    pub key: Key,
+    #[serde(default)]
+    pub mods: ModsWrapper,
+    #[serde(default)]
+    pub mode: ModeWrapper,
+}
+
+/// Hint mouse highlighting.
+#[derive(ConfigDeserialize, Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HintMouse {
+    /// Hint mouse highlighting availability.
+    pub enabled: bool,
+
+    /// Required mouse modifiers for hint highlighting.
+    pub mods: ModsWrapper,
+}
+
+/// Lazy regex with interior mutability.
+#[derive(Clone, Debug)]
+pub struct LazyRegex(Rc<RefCell<LazyRegexVariant>>);
+
+impl LazyRegex {
+    /// Execute a function with the compiled regex DFAs as parameter.
+    pub fn with_compiled<T, F>(&self, f: F) -> Option<T>
+    where
+        F: FnMut(&RegexSearch) -> T,
+    {
+        self.0.borrow_mut().compiled().map(f)
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyRegex {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let regex = LazyRegexVariant::Pattern(String::deserialize(deserializer)?);
+        Ok(Self(Rc::new(RefCell::new(regex))))
+    }
+}
+
+/// Regexes are compared by their source pattern.
+impl PartialEq for LazyRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.borrow().pattern() == other.0.borrow().pattern()
+    }
+}
+impl Eq for LazyRegex {}
+
+/// Regex which is compiled on demand, to avoid expensive computations at startup.
+#[derive(Clone, Debug)]
+pub enum LazyRegexVariant {
+    Compiled(String, Box<RegexSearch>),
+    Pattern(String),
+    Uncompilable(String),
+}
+
+impl LazyRegexVariant {
+    /// Get a reference to the compiled regex.
+    ///
+    /// If the regex is not already compiled, this will compile the DFAs and store them for future
+    /// access.
+    fn compiled(&mut self) -> Option<&RegexSearch> {
+        // Check if the regex has already been compiled.
+        let regex = match self {
+            Self::Compiled(_, regex_search) => return Some(regex_search),
+            Self::Uncompilable(_) => return None,
+            Self::Pattern(regex) => regex,
+        };
+
+        // Compile the regex.
+        let regex_search = match RegexSearch::new(regex) {
+            Ok(regex_search) => regex_search,
+            Err(err) => {
+                error!("Hint regex is invalid: {}", err);
+                *self = Self::Uncompilable(mem::take(regex));
+                return None;
+            },
+        };
+        *self = Self::Compiled(mem::take(regex), Box::new(regex_search));
+
+        // Return a reference to the compiled DFAs.
+        match self {
+            Self::Compiled(_, dfas) => Some(dfas),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the regex's source pattern.
+    fn pattern(&self) -> &str {
+        match self {
+            Self::Compiled(regex, _) | Self::Pattern(regex) | Self::Uncompilable(regex) => regex,
+        }
+    }
+}
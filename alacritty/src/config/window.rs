@@ -56,6 +56,12 @@ pub struct WindowConfig {
     /// Resize increments.
     pub resize_increments: bool,
 
+    /// Window operations which may be requested through escape sequences.
+    pub allowed_operations: Vec<WindowOperationPermission>,
+
+    /// Show an indicator status line unless the application writes its own.
+    pub indicator_status_line: bool,
+
     /// Pixel padding.
     padding: Delta<u8>,
 
@@ -78,6 +84,8 @@ impl Default for WindowConfig {
             padding: Default::default(),
             dimensions: Default::default(),
             resize_increments: Default::default(),
+            allowed_operations: Default::default(),
+            indicator_status_line: Default::default(),
             #[cfg(target_os = "macos")]
             option_as_alt: Default::default(),
         }
@@ -185,6 +193,23 @@ impl Default for Decorations {
     }
 }
 
+/// Window operation which may be requested through XTWINOPS escape sequences.
+#[derive(ConfigDeserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WindowOperationPermission {
+    /// Minimizing and restoring the window.
+    Iconify,
+    /// Raising and lowering the window.
+    Raise,
+    /// Maximizing and restoring the window.
+    Maximize,
+    /// Entering and leaving fullscreen mode.
+    Fullscreen,
+    /// Resizing the window.
+    Resize,
+    /// Reporting the window title.
+    ReportTitle,
+}
+
 /// Window Dimensions.
 ///
 /// Newtype to avoid passing values incorrectly.
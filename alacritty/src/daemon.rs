@@ -4,6 +4,7 @@ use std::fs;
 use std::io;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 #[rustfmt::skip]
@@ -25,7 +26,11 @@ use crate::macos;
 
 /// Start a new process in the background.
 #[cfg(windows)]
-pub fn spawn_daemon<I, S>(program: &str, args: I) -> io::Result<()>
+pub fn spawn_daemon<I, S>(
+    program: &str,
+    args: I,
+    working_directory: Option<&Path>,
+) -> io::Result<()>
 where
     I: IntoIterator<Item = S> + Copy,
     S: AsRef<OsStr>,
@@ -34,14 +39,12 @@ where
     // CREATE_NEW_PROCESS_GROUP and CREATE_NO_WINDOW has the effect
     // that console applications will run without opening a new
     // console window.
-    Command::new(program)
-        .args(args)
-        .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW)
-        .spawn()
-        .map(|_| ())
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+    if let Some(working_directory) = working_directory {
+        command.current_dir(working_directory);
+    }
+    command.creation_flags(CREATE_NEW_PROCESS_GROUP | CREATE_NO_WINDOW).spawn().map(|_| ())
 }
 
 /// Start a new process in the background.
@@ -49,8 +52,7 @@ where
 pub fn spawn_daemon<I, S>(
     program: &str,
     args: I,
-    master_fd: RawFd,
-    shell_pid: u32,
+    working_directory: Option<&Path>,
 ) -> io::Result<()>
 where
     I: IntoIterator<Item = S> + Copy,
@@ -58,8 +60,8 @@ where
 {
     let mut command = Command::new(program);
     command.args(args).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
-    if let Ok(cwd) = foreground_process_path(master_fd, shell_pid) {
-        command.current_dir(cwd);
+    if let Some(working_directory) = working_directory {
+        command.current_dir(working_directory);
     }
     unsafe {
         command
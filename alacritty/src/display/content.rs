@@ -1,17 +1,23 @@
-use std::mem;
+use std::borrow::Cow;
+use std::ops::Deref;
+use std::{cmp, mem};
 
 use alacritty_terminal::ansi::{Color, CursorShape, NamedColor};
 use alacritty_terminal::event::EventListener;
-use alacritty_terminal::grid::Indexed;
-use alacritty_terminal::index::Point;
+use alacritty_terminal::graphics::GraphicCell;
+use alacritty_terminal::grid::{Dimensions, Grid, Indexed, LineAttr};
+use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::selection::SelectionRange;
 use alacritty_terminal::term::cell::{Cell, Flags, Hyperlink};
 use alacritty_terminal::term::color::{CellRgb, Rgb};
-use alacritty_terminal::term::{self, RenderableContent as TerminalContent, Term};
+use alacritty_terminal::term::search::{Match, RegexSearch};
+use alacritty_terminal::term::{self, RenderableContent as TerminalContent, Term, TermMode};
 
 use crate::config::UiConfig;
 use crate::display::color::{List, DIM_FACTOR};
+use crate::display::hint::{self, HintState};
 use crate::display::Display;
+use crate::event::SearchState;
 
 /// Minimum contrast between a fixed cursor color and the cell's background.
 pub const MIN_CURSOR_CONTRAST: f64 = 1.5;
@@ -24,8 +30,13 @@ pub struct RenderableContent<'a> {
     cursor: RenderableCursor,
     cursor_shape: CursorShape,
     cursor_point: Point<usize>,
+    search: Option<HintMatches<'a>>,
+    hint: Option<Hint<'a>>,
     config: &'a UiConfig,
     colors: &'a List,
+    focused_match: Option<&'a Match>,
+    grid: &'a Grid<Cell>,
+    line_attr: Option<(Line, LineAttr)>,
 }
 
 impl<'a> RenderableContent<'a> {
@@ -33,7 +44,10 @@ impl<'a> RenderableContent<'a> {
         config: &'a UiConfig,
         display: &'a mut Display,
         term: &'a Term<T>,
+        search_state: &'a SearchState,
     ) -> Self {
+        let search = search_state.dfas().map(|dfas| HintMatches::visible_regex_matches(term, dfas));
+        let focused_match = search_state.focused_match();
         let terminal_content = term.renderable_content();
 
         // Find terminal cursor shape.
@@ -51,7 +65,19 @@ impl<'a> RenderableContent<'a> {
         // Convert terminal cursor point to viewport position.
         let cursor_point = terminal_content.cursor.point;
         let display_offset = terminal_content.display_offset;
-        let cursor_point = term::point_to_viewport(display_offset, cursor_point).unwrap();
+        let mut cursor_point = term::point_to_viewport(display_offset, cursor_point).unwrap();
+
+        // Cells of double-width lines are drawn at twice their column.
+        if term.grid().row_attrs(terminal_content.cursor.point.line).line_attr != LineAttr::Single {
+            cursor_point.column.0 *= 2;
+        }
+
+        let hint = if display.hint_state.active() {
+            display.hint_state.update_matches(term);
+            Some(Hint::from(&display.hint_state))
+        } else {
+            None
+        };
 
         Self {
             colors: &display.colors,
@@ -59,7 +85,12 @@ impl<'a> RenderableContent<'a> {
             terminal_content,
             cursor_shape,
             cursor_point,
+            search,
+            hint,
             config,
+            focused_match,
+            grid: term.grid(),
+            line_attr: None,
         }
     }
 
@@ -78,17 +109,66 @@ impl<'a> RenderableContent<'a> {
 
     /// Get the RGB value for a color index.
     pub fn color(&self, color: usize) -> Rgb {
+        // Reverse video inverts the colors by swapping the default foreground and background.
+        let color = if self.terminal_content.mode.contains(TermMode::REVERSE_VIDEO) {
+            match color {
+                color if color == NamedColor::Foreground as usize => {
+                    NamedColor::Background as usize
+                },
+                color if color == NamedColor::Background as usize => {
+                    NamedColor::Foreground as usize
+                },
+                color => color,
+            }
+        } else {
+            color
+        };
+
         self.terminal_content.colors[color].unwrap_or(self.colors[color])
     }
 
+    /// Character size of a line.
+    ///
+    /// Since cells are rendered line by line, the size of the last line is cached.
+    fn line_attr(&mut self, line: Line) -> LineAttr {
+        match self.line_attr {
+            Some((cached_line, line_attr)) if cached_line == line => line_attr,
+            _ => {
+                let line_attr = self.grid.row_attrs(line).line_attr;
+                self.line_attr = Some((line, line_attr));
+                line_attr
+            },
+        }
+    }
+
     pub fn selection_range(&self) -> Option<SelectionRange> {
         self.terminal_content.selection
     }
 
+    /// Get the renderable cells of the host-writable status line, drawn at `line`.
+    pub fn status_line_cells(
+        &mut self,
+        status_line: &Grid<Cell>,
+        line: usize,
+    ) -> Vec<RenderableCell> {
+        status_line
+            .display_iter()
+            .map(|cell| {
+                let point = Point::new(line, cell.point.column);
+                RenderableCell::new_status_line(self, cell.cell, point)
+            })
+            .filter(|cell| !cell.is_empty() && !cell.flags.contains(Flags::WIDE_CHAR_SPACER))
+            .collect()
+    }
+
     /// Assemble the information required to render the terminal cursor.
     fn renderable_cursor(&mut self, cell: &RenderableCell) -> RenderableCursor {
         // Cursor colors.
-        let color = self.config.colors.cursor;
+        let color = if self.terminal_content.mode.contains(TermMode::VI) {
+            self.config.colors.vi_mode_cursor
+        } else {
+            self.config.colors.cursor
+        };
         let cursor_color =
             self.terminal_content.colors[NamedColor::Cursor].map_or(color.background, CellRgb::Rgb);
         let text_color = color.foreground;
@@ -108,7 +188,7 @@ impl<'a> RenderableContent<'a> {
         }
 
         RenderableCursor {
-            is_wide: cell.flags.contains(Flags::WIDE_CHAR),
+            is_wide: cell.columns() > 1,
             shape: self.cursor_shape,
             point: self.cursor_point,
             cursor_color,
@@ -130,6 +210,11 @@ impl<'a> Iterator for RenderableContent<'a> {
             let cell = self.terminal_content.display_iter.next()?;
             let mut cell = RenderableCell::new(self, cell);
 
+            // Skip cells pushed out of the viewport by double-width lines.
+            if cell.point.column.0 >= self.grid.columns() {
+                continue;
+            }
+
             if self.cursor_point == cell.point {
                 // Store the cursor which should be rendered.
                 self.cursor = self.renderable_cursor(&cell);
@@ -161,6 +246,7 @@ pub struct RenderableCell {
     pub bg_alpha: f32,
     pub underline: Rgb,
     pub flags: Flags,
+    pub line_attr: LineAttr,
     pub extra: Option<Box<RenderableCellExtra>>,
 }
 
@@ -170,20 +256,12 @@ pub struct RenderableCell {
 pub struct RenderableCellExtra {
     pub zerowidth: Option<Vec<char>>,
     pub hyperlink: Option<Hyperlink>,
+    pub graphic: Option<GraphicCell>,
 }
 
 impl RenderableCell {
     fn new(content: &mut RenderableContent<'_>, cell: Indexed<&Cell>) -> Self {
-        // Lookup RGB values.
-        let mut fg = Self::compute_fg_rgb(content, cell.fg, cell.flags);
-        let mut bg = Self::compute_bg_rgb(content, cell.bg);
-
-        let mut bg_alpha = if cell.flags.contains(Flags::INVERSE) {
-            mem::swap(&mut fg, &mut bg);
-            1.0
-        } else {
-            Self::compute_bg_alpha(content.config, cell.bg)
-        };
+        let (mut fg, mut bg, mut bg_alpha) = Self::compute_colors(content, &cell);
 
         let is_selected = content.terminal_content.selection.map_or(false, |selection| {
             selection.contains_cell(
@@ -194,10 +272,22 @@ impl RenderableCell {
         });
 
         let display_offset = content.terminal_content.display_offset;
+        let viewport_start = Point::new(Line(-(display_offset as i32)), Column(0));
         let colors = &content.config.colors;
-        let character = cell.c;
+        let mut character = cell.c;
 
-        if is_selected {
+        if let Some((c, is_first)) =
+            content.hint.as_mut().and_then(|hint| hint.advance(viewport_start, cell.point))
+        {
+            let (config_fg, config_bg) = if is_first {
+                (colors.hints.start.foreground, colors.hints.start.background)
+            } else {
+                (colors.hints.end.foreground, colors.hints.end.background)
+            };
+            Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
+
+            character = c;
+        } else if is_selected {
             let config_fg = colors.selection.foreground;
             let config_bg = colors.selection.background;
             Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
@@ -208,11 +298,25 @@ impl RenderableCell {
                 bg = content.color(NamedColor::Foreground as usize);
                 bg_alpha = 1.0;
             }
+        } else if content.search.as_mut().map_or(false, |search| search.advance(cell.point)) {
+            let focused = content.focused_match.map_or(false, |fm| fm.contains(&cell.point));
+            let (config_fg, config_bg) = if focused {
+                (colors.search.focused_match.foreground, colors.search.focused_match.background)
+            } else {
+                (colors.search.matches.foreground, colors.search.matches.background)
+            };
+            Self::compute_cell_rgb(&mut fg, &mut bg, &mut bg_alpha, config_fg, config_bg);
         }
 
         // Convert cell point to viewport position.
         let cell_point = cell.point;
-        let point = term::point_to_viewport(display_offset, cell_point).unwrap();
+        let mut point = term::point_to_viewport(display_offset, cell_point).unwrap();
+
+        // Cells of double-width lines are drawn at twice their column.
+        let line_attr = content.line_attr(cell_point.line);
+        if line_attr != LineAttr::Single {
+            point.column.0 *= 2;
+        }
 
         let flags = cell.flags;
         let underline = cell
@@ -221,15 +325,59 @@ impl RenderableCell {
 
         let zerowidth = cell.zerowidth();
         let hyperlink = cell.hyperlink();
+        let graphic = cell.graphic();
 
-        let extra = (zerowidth.is_some() || hyperlink.is_some()).then(|| {
+        let extra = (zerowidth.is_some() || hyperlink.is_some() || graphic.is_some()).then(|| {
             Box::new(RenderableCellExtra {
                 zerowidth: zerowidth.map(|zerowidth| zerowidth.to_vec()),
                 hyperlink,
+                graphic: graphic.cloned(),
             })
         });
 
-        RenderableCell { flags, character, bg_alpha, point, fg, bg, underline, extra }
+        RenderableCell { flags, character, bg_alpha, point, fg, bg, underline, line_attr, extra }
+    }
+
+    /// Create a cell of the status line, which is not part of the terminal grid.
+    fn new_status_line(
+        content: &mut RenderableContent<'_>,
+        cell: &Cell,
+        point: Point<usize>,
+    ) -> Self {
+        let (fg, bg, bg_alpha) = Self::compute_colors(content, cell);
+
+        let underline = cell
+            .underline_color()
+            .map_or(fg, |underline| Self::compute_fg_rgb(content, underline, cell.flags));
+
+        let extra = cell.zerowidth().map(|zerowidth| {
+            Box::new(RenderableCellExtra {
+                zerowidth: Some(zerowidth.to_vec()),
+                hyperlink: None,
+                graphic: None,
+            })
+        });
+
+        RenderableCell {
+            flags: cell.flags,
+            character: cell.c,
+            bg_alpha,
+            point,
+            fg,
+            bg,
+            underline,
+            line_attr: LineAttr::Single,
+            extra,
+        }
+    }
+
+    /// Number of viewport columns covered by the cell.
+    pub fn columns(&self) -> usize {
+        let columns = if self.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+        match self.line_attr {
+            LineAttr::Single => columns,
+            _ => columns * 2,
+        }
     }
 
     /// Check if cell contains any renderable content.
@@ -240,6 +388,22 @@ impl RenderableCell {
             && !self.flags.intersects(Flags::ALL_UNDERLINES | Flags::STRIKEOUT)
     }
 
+    /// Get the foreground, background and background alpha of a cell.
+    fn compute_colors(content: &mut RenderableContent<'_>, cell: &Cell) -> (Rgb, Rgb, f32) {
+        // Lookup RGB values.
+        let mut fg = Self::compute_fg_rgb(content, cell.fg, cell.flags);
+        let mut bg = Self::compute_bg_rgb(content, cell.bg);
+
+        let bg_alpha = if cell.flags.contains(Flags::INVERSE) {
+            mem::swap(&mut fg, &mut bg);
+            1.0
+        } else {
+            Self::compute_bg_alpha(content.config, cell.bg)
+        };
+
+        (fg, bg, bg_alpha)
+    }
+
     /// Apply [`CellRgb`] colors to the cell's colors.
     fn compute_cell_rgb(
         cell_fg: &mut Rgb,
@@ -369,3 +533,95 @@ impl RenderableCursor {
         self.point
     }
 }
+
+/// Regex hints for keyboard shortcuts.
+struct Hint<'a> {
+    /// Hint matches and position.
+    matches: HintMatches<'a>,
+
+    /// Last match checked against current cell position.
+    labels: &'a Vec<Vec<char>>,
+}
+
+impl<'a> Hint<'a> {
+    /// Advance the hint iterator.
+    ///
+    /// If the point is within a hint, the keyboard shortcut character that should be displayed at
+    /// this position will be returned.
+    ///
+    /// The tuple's [`bool`] will be `true` when the character is the first for this hint.
+    fn advance(&mut self, viewport_start: Point, point: Point) -> Option<(char, bool)> {
+        // Check if we're within a match at all.
+        if !self.matches.advance(point) {
+            return None;
+        }
+
+        // Match starting position on this line; linebreaks interrupt the hint labels.
+        let start = self
+            .matches
+            .get(self.matches.index)
+            .map(|bounds| cmp::max(*bounds.start(), viewport_start))
+            .filter(|start| start.line == point.line)?;
+
+        // Position within the hint label.
+        let label_position = point.column.0 - start.column.0;
+        let is_first = label_position == 0;
+
+        // Hint label character.
+        self.labels[self.matches.index].get(label_position).copied().map(|c| (c, is_first))
+    }
+}
+
+impl<'a> From<&'a HintState> for Hint<'a> {
+    fn from(hint_state: &'a HintState) -> Self {
+        let matches = HintMatches::new(hint_state.matches());
+        Self { labels: hint_state.labels(), matches }
+    }
+}
+
+/// Visible hint match tracking.
+#[derive(Default)]
+struct HintMatches<'a> {
+    /// All visible matches.
+    matches: Cow<'a, [Match]>,
+
+    /// Index of the last match checked.
+    index: usize,
+}
+
+impl<'a> HintMatches<'a> {
+    /// Create new renderable matches iterator.
+    fn new(matches: impl Into<Cow<'a, [Match]>>) -> Self {
+        Self { matches: matches.into(), index: 0 }
+    }
+
+    /// Create from regex matches in the visible part of the terminal.
+    fn visible_regex_matches<T>(term: &Term<T>, dfas: &RegexSearch) -> Self {
+        let matches = hint::visible_regex_match_iter(term, dfas).collect::<Vec<_>>();
+        Self::new(matches)
+    }
+
+    /// Advance the regex tracker to the next point.
+    ///
+    /// This will return `true` if the point passed is part of a regex match.
+    fn advance(&mut self, point: Point) -> bool {
+        while let Some(bounds) = self.get(self.index) {
+            if bounds.start() > &point {
+                break;
+            } else if bounds.end() < &point {
+                self.index += 1;
+            } else {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<'a> Deref for HintMatches<'a> {
+    type Target = [Match];
+
+    fn deref(&self) -> &Self::Target {
+        self.matches.deref()
+    }
+}
@@ -17,32 +17,38 @@ use log::{debug, info};
 use parking_lot::MutexGuard;
 use serde::{Deserialize, Serialize};
 use winit::dpi::PhysicalSize;
+use winit::event::ModifiersState;
+use winit::window::CursorIcon;
 
 use crossfont::{self, Rasterize, Rasterizer};
 use unicode_width::UnicodeWidthChar;
 
-use alacritty_terminal::ansi::{CursorShape, NamedColor};
+use alacritty_terminal::ansi::{CursorShape, NamedColor, StatusLineType};
 use alacritty_terminal::event::{EventListener, OnResize, WindowSize};
-use alacritty_terminal::grid::Dimensions as TermDimensions;
-use alacritty_terminal::index::{Column, Point};
-use alacritty_terminal::selection::SelectionRange;
+use alacritty_terminal::grid::{Dimensions as TermDimensions, LineAttr};
+use alacritty_terminal::index::{Column, Direction, Line, Point};
+use alacritty_terminal::selection::{Selection, SelectionRange};
 use alacritty_terminal::term::cell::Flags;
 use alacritty_terminal::term::color::Rgb;
-use alacritty_terminal::term::{self, Term, TermDamage, MIN_COLUMNS, MIN_SCREEN_LINES};
+use alacritty_terminal::term::search::Match;
+use alacritty_terminal::term::{self, Term, TermDamage, TermMode, MIN_COLUMNS, MIN_SCREEN_LINES};
 
 use crate::config::font::Font;
 use crate::config::window::Dimensions;
 #[cfg(not(windows))]
 use crate::config::window::StartupMode;
 use crate::config::UiConfig;
+use crate::display::bell::VisualBell;
 use crate::display::color::List;
 use crate::display::content::{RenderableContent, RenderableCursor};
 use crate::display::cursor::IntoRects;
 use crate::display::damage::RenderDamageIterator;
+use crate::display::hint::{HintMatch, HintState};
 use crate::display::meter::Meter;
 use crate::display::window::Window;
-use crate::event::{Event, EventType};
+use crate::event::{Event, EventType, Mouse, SearchState};
 use crate::message_bar::{MessageBuffer, MessageType};
+use crate::renderer::graphics::RenderGraphic;
 use crate::renderer::rects::{RenderLine, RenderLines, RenderRect};
 use crate::renderer::{self, GlyphCache, Renderer};
 use crate::scheduler::{Scheduler, TimerId, Topic};
@@ -50,12 +56,20 @@ use crate::string::{ShortenDirection, StrShortener};
 
 pub mod content;
 pub mod cursor;
+pub mod hint;
 pub mod window;
 
+mod bell;
 mod color;
 mod damage;
 mod meter;
 
+/// Label for the forward terminal search bar.
+const FORWARD_SEARCH_LABEL: &str = "Search: ";
+
+/// Label for the backward terminal search bar.
+const BACKWARD_SEARCH_LABEL: &str = "Backward Search: ";
+
 /// The character used to shorten the visible text like uri preview or search regex.
 const SHORTENER: char = '…';
 
@@ -335,15 +349,33 @@ pub struct Display {
     /// Mapped RGB values for each terminal color.
     pub colors: List,
 
+    /// Hint highlighted by the mouse.
+    pub highlighted_hint: Option<HintMatch>,
+
+    /// Hint highlighted by the vi mode cursor.
+    pub vi_highlighted_hint: Option<HintMatch>,
+
+    /// Mouse point position when highlighting hints.
+    hint_mouse_point: Option<Point>,
+
+    /// State of the visual bell.
+    pub visual_bell: VisualBell,
+
     /// Unprocessed display updates.
     pub pending_update: DisplayUpdate,
 
     /// The renderer update that takes place only once before the actual rendering.
     pub pending_renderer_update: Option<RendererUpdate>,
 
+    /// The state of the keyboard hint selection.
+    pub hint_state: HintState,
+
     /// The ime on the given display.
     pub ime: Ime,
 
+    /// Whether a line below the terminal is reserved for the status line.
+    pub status_line: bool,
+
     /// The state of the timer for frame scheduling.
     pub frame_timer: FrameTimer,
 
@@ -472,6 +504,8 @@ impl Display {
             info!("Failed to disable vsync: {}", err);
         }
 
+        let hint_state = HintState::new(config.hints.alphabet());
+
         Ok(Self {
             window,
             context: ManuallyDrop::new(Replaceable::new(context)),
@@ -481,10 +515,16 @@ impl Display {
             meter: Meter::new(),
             size_info,
             ime: Ime::new(),
+            status_line: false,
             is_wayland,
             cursor_hidden: false,
             frame_timer: FrameTimer::new(),
             colors: List::from(&config.colors),
+            visual_bell: VisualBell::from(&config.bell),
+            highlighted_hint: Default::default(),
+            vi_highlighted_hint: Default::default(),
+            hint_mouse_point: Default::default(),
+            hint_state,
             pending_update: Default::default(),
             pending_renderer_update: Default::default(),
             debug_damage,
@@ -563,6 +603,7 @@ impl Display {
         terminal: &mut Term<T>,
         pty_resize_handle: &mut dyn OnResize,
         message_buffer: &MessageBuffer,
+        search_state: &SearchState,
         config: &UiConfig,
     ) where
         T: EventListener,
@@ -607,8 +648,13 @@ impl Display {
         );
 
         // Update number of column/lines in the viewport.
+        let search_active = search_state.history_index.is_some();
         let message_bar_lines = message_buffer.message().map_or(0, |m| m.text(&new_size).len());
-        new_size.reserve_lines(message_bar_lines);
+        let search_lines = usize::from(search_active);
+        self.status_line = config.window.indicator_status_line
+            || terminal.status_line_type() != StatusLineType::None;
+        let status_lines = usize::from(self.status_line);
+        new_size.reserve_lines(message_bar_lines + search_lines + status_lines);
 
         // Update resize increments.
         if config.window.resize_increments {
@@ -620,6 +666,7 @@ impl Display {
 
         // Resize terminal.
         terminal.resize(new_size);
+        terminal.set_cell_size(cell_width as usize, cell_height as usize);
 
         // Queue renderer update if terminal dimensions/padding changed.
         if new_size != self.size_info {
@@ -684,16 +731,54 @@ impl Display {
         &mut self,
         terminal: &mut MutexGuard<'_, Term<T>>,
         selection_range: Option<SelectionRange>,
+        search_state: &SearchState,
     ) {
+        // Double-size lines are drawn across more columns than their damage covers.
+        let display_offset = terminal.grid().display_offset() as i32;
+        let double_size_lines: Vec<usize> = (0..terminal.screen_lines())
+            .filter(|line| {
+                let line = Line(*line as i32 - display_offset);
+                terminal.grid().row_attrs(line).line_attr != LineAttr::Single
+            })
+            .collect();
+
+        // Ensure the entire window is redrawn while the visual bell is animating or search and hint
+        // highlights are visible.
+        let requires_full_damage = self.visual_bell.intensity() != 0.
+            || self.hint_state.active()
+            || search_state.regex().is_some();
+        if requires_full_damage {
+            terminal.mark_fully_damaged();
+        }
+
         match terminal.damage(selection_range) {
             TermDamage::Full => self.fully_damage(),
             TermDamage::Partial(damaged_lines) => {
+                // Damage the entire row of damaged double-size lines.
+                let columns = self.size_info.columns() as u32;
+                let double_size_damage: Vec<_> = damaged_lines
+                    .clone()
+                    .filter(|damage| double_size_lines.contains(&damage.line))
+                    .map(|damage| {
+                        self.damage_from_point(Point::new(damage.line, Column(0)), columns)
+                    })
+                    .collect();
+
                 let damaged_rects = RenderDamageIterator::new(damaged_lines, self.size_info.into());
-                for damaged_rect in damaged_rects {
+                for damaged_rect in damaged_rects.chain(double_size_damage) {
                     self.damage_rects.push(damaged_rect);
                 }
             },
         }
+
+        // The status line is tracked separately, since it's not part of the terminal grid.
+        let indicator = terminal.status_line_type() != StatusLineType::HostWritable;
+        if self.status_line && (indicator || terminal.status_line_damaged()) {
+            let point = Point::new(self.size_info.screen_lines(), Column(0));
+            let damage = self.damage_from_point(point, self.size_info.columns() as u32);
+            self.damage_rects.push(damage);
+        }
+
         terminal.reset_damage();
     }
 
@@ -708,25 +793,62 @@ impl Display {
         scheduler: &mut Scheduler,
         message_buffer: &MessageBuffer,
         config: &UiConfig,
+        search_state: &SearchState,
     ) {
+        let status_lines = usize::from(self.status_line);
+        let status_line_point = Point::new(self.size_info.screen_lines(), Column(0));
+        let num_cols = self.size_info.columns();
+
         // Collect renderable content before the terminal is dropped.
-        let mut content = RenderableContent::new(config, self, &terminal);
+        let mut content = RenderableContent::new(config, self, &terminal, search_state);
         let mut grid_cells = Vec::new();
         for cell in &mut content {
             grid_cells.push(cell);
         }
+
+        // Collect the status line, which is either written by the host or an indicator.
+        let mut status_line_cells = Vec::new();
+        let mut status_indicator = None;
+        if status_lines != 0 {
+            if terminal.status_line_type() == StatusLineType::HostWritable {
+                let status_line = terminal.status_line();
+                status_line_cells = content.status_line_cells(status_line, status_line_point.line);
+            } else {
+                status_indicator = Some(Self::format_status_indicator(&terminal, num_cols));
+            }
+        }
         let selection_range = content.selection_range();
         let foreground_color = content.color(NamedColor::Foreground as usize);
         let background_color = content.color(NamedColor::Background as usize);
         let display_offset = content.display_offset();
+        let success_color = content.color(NamedColor::Green as usize);
+        let failure_color = content.color(NamedColor::Red as usize);
         let cursor = content.cursor();
+        let has_highlighted_hint =
+            self.highlighted_hint.is_some() || self.vi_highlighted_hint.is_some();
 
         let cursor_point = terminal.grid().cursor.point;
+        let total_lines = terminal.grid().total_lines();
+        let vi_cursor_point = if terminal.mode().contains(TermMode::VI) {
+            Some(terminal.vi_mode_cursor.point)
+        } else {
+            None
+        };
         let metrics = self.glyph_cache.font_metrics();
         let size_info = self.size_info;
+        let graphics_queues = terminal.graphics_take_queues();
+
+        // Collect the exit status of finished commands in the viewport.
+        let exit_markers: Vec<_> = (0..size_info.screen_lines())
+            .filter_map(|line| {
+                let grid_line = Line(line as i32 - display_offset as i32);
+                let exit_code = terminal.grid().row_attrs(grid_line).shell_marks.exit_code?;
+                Some((line, if exit_code == 0 { success_color } else { failure_color }))
+            })
+            .collect();
 
         if self.collect_damage() {
-            self.update_damage(&mut terminal, selection_range);
+            self.update_damage(&mut terminal, selection_range, search_state);
         }
 
         // Drop terminal as early as possible to free lock.
@@ -735,9 +857,24 @@ impl Display {
         // Make sure this window's OpenGL context is active.
         self.make_current();
 
+        // Upload new images before they're drawn.
+        if let Some(graphics_queues) = graphics_queues {
+            self.renderer.update_graphics(graphics_queues);
+        }
+
         self.renderer.clear(background_color, config.window_opacity());
         let mut lines = RenderLines::new();
 
+        // Images with a negative z-index are drawn below the text.
+        let (graphics_below, graphics_above): (Vec<_>, Vec<_>) = grid_cells
+            .iter()
+            .filter_map(|cell| {
+                let graphic = cell.extra.as_ref()?.graphic.clone()?;
+                Some(RenderGraphic { point: cell.point, graphic })
+            })
+            .partition(|graphic| graphic.graphic.placement.z_index < 0);
+        self.renderer.draw_graphics(&size_info, graphics_below);
+
         // Draw grid.
         {
             let _sampler = self.meter.sampler();
@@ -747,11 +884,24 @@ impl Display {
             self.renderer.set_viewport(&size_info);
 
             let glyph_cache = &mut self.glyph_cache;
+            let highlighted_hint = &self.highlighted_hint;
+            let vi_highlighted_hint = &self.vi_highlighted_hint;
 
             self.renderer.draw_cells(
                 &size_info,
                 glyph_cache,
-                grid_cells.into_iter().map(|cell| {
+                grid_cells.into_iter().map(|mut cell| {
+                    // Underline hints hovered by mouse or vi mode cursor.
+                    let point = term::viewport_to_point(display_offset, cell.point);
+                    let hyperlink = cell.extra.as_ref().and_then(|extra| extra.hyperlink.as_ref());
+
+                    let should_highlight = |hint: &Option<HintMatch>| {
+                        hint.as_ref().map_or(false, |hint| hint.should_highlight(point, hyperlink))
+                    };
+                    if should_highlight(highlighted_hint) || should_highlight(vi_highlighted_hint) {
+                        cell.flags.insert(Flags::UNDERLINE);
+                    }
+
                     // Update underline/strikeout.
                     lines.update(&cell);
 
@@ -760,22 +910,118 @@ impl Display {
             );
         }
 
+        self.renderer.draw_graphics(&size_info, graphics_above);
+
+        // Draw the status line below the terminal.
+        if let Some(status_indicator) = status_indicator {
+            let fg = config.colors.footer_bar_foreground();
+            let bg = config.colors.footer_bar_background();
+            self.renderer.draw_string(
+                status_line_point,
+                fg,
+                bg,
+                status_indicator.chars(),
+                &size_info,
+                &mut self.glyph_cache,
+            );
+        } else if !status_line_cells.is_empty() {
+            let glyph_cache = &mut self.glyph_cache;
+            self.renderer.draw_cells(&size_info, glyph_cache, status_line_cells.into_iter());
+        }
+
         let mut rects = lines.rects(&metrics, &size_info);
 
+        if let Some(vi_cursor_point) = vi_cursor_point {
+            // Indicate vi mode by showing the cursor's position in the top right corner.
+            let line = (-vi_cursor_point.line.0 + size_info.bottommost_line().0) as usize;
+            let obstructed_column = Some(vi_cursor_point)
+                .filter(|point| point.line == -(display_offset as i32))
+                .map(|point| point.column);
+            self.draw_line_indicator(config, total_lines, obstructed_column, line);
+        } else if search_state.regex().is_some() {
+            // Show current display offset in vi-less search to indicate match position.
+            self.draw_line_indicator(config, total_lines, None, display_offset);
+        }
+
+        // Mark the exit status of commands next to their prompt, if there's padding to the left.
+        let marker_width = (size_info.cell_width() / 4.).floor().min(size_info.padding_x().floor());
+        if marker_width >= 1. {
+            let marker_x = size_info.padding_x() - marker_width;
+            for (line, color) in exit_markers {
+                let y = size_info.padding_y() + line as f32 * size_info.cell_height();
+                rects.push(RenderRect::new(
+                    marker_x,
+                    y,
+                    marker_width,
+                    size_info.cell_height(),
+                    color,
+                    1.,
+                ));
+            }
+        }
+
         // Draw cursor.
         rects.extend(cursor.rects(&size_info, config.terminal_config.cursor.thickness()));
 
+        // Push visual bell after url/underline/strikeout rects.
+        let visual_bell_intensity = self.visual_bell.intensity();
+        if visual_bell_intensity != 0. {
+            let visual_bell_rect = RenderRect::new(
+                0.,
+                0.,
+                size_info.width(),
+                size_info.height(),
+                config.bell.color,
+                visual_bell_intensity as f32,
+            );
+            rects.push(visual_bell_rect);
+        }
+
         // Handle IME positioning and search bar rendering.
-        let ime_position = {
-            let num_lines = self.size_info.screen_lines();
-            term::point_to_viewport(display_offset, cursor_point)
-                .filter(|point| point.line < num_lines)
+        let ime_position = match search_state.regex() {
+            Some(regex) => {
+                let search_label = match search_state.direction() {
+                    Direction::Right => FORWARD_SEARCH_LABEL,
+                    Direction::Left => BACKWARD_SEARCH_LABEL,
+                };
+
+                let search_text = Self::format_search(regex, search_label, size_info.columns());
+
+                // Render the search bar.
+                self.draw_search(config, &search_text);
+
+                // Draw search bar cursor.
+                let line = size_info.screen_lines() + status_lines;
+                let column = Column(search_text.chars().count() - 1);
+
+                // Add cursor to search bar if IME is not active.
+                if self.ime.preedit().is_none() {
+                    let fg = config.colors.footer_bar_foreground();
+                    let shape = CursorShape::Underline;
+                    let cursor = RenderableCursor::new(Point::new(line, column), shape, fg, false);
+                    rects.extend(
+                        cursor.rects(&size_info, config.terminal_config.cursor.thickness()),
+                    );
+                }
+
+                Some(Point::new(line, column))
+            },
+            None => {
+                let num_lines = self.size_info.screen_lines();
+                term::point_to_viewport(display_offset, cursor_point)
+                    .filter(|point| point.line < num_lines)
+            },
         };
 
         // Handle IME.
         if self.ime.is_enabled() {
             if let Some(point) = ime_position {
-                let (fg, bg) = (foreground_color, background_color);
+                let (fg, bg) = if search_state.regex().is_some() {
+                    (config.colors.footer_bar_foreground(), config.colors.footer_bar_background())
+                } else {
+                    (foreground_color, background_color)
+                };
+
                 self.draw_ime_preview(point, fg, bg, &mut rects, config);
             }
         }
@@ -785,10 +1031,11 @@ impl Display {
         }
 
         if let Some(message) = message_buffer.message() {
+            let search_offset = usize::from(search_state.regex().is_some());
             let text = message.text(&size_info);
 
             // Create a new rectangle for the background.
-            let start_line = size_info.screen_lines();
+            let start_line = size_info.screen_lines() + status_lines + search_offset;
             let y = size_info.cell_height().mul_add(start_line as f32, size_info.padding_y());
 
             let bg = match message.ty() {
@@ -826,6 +1073,12 @@ impl Display {
 
         self.draw_render_timer(config);
 
+        // Draw hyperlink uri preview.
+        if has_highlighted_hint {
+            let cursor_point = vi_cursor_point.or(Some(cursor_point));
+            self.draw_hyperlink_preview(config, cursor_point, display_offset);
+        }
+
         // Frame event should be requested before swapping buffers on Wayland, since it requires
         // surface `commit`, which is done by swap buffers under the hood.
         if self.is_wayland {
@@ -859,6 +1112,95 @@ impl Display {
     pub fn update_config(&mut self, config: &UiConfig) {
         self.debug_damage = config.debug.highlight_damage;
         self.colors = List::from(&config.colors);
+        self.visual_bell.update_config(&config.bell);
+        self.hint_state.update_alphabet(config.hints.alphabet());
+    }
+
+    /// Update the mouse/vi mode cursor hint highlighting.
+    ///
+    /// This will return whether the highlighted hints changed.
+    pub fn update_highlighted_hints<T>(
+        &mut self,
+        term: &Term<T>,
+        config: &UiConfig,
+        mouse: &Mouse,
+        modifiers: ModifiersState,
+    ) -> bool {
+        let display_offset = term.grid().display_offset();
+
+        // Update vi mode cursor hint.
+        let vi_highlighted_hint = if term.mode().contains(TermMode::VI) {
+            let mods = ModifiersState::all();
+            let point = term.vi_mode_cursor.point;
+            hint::highlighted_at(term, config, point, mods)
+        } else {
+            None
+        };
+        let mut dirty = vi_highlighted_hint != self.vi_highlighted_hint;
+        if dirty {
+            let old_hint = mem::replace(&mut self.vi_highlighted_hint, vi_highlighted_hint);
+            let new_bounds = self.vi_highlighted_hint.as_ref().map(|hint| hint.bounds().clone());
+            self.damage_hint(old_hint.map(|hint| hint.bounds().clone()), display_offset);
+            self.damage_hint(new_bounds, display_offset);
+        }
+
+        // Abort if mouse highlighting conditions are not met.
+        let mouse_point = if !mouse.inside_text_area
+            || !term.selection.as_ref().map_or(true, Selection::is_empty)
+        {
+            None
+        } else {
+            Some(mouse.point(&self.size_info, display_offset))
+        };
+
+        // Find highlighted hint at mouse position.
+        let highlighted_hint =
+            mouse_point.and_then(|point| hint::highlighted_at(term, config, point, modifiers));
+
+        // Update cursor shape.
+        if highlighted_hint.is_some() {
+            // If mouse changed the line, we should update the hyperlink preview, since the
+            // highlighted hint could be disrupted by the old preview.
+            dirty |= self.hint_mouse_point.map(|point| point.line) != mouse_point.map(|p| p.line);
+            self.hint_mouse_point = mouse_point;
+            self.window.set_mouse_cursor(CursorIcon::Hand);
+        } else if self.highlighted_hint.is_some() {
+            self.hint_mouse_point = None;
+            if term.mode().intersects(TermMode::MOUSE_MODE) && !term.mode().contains(TermMode::VI) {
+                self.window.set_mouse_cursor(CursorIcon::Default);
+            } else {
+                self.window.set_mouse_cursor(CursorIcon::Text);
+            }
+        }
+
+        if highlighted_hint != self.highlighted_hint {
+            let old_hint = mem::replace(&mut self.highlighted_hint, highlighted_hint);
+            let new_bounds = self.highlighted_hint.as_ref().map(|hint| hint.bounds().clone());
+            self.damage_hint(old_hint.map(|hint| hint.bounds().clone()), display_offset);
+            self.damage_hint(new_bounds, display_offset);
+            dirty = true;
+        }
+
+        dirty
+    }
+
+    /// Damage all visible lines of a highlighted hint.
+    fn damage_hint(&mut self, bounds: Option<Match>, display_offset: usize) {
+        let bounds = match bounds {
+            Some(bounds) if self.collect_damage() => bounds,
+            _ => return,
+        };
+
+        let num_cols = self.size_info.columns() as u32;
+        for line in bounds.start().line.0..=bounds.end().line.0 {
+            let point = Point::new(Line(line), Column(0));
+            if let Some(point) = term::point_to_viewport(display_offset, point)
+                .filter(|point| point.line < self.size_info.screen_lines())
+            {
+                let damage = self.damage_from_point(point, num_cols);
+                self.damage_rects.push(damage);
+            }
+        }
     }
 
     #[inline(never)]
@@ -950,6 +1292,144 @@ impl Display {
         self.window.update_ime_position(ime_popup_point, &self.size_info);
     }
 
+    /// Format search regex to account for the cursor and fullwidth characters.
+    fn format_search(search_regex: &str, search_label: &str, max_width: usize) -> String {
+        let label_len = search_label.len();
+
+        // Skip `search_regex` formatting if only label is visible.
+        if label_len > max_width {
+            return search_label[..max_width].to_owned();
+        }
+
+        // The search string consists of `search_label` + `search_regex` + `cursor`.
+        let mut bar_text = String::from(search_label);
+        bar_text.extend(StrShortener::new(
+            search_regex,
+            max_width.wrapping_sub(label_len + 1),
+            ShortenDirection::Left,
+            Some(SHORTENER),
+        ));
+
+        // Add place for cursor.
+        bar_text.push(' ');
+
+        bar_text
+    }
+
+    /// Format the text of the indicator status line.
+    fn format_status_indicator<T>(terminal: &Term<T>, max_width: usize) -> String {
+        let point = terminal.grid().cursor.point;
+        let mut text = format!(" Ln {}, Col {}", point.line.0 + 1, point.column.0 + 1);
+
+        let mode = terminal.mode();
+        let labels = [
+            (TermMode::INSERT, "Insert"),
+            (TermMode::ALT_SCREEN, "Alt Screen"),
+            (TermMode::VI, "Vi"),
+            (TermMode::MOUSE_MODE, "Mouse"),
+        ];
+        for (_, label) in labels.iter().filter(|(flag, _)| mode.intersects(*flag)) {
+            text.push_str("  ");
+            text.push_str(label);
+        }
+
+        // Assure text length is exactly max_width.
+        let text: String = text.chars().take(max_width).collect();
+        format!("{:<1$}", text, max_width)
+    }
+
+    /// Draw current search regex.
+    #[inline(never)]
+    fn draw_search(&mut self, config: &UiConfig, text: &str) {
+        // Assure text length is at least num_cols.
+        let num_cols = self.size_info.columns();
+        let text = format!("{:<1$}", text, num_cols);
+
+        let line = self.size_info.screen_lines() + usize::from(self.status_line);
+        let point = Point::new(line, Column(0));
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+
+        self.renderer.draw_string(
+            point,
+            fg,
+            bg,
+            text.chars(),
+            &self.size_info,
+            &mut self.glyph_cache,
+        );
+    }
+
+    /// Resize the window to fit the requested terminal dimensions.
+    #[cfg(unix)]
+    pub fn resize_to_dimensions(&self, config: &UiConfig, dimensions: Dimensions) {
+        let cell_width = self.size_info.cell_width();
+        let cell_height = self.size_info.cell_height();
+        let scale_factor = self.window.scale_factor as f32;
+        let size = window_size(config, dimensions, cell_width, cell_height, scale_factor);
+        self.window.set_inner_size(size);
+    }
+
+    /// Draw preview for the currently highlighted `Hyperlink`.
+    #[inline(never)]
+    fn draw_hyperlink_preview(
+        &mut self,
+        config: &UiConfig,
+        cursor_point: Option<Point>,
+        display_offset: usize,
+    ) {
+        let num_cols = self.size_info.columns();
+        let uris: Vec<_> = self
+            .highlighted_hint
+            .iter()
+            .chain(&self.vi_highlighted_hint)
+            .filter_map(|hint| hint.hyperlink().map(|hyperlink| hyperlink.uri()))
+            .map(|uri| StrShortener::new(uri, num_cols, ShortenDirection::Right, Some(SHORTENER)))
+            .collect();
+
+        if uris.is_empty() {
+            return;
+        }
+
+        // The maximum amount of protected lines including the ones we'll show preview on.
+        let max_protected_lines = uris.len() * 2;
+
+        // Lines we shouldn't show preview on, because it'll obscure the highlighted hint.
+        let mut protected_lines = Vec::with_capacity(max_protected_lines);
+        if self.size_info.screen_lines() >= max_protected_lines {
+            // Prefer to show preview even when it'll likely obscure the highlighted hint, when
+            // there's no place left for it.
+            protected_lines.push(self.hint_mouse_point.map(|point| point.line));
+            protected_lines.push(cursor_point.map(|point| point.line));
+        }
+
+        // Find the line in viewport we can draw preview on without obscuring protected lines.
+        let viewport_bottom = self.size_info.bottommost_line() - Line(display_offset as i32);
+        let viewport_top = viewport_bottom - (self.size_info.screen_lines() - 1);
+        let uri_lines = (viewport_top.0..=viewport_bottom.0)
+            .rev()
+            .map(|line| Some(Line(line)))
+            .filter(|line| !protected_lines.contains(line))
+            .take(uris.len())
+            .flat_map(|line| term::point_to_viewport(display_offset, Point::new(line?, Column(0))));
+
+        let fg = config.colors.footer_bar_foreground();
+        let bg = config.colors.footer_bar_background();
+        for (uri, point) in uris.into_iter().zip(uri_lines) {
+            // Damage the uri preview.
+            if self.collect_damage() {
+                let damage = self.damage_from_point(point, num_cols as u32);
+                self.damage_rects.push(damage);
+
+                // Damage the uri preview for the next frame as well.
+                self.next_frame_damage_rects.push(damage);
+            }
+
+            self.renderer.draw_string(point, fg, bg, uri, &self.size_info, &mut self.glyph_cache);
+        }
+    }
+
     /// Draw render timer.
     #[inline(never)]
     fn draw_render_timer(&mut self, config: &UiConfig) {
@@ -976,6 +1456,37 @@ impl Display {
         self.renderer.draw_string(point, fg, bg, timing.chars(), &self.size_info, glyph_cache);
     }
 
+    /// Draw an indicator for the position of a line in history.
+    #[inline(never)]
+    fn draw_line_indicator(
+        &mut self,
+        config: &UiConfig,
+        total_lines: usize,
+        obstructed_column: Option<Column>,
+        line: usize,
+    ) {
+        let text = format!("[{}/{}]", line, total_lines - 1);
+        let column = Column(self.size_info.columns().saturating_sub(text.len()));
+        let point = Point::new(0, column);
+
+        if self.collect_damage() {
+            // Damage the line indicator for the current and next frame.
+            let damage = self.damage_from_point(point, text.len() as u32);
+            self.damage_rects.push(damage);
+            self.next_frame_damage_rects.push(damage);
+        }
+
+        let colors = &config.colors;
+        let fg = colors.line_indicator.foreground.unwrap_or(colors.primary.background);
+        let bg = colors.line_indicator.background.unwrap_or(colors.primary.foreground);
+
+        // Do not render anything if it would obscure the vi mode cursor.
+        if obstructed_column.map_or(true, |obstructed_column| obstructed_column < column) {
+            let glyph_cache = &mut self.glyph_cache;
+            self.renderer.draw_string(point, fg, bg, text.chars(), &self.size_info, glyph_cache);
+        }
+    }
+
     /// Damage `len` starting from a `point`.
     ///
     /// This method also enqueues damage for the next frame automatically.
@@ -229,6 +229,16 @@ impl Window {
         self.window.inner_size()
     }
 
+    #[inline]
+    pub fn outer_position(&self) -> Option<PhysicalPosition<i32>> {
+        self.window.outer_position().ok()
+    }
+
+    #[inline]
+    pub fn focus_window(&self) {
+        self.window.focus_window();
+    }
+
     #[inline]
     pub fn set_visible(&self, visibility: bool) {
         self.window.set_visible(visibility);
@@ -332,6 +342,11 @@ impl Window {
         self.window.request_user_attention(attention);
     }
 
+    #[inline]
+    pub fn request_redraw(&self) {
+        self.window.request_redraw();
+    }
+
     pub fn id(&self) -> WindowId {
         self.window.id()
     }
@@ -394,6 +409,11 @@ impl Window {
         self.wayland_surface.as_ref()
     }
 
+    /// Disable/enable IME.
+    pub fn set_ime_allowed(&self, allowed: bool) {
+        self.window.set_ime_allowed(allowed);
+    }
+
     /// Adjust the IME editor position according to the new location of the cursor.
     pub fn update_ime_position(&self, point: Point<usize>, size: &SizeInfo) {
         let nspot_x = f64::from(size.padding_x() + point.column.0 as f32 * size.cell_width());
@@ -2,15 +2,25 @@
 
 use std::borrow::Cow;
 use std::cmp::{max, min};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::error::Error;
 use std::ffi::OsStr;
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 #[cfg(not(windows))]
 use std::os::unix::io::RawFd;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+#[cfg(unix)]
+use std::os::unix::process::ExitStatusExt;
 use std::path::PathBuf;
+use std::process::ExitStatus;
 use std::rc::Rc;
 use std::sync::atomic::Ordering;
+#[cfg(unix)]
+use std::sync::mpsc::Sender;
+#[cfg(unix)]
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use std::{env, f32, mem};
 
@@ -32,32 +42,56 @@ use winit::window::WindowId;
 
 use crossfont::{self, Size};
 
-use alacritty_terminal::config::LOG_TARGET_CONFIG;
+use alacritty_terminal::ansi::{Notification, WindowOperation};
+use alacritty_terminal::config::{Hold, LOG_TARGET_CONFIG};
 use alacritty_terminal::event::{Event as TerminalEvent, EventListener, Notify};
 use alacritty_terminal::event_loop::Notifier;
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Column, Point, Side};
+use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
 use alacritty_terminal::selection::{Selection, SelectionType};
+use alacritty_terminal::term::search::{Match, RegexSearch};
 use alacritty_terminal::term::{self, ClipboardType, Term, TermMode};
 
 #[cfg(unix)]
-use crate::cli::IpcConfig;
+use crate::cli::{IpcConfig, SocketMessage};
 use crate::cli::{Options as CliOptions, WindowOptions};
 use crate::clipboard::Clipboard;
+use crate::config::ui_config::{HintAction, HintInternalAction};
+#[cfg(unix)]
+use crate::config::window::Dimensions as WindowDimensions;
+use crate::config::window::WindowOperationPermission;
 use crate::config::{self, UiConfig};
 #[cfg(not(windows))]
 use crate::daemon::foreground_process_path;
 use crate::daemon::spawn_daemon;
+use crate::display::hint::HintMatch;
 use crate::display::window::Window;
 use crate::display::{Display, Preedit, SizeInfo};
-use crate::input::{self, ActionContext as _, FONT_SIZE_STEP};
-use crate::message_bar::{Message, MessageBuffer};
+use crate::input::{self, ActionContext as _, KeyboardState, FONT_SIZE_STEP};
+#[cfg(unix)]
+use crate::ipc::{IpcError, IpcEvent, IpcSubscribers, SocketReply};
+use crate::message_bar::{Message, MessageBuffer, MessageType};
 use crate::scheduler::{Scheduler, TimerId, Topic};
 use crate::window_context::WindowContext;
 
+/// Duration after the last user input until an unlimited search is performed.
+pub const TYPING_SEARCH_DELAY: Duration = Duration::from_millis(500);
+
+/// Minimum time between two desktop notifications.
+const NOTIFICATION_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum number of lines for the blocking search while still typing the search regex.
+const MAX_SEARCH_WHILE_TYPING: Option<usize> = Some(1000);
+
+/// Maximum number of search terms stored in the history.
+const MAX_SEARCH_HISTORY_SIZE: usize = 255;
+
 /// Touch zoom speed.
 const TOUCH_ZOOM_FACTOR: f32 = 0.01;
 
+/// Message bar target for the child process exit banner.
+pub const CHILD_EXIT_MESSAGE_TARGET: &str = "alacritty_child_exit";
+
 /// Alacritty events.
 #[derive(Debug, Clone)]
 pub struct Event {
@@ -88,11 +122,23 @@ pub enum EventType {
     ConfigReload(PathBuf),
     Message(Message),
     Scroll(Scroll),
+    SearchNext,
     CreateWindow(WindowOptions),
+    RestartShell {
+        keep_scrollback: bool,
+    },
+    RespawnShell {
+        keep_scrollback: bool,
+    },
     #[cfg(unix)]
     IpcConfig(IpcConfig),
+    #[cfg(unix)]
+    IpcRequest(SocketMessage, Sender<SocketReply>),
+    #[cfg(unix)]
+    IpcSubscribe(Arc<UnixStream>),
     BlinkCursor,
     BlinkCursorTimeout,
+    NotificationTimeout,
     Frame,
 }
 
@@ -102,19 +148,92 @@ impl From<TerminalEvent> for EventType {
     }
 }
 
+/// Regex search state.
+pub struct SearchState {
+    /// Search direction.
+    pub direction: Direction,
+
+    /// Current position in the search history.
+    pub history_index: Option<usize>,
+
+    /// Change in display offset since the beginning of the search.
+    display_offset_delta: i32,
+
+    /// Search origin in viewport coordinates relative to original display offset.
+    origin: Point,
+
+    /// Focused match during active search.
+    focused_match: Option<Match>,
+
+    /// Search regex and history.
+    ///
+    /// During an active search, the first element is the user's current input.
+    ///
+    /// While going through history, the [`SearchState::history_index`] will point to the element
+    /// in history which is currently being previewed.
+    history: VecDeque<String>,
+
+    /// Compiled search automatons.
+    dfas: Option<RegexSearch>,
+}
+
+impl SearchState {
+    /// Search regex text if a search is active.
+    pub fn regex(&self) -> Option<&String> {
+        self.history_index.and_then(|index| self.history.get(index))
+    }
+
+    /// Direction of the search from the search origin.
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Focused match during vi-less search.
+    pub fn focused_match(&self) -> Option<&Match> {
+        self.focused_match.as_ref()
+    }
+
+    /// Active search dfas.
+    pub fn dfas(&self) -> Option<&RegexSearch> {
+        self.dfas.as_ref()
+    }
+
+    /// Search regex text if a search is active.
+    fn regex_mut(&mut self) -> Option<&mut String> {
+        self.history_index.and_then(move |index| self.history.get_mut(index))
+    }
+}
+
+impl Default for SearchState {
+    fn default() -> Self {
+        Self {
+            direction: Direction::Right,
+            display_offset_delta: Default::default(),
+            focused_match: Default::default(),
+            history_index: Default::default(),
+            history: Default::default(),
+            origin: Default::default(),
+            dfas: Default::default(),
+        }
+    }
+}
+
 pub struct ActionContext<'a, N, T> {
     pub notifier: &'a mut N,
+    pub child_exited: &'a mut bool,
     pub terminal: &'a mut Term<T>,
     pub clipboard: &'a mut Clipboard,
     pub mouse: &'a mut Mouse,
     pub touch: &'a mut TouchPurpose,
     pub received_count: &'a mut usize,
     pub suppress_chars: &'a mut bool,
+    pub keyboard: &'a mut KeyboardState,
     pub modifiers: &'a mut ModifiersState,
     pub display: &'a mut Display,
     pub message_buffer: &'a mut MessageBuffer,
     pub config: &'a UiConfig,
     pub cursor_blink_timed_out: &'a mut bool,
+    pub search_state: &'a mut SearchState,
     pub event_loop: &'a EventLoopWindowTarget<Event>,
     pub event_proxy: &'a EventLoopProxy<Event>,
     pub scheduler: &'a mut Scheduler,
@@ -122,6 +241,7 @@ pub struct ActionContext<'a, N, T> {
     pub dirty: &'a mut bool,
     pub occluded: &'a mut bool,
     pub preserve_title: bool,
+    pub hold: Hold,
     #[cfg(not(windows))]
     pub master_fd: RawFd,
     #[cfg(not(windows))]
@@ -148,12 +268,16 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
     fn scroll(&mut self, scroll: Scroll) {
         let old_offset = self.terminal.grid().display_offset() as i32;
 
+        let old_vi_cursor = self.terminal.vi_mode_cursor;
         self.terminal.scroll_display(scroll);
 
         let lines_changed = old_offset - self.terminal.grid().display_offset() as i32;
 
         // Update selection.
-        if self.mouse.left_button_state == ElementState::Pressed
+        let vi_mode = self.terminal.mode().contains(TermMode::VI);
+        if vi_mode && self.terminal.selection.as_ref().map_or(false, |s| !s.is_empty()) {
+            self.update_selection(self.terminal.vi_mode_cursor.point, Side::Right);
+        } else if self.mouse.left_button_state == ElementState::Pressed
             || self.mouse.right_button_state == ElementState::Pressed
         {
             let display_offset = self.terminal.grid().display_offset();
@@ -161,8 +285,14 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
             self.update_selection(point, self.mouse.cell_side);
         }
 
-        // Update dirty if actually scrolled or we're in the Vi mode.
-        *self.dirty |= lines_changed != 0;
+        // Scrolling inside Vi mode moves the cursor, so start typing.
+        if vi_mode {
+            self.on_typing_start();
+        }
+
+        // Update dirty if actually scrolled or moved Vi cursor in Vi mode.
+        *self.dirty |=
+            lines_changed != 0 || (vi_mode && old_vi_cursor != self.terminal.vi_mode_cursor);
     }
 
     // Copy text selection.
@@ -204,6 +334,7 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
         // Move vi cursor and expand selection.
         if self.terminal.mode().contains(TermMode::VI) {
+            self.terminal.vi_mode_cursor.point = point;
             selection.include_all();
         }
 
@@ -218,6 +349,18 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.copy_selection(ClipboardType::Selection);
     }
 
+    fn select_lines(&mut self, lines: RangeInclusive<Line>) {
+        let start = Point::new(*lines.start(), Column(0));
+        let end = Point::new(*lines.end(), self.terminal.last_column());
+
+        let mut selection = Selection::new(SelectionType::Lines, start, Side::Left);
+        selection.update(end, Side::Right);
+        self.terminal.selection = Some(selection);
+        *self.dirty = true;
+
+        self.copy_selection(ClipboardType::Selection);
+    }
+
     fn toggle_selection(&mut self, ty: SelectionType, point: Point, side: Side) {
         match &mut self.terminal.selection {
             Some(selection) if selection.ty == ty && !selection.is_empty() => {
@@ -264,6 +407,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.suppress_chars
     }
 
+    #[inline]
+    fn keyboard(&mut self) -> &mut KeyboardState {
+        self.keyboard
+    }
+
     #[inline]
     fn modifiers(&mut self) -> &mut ModifiersState {
         self.modifiers
@@ -292,6 +440,7 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
     fn spawn_new_instance(&mut self) {
         let mut env_args = env::args();
         let alacritty = env_args.next().unwrap();
+        let has_working_directory = self.working_directory().is_some();
 
         let mut args: Vec<String> = Vec::new();
 
@@ -303,9 +452,8 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
                 break;
             }
 
-            // On unix, the working directory of the foreground shell is used by `start_daemon`.
-            #[cfg(not(windows))]
-            if arg == "--working-directory" {
+            // The working directory of the shell is used by `spawn_daemon`.
+            if has_working_directory && arg == "--working-directory" {
                 let _ = env_args.next();
                 continue;
             }
@@ -316,34 +464,20 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         self.spawn_daemon(&alacritty, &args);
     }
 
-    #[cfg(not(windows))]
     fn create_new_window(&mut self) {
         let mut options = WindowOptions::default();
-        if let Ok(working_directory) = foreground_process_path(self.master_fd, self.shell_pid) {
-            options.terminal_options.working_directory = Some(working_directory);
-        }
+        options.terminal_options.working_directory = self.working_directory();
 
         let _ = self.event_proxy.send_event(Event::new(EventType::CreateWindow(options), None));
     }
 
-    #[cfg(windows)]
-    fn create_new_window(&mut self) {
-        let _ = self
-            .event_proxy
-            .send_event(Event::new(EventType::CreateWindow(WindowOptions::default()), None));
-    }
-
     fn spawn_daemon<I, S>(&self, program: &str, args: I)
     where
         I: IntoIterator<Item = S> + Debug + Copy,
         S: AsRef<OsStr>,
     {
-        #[cfg(not(windows))]
-        let result = spawn_daemon(program, args, self.master_fd, self.shell_pid);
-        #[cfg(windows)]
-        let result = spawn_daemon(program, args);
-
-        match result {
+        let working_directory = self.working_directory();
+        match spawn_daemon(program, args, working_directory.as_deref()) {
             Ok(_) => debug!("Launched {} with args {:?}", program, args),
             Err(_) => warn!("Unable to launch {} with args {:?}", program, args),
         }
@@ -368,6 +502,234 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    /// Toggle the vi mode status.
+    #[inline]
+    fn toggle_vi_mode(&mut self) {
+        let was_in_vi_mode = self.terminal.mode().contains(TermMode::VI);
+        if was_in_vi_mode {
+            // If we had search running when leaving Vi mode we should mark terminal fully damaged
+            // to cleanup highlighted results.
+            if self.search_state.dfas.take().is_some() {
+                self.terminal.mark_fully_damaged();
+            }
+        } else {
+            self.clear_selection();
+        }
+
+        if self.search_active() {
+            self.cancel_search();
+        }
+
+        // We don't want IME in Vi mode.
+        self.window().set_ime_allowed(was_in_vi_mode);
+
+        self.terminal.toggle_vi_mode();
+
+        *self.dirty = true;
+    }
+
+    #[inline]
+    fn start_search(&mut self, direction: Direction) {
+        // Only create new history entry if the previous regex wasn't empty.
+        if self.search_state.history.front().map_or(true, |regex| !regex.is_empty()) {
+            self.search_state.history.push_front(String::new());
+            self.search_state.history.truncate(MAX_SEARCH_HISTORY_SIZE);
+        }
+
+        self.search_state.history_index = Some(0);
+        self.search_state.direction = direction;
+        self.search_state.focused_match = None;
+
+        // Store original search position as origin and reset location.
+        if self.terminal.mode().contains(TermMode::VI) {
+            self.search_state.origin = self.terminal.vi_mode_cursor.point;
+            self.search_state.display_offset_delta = 0;
+
+            // Adjust origin for content moving upward on search start.
+            if self.terminal.grid().cursor.point.line + 1 == self.terminal.screen_lines() {
+                self.search_state.origin.line -= 1;
+            }
+        } else {
+            let viewport_top = Line(-(self.terminal.grid().display_offset() as i32)) - 1;
+            let viewport_bottom = viewport_top + self.terminal.bottommost_line();
+            let last_column = self.terminal.last_column();
+            self.search_state.origin = match direction {
+                Direction::Right => Point::new(viewport_top, Column(0)),
+                Direction::Left => Point::new(viewport_bottom, last_column),
+            };
+        }
+
+        // Enable IME so we can input into the search bar with it if we were in Vi mode.
+        self.window().set_ime_allowed(true);
+
+        self.display.pending_update.dirty = true;
+    }
+
+    #[inline]
+    fn confirm_search(&mut self) {
+        // Just cancel search when not in vi mode.
+        if !self.terminal.mode().contains(TermMode::VI) {
+            self.cancel_search();
+            return;
+        }
+
+        // Force unlimited search if the previous one was interrupted.
+        let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
+        if self.scheduler.scheduled(timer_id) {
+            self.goto_match(None);
+        }
+
+        self.exit_search();
+    }
+
+    #[inline]
+    fn cancel_search(&mut self) {
+        if self.terminal.mode().contains(TermMode::VI) {
+            // Recover pre-search state in vi mode.
+            self.search_reset_state();
+        } else if let Some(focused_match) = &self.search_state.focused_match {
+            // Create a selection for the focused match.
+            let start = *focused_match.start();
+            let end = *focused_match.end();
+            self.start_selection(SelectionType::Simple, start, Side::Left);
+            self.update_selection(end, Side::Right);
+            self.copy_selection(ClipboardType::Selection);
+        }
+
+        self.search_state.dfas = None;
+
+        self.exit_search();
+    }
+
+    #[inline]
+    fn search_input(&mut self, c: char) {
+        match self.search_state.history_index {
+            Some(0) => (),
+            // When currently in history, replace active regex with history on change.
+            Some(index) => {
+                self.search_state.history[0] = self.search_state.history[index].clone();
+                self.search_state.history_index = Some(0);
+            },
+            None => return,
+        }
+        let regex = &mut self.search_state.history[0];
+
+        match c {
+            // Handle backspace/ctrl+h.
+            '\x08' | '\x7f' => {
+                let _ = regex.pop();
+            },
+            // Add ascii and unicode text.
+            ' '..='~' | '\u{a0}'..='\u{10ffff}' => regex.push(c),
+            // Ignore non-printable characters.
+            _ => return,
+        }
+
+        if !self.terminal.mode().contains(TermMode::VI) {
+            // Clear selection so we do not obstruct any matches.
+            self.terminal.selection = None;
+        }
+
+        self.update_search();
+    }
+
+    #[inline]
+    fn search_pop_word(&mut self) {
+        if let Some(regex) = self.search_state.regex_mut() {
+            *regex = regex.trim_end().to_owned();
+            regex.truncate(regex.rfind(' ').map_or(0, |i| i + 1));
+            self.update_search();
+        }
+    }
+
+    /// Go to the previous regex in the search history.
+    #[inline]
+    fn search_history_previous(&mut self) {
+        let index = match &mut self.search_state.history_index {
+            None => return,
+            Some(index) if *index + 1 >= self.search_state.history.len() => return,
+            Some(index) => index,
+        };
+
+        *index += 1;
+        self.update_search();
+    }
+
+    /// Go to the next regex in the search history.
+    #[inline]
+    fn search_history_next(&mut self) {
+        let index = match &mut self.search_state.history_index {
+            Some(0) | None => return,
+            Some(index) => index,
+        };
+
+        *index -= 1;
+        self.update_search();
+    }
+
+    #[inline]
+    fn advance_search_origin(&mut self, direction: Direction) {
+        // Use focused match as new search origin if available.
+        if let Some(focused_match) = &self.search_state.focused_match {
+            let new_origin = match direction {
+                Direction::Right => focused_match.end().add(self.terminal, Boundary::None, 1),
+                Direction::Left => focused_match.start().sub(self.terminal, Boundary::None, 1),
+            };
+
+            self.terminal.scroll_to_point(new_origin);
+
+            self.search_state.display_offset_delta = 0;
+            self.search_state.origin = new_origin;
+        }
+
+        // Search for the next match using the supplied direction.
+        let search_direction = mem::replace(&mut self.search_state.direction, direction);
+        self.goto_match(None);
+        self.search_state.direction = search_direction;
+
+        // If we found a match, we set the search origin right in front of it to make sure that
+        // after modifications to the regex the search is started without moving the focused match
+        // around.
+        let focused_match = match &self.search_state.focused_match {
+            Some(focused_match) => focused_match,
+            None => return,
+        };
+
+        // Set new origin to the left/right of the match, depending on search direction.
+        let new_origin = match self.search_state.direction {
+            Direction::Right => *focused_match.start(),
+            Direction::Left => *focused_match.end(),
+        };
+
+        // Store the search origin with display offset by checking how far we need to scroll to it.
+        let old_display_offset = self.terminal.grid().display_offset() as i32;
+        self.terminal.scroll_to_point(new_origin);
+        let new_display_offset = self.terminal.grid().display_offset() as i32;
+        self.search_state.display_offset_delta = new_display_offset - old_display_offset;
+
+        // Store origin and scroll back to the match.
+        self.terminal.scroll_display(Scroll::Delta(-self.search_state.display_offset_delta));
+        self.search_state.origin = new_origin;
+    }
+
+    /// Find the next search match.
+    fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match> {
+        self.search_state
+            .dfas
+            .as_ref()
+            .and_then(|dfas| self.terminal.search_next(dfas, origin, direction, side, None))
+    }
+
+    #[inline]
+    fn search_direction(&self) -> Direction {
+        self.search_state.direction
+    }
+
+    #[inline]
+    fn search_active(&self) -> bool {
+        self.search_state.history_index.is_some()
+    }
+
     /// Handle keyboard typing start.
     ///
     /// This will temporarily disable some features like terminal cursor blinking or the mouse
@@ -395,6 +757,85 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
         }
     }
 
+    /// Process a new character for keyboard hints.
+    fn hint_input(&mut self, c: char) {
+        if let Some(hint) = self.display.hint_state.keyboard_input(self.terminal, c) {
+            self.mouse.block_hint_launcher = false;
+            self.trigger_hint(&hint);
+        }
+        *self.dirty = true;
+    }
+
+    /// Trigger a hint action.
+    fn trigger_hint(&mut self, hint: &HintMatch) {
+        if self.mouse.block_hint_launcher {
+            return;
+        }
+
+        let hint_bounds = hint.bounds();
+        let text = match hint.hyperlink() {
+            Some(hyperlink) => hyperlink.uri().to_owned(),
+            None => self.terminal.bounds_to_string(*hint_bounds.start(), *hint_bounds.end()),
+        };
+
+        match &hint.action() {
+            // Launch an external program.
+            HintAction::Command(command) => {
+                let mut args = command.args().to_vec();
+                args.push(text);
+                self.spawn_daemon(command.program(), &args);
+            },
+            // Copy the text to the clipboard.
+            HintAction::Action(HintInternalAction::Copy) => {
+                self.clipboard.store(ClipboardType::Clipboard, text);
+            },
+            // Write the text to the PTY/search.
+            HintAction::Action(HintInternalAction::Paste) => self.paste(&text),
+            // Select the text.
+            HintAction::Action(HintInternalAction::Select) => {
+                self.start_selection(SelectionType::Simple, *hint_bounds.start(), Side::Left);
+                self.update_selection(*hint_bounds.end(), Side::Right);
+                self.copy_selection(ClipboardType::Selection);
+            },
+            // Move the vi mode cursor.
+            HintAction::Action(HintInternalAction::MoveViModeCursor) => {
+                // Enter vi mode if we're not in it already.
+                if !self.terminal.mode().contains(TermMode::VI) {
+                    self.terminal.toggle_vi_mode();
+                }
+
+                self.terminal.vi_goto_point(*hint_bounds.start());
+                self.mark_dirty();
+            },
+        }
+    }
+
+    /// Copy the URI of the hyperlink below the vi mode or mouse cursor.
+    fn copy_hyperlink(&mut self) {
+        let point = if self.terminal.mode().contains(TermMode::VI) {
+            self.terminal.vi_mode_cursor.point
+        } else {
+            let display_offset = self.terminal.grid().display_offset();
+            self.mouse.point(&self.size_info(), display_offset)
+        };
+
+        if let Some(hyperlink) = self.terminal.grid()[point].hyperlink() {
+            self.clipboard.store(ClipboardType::Clipboard, hyperlink.uri().to_owned());
+        }
+    }
+
+    #[inline]
+    fn child_exited(&self) -> bool {
+        *self.child_exited
+    }
+
+    /// Replace the shell with a new one.
+    fn restart_shell(&mut self, keep_scrollback: bool) {
+        let event = EventType::RestartShell { keep_scrollback };
+        let event = Event::new(event, self.display.window.id());
+        let _ = self.event_proxy.send_event(event);
+    }
+
     /// Expand the selection to the current mouse cursor position.
     #[inline]
     fn expand_selection(&mut self) {
@@ -428,7 +869,11 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 
     /// Paste a text into the terminal.
     fn paste(&mut self, text: &str) {
-        if self.terminal().mode().contains(TermMode::BRACKETED_PASTE) {
+        if self.search_active() {
+            for c in text.chars() {
+                self.search_input(c);
+            }
+        } else if self.terminal().mode().contains(TermMode::BRACKETED_PASTE) {
             self.write_to_pty(&b"\x1b[200~"[..]);
 
             // Write filtered escape sequences.
@@ -473,14 +918,279 @@ impl<'a, N: Notify + 'a, T: EventListener> input::ActionContext<T> for ActionCon
 }
 
 impl<'a, N: Notify + 'a, T: EventListener> ActionContext<'a, N, T> {
+    /// Current working directory of the shell.
+    ///
+    /// The directory reported by the shell through OSC 7 is preferred, since the foreground
+    /// process might not be the shell, or be running on a different machine.
+    fn working_directory(&self) -> Option<PathBuf> {
+        if let Some(working_directory) = self.terminal.working_directory() {
+            return Some(working_directory.to_owned());
+        }
+
+        #[cfg(not(windows))]
+        if let Ok(working_directory) = foreground_process_path(self.master_fd, self.shell_pid) {
+            return Some(working_directory);
+        }
+
+        None
+    }
+
+    fn update_search(&mut self) {
+        let regex = match self.search_state.regex() {
+            Some(regex) => regex,
+            None => return,
+        };
+
+        // Hide cursor while typing into the search bar.
+        if self.config.mouse.hide_when_typing {
+            self.display.window.set_mouse_visible(false);
+        }
+
+        if regex.is_empty() {
+            // Stop search if there's nothing to search for.
+            self.search_reset_state();
+            self.search_state.dfas = None;
+        } else {
+            // Create search dfas for the new regex string.
+            self.search_state.dfas = RegexSearch::new(regex).ok();
+
+            // Update search highlighting.
+            self.goto_match(MAX_SEARCH_WHILE_TYPING);
+        }
+
+        *self.dirty = true;
+    }
+
+    /// Reset terminal to the state before search was started.
+    fn search_reset_state(&mut self) {
+        // Unschedule pending timers.
+        let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
+        self.scheduler.unschedule(timer_id);
+
+        // Clear focused match.
+        self.search_state.focused_match = None;
+
+        // The viewport reset logic is only needed for vi mode, since without it our origin is
+        // always at the current display offset instead of at the vi cursor position which we need
+        // to recover to.
+        if !self.terminal.mode().contains(TermMode::VI) {
+            return;
+        }
+
+        // Reset display offset and cursor position.
+        self.terminal.vi_mode_cursor.point = self.search_state.origin;
+        self.terminal.scroll_display(Scroll::Delta(self.search_state.display_offset_delta));
+        self.search_state.display_offset_delta = 0;
+
+        *self.dirty = true;
+    }
+
+    /// Jump to the first regex match from the search origin.
+    fn goto_match(&mut self, mut limit: Option<usize>) {
+        let dfas = match &self.search_state.dfas {
+            Some(dfas) => dfas,
+            None => return,
+        };
+
+        // Limit search only when enough lines are available to run into the limit.
+        limit = limit.filter(|&limit| limit <= self.terminal.total_lines());
+
+        // Jump to the next match.
+        let direction = self.search_state.direction;
+        let clamped_origin = self.search_state.origin.grid_clamp(self.terminal, Boundary::Grid);
+        match self.terminal.search_next(dfas, clamped_origin, direction, Side::Left, limit) {
+            Some(regex_match) => {
+                let old_offset = self.terminal.grid().display_offset() as i32;
+
+                if self.terminal.mode().contains(TermMode::VI) {
+                    // Move vi cursor to the start of the match.
+                    self.terminal.vi_goto_point(*regex_match.start());
+                } else {
+                    // Select the match when vi mode is not active.
+                    self.terminal.scroll_to_point(*regex_match.start());
+                }
+
+                // Update the focused match.
+                self.search_state.focused_match = Some(regex_match);
+
+                // Store number of lines the viewport had to be moved.
+                let display_offset = self.terminal.grid().display_offset();
+                self.search_state.display_offset_delta += old_offset - display_offset as i32;
+
+                // Since we found a result, we require no delayed re-search.
+                let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
+                self.scheduler.unschedule(timer_id);
+            },
+            // Reset viewport only when we know there is no match, to prevent unnecessary jumping.
+            None if limit.is_none() => self.search_reset_state(),
+            None => {
+                // Schedule delayed search if we ran into our search limit.
+                let timer_id = TimerId::new(Topic::DelayedSearch, self.display.window.id());
+                if !self.scheduler.scheduled(timer_id) {
+                    let event = Event::new(EventType::SearchNext, self.display.window.id());
+                    self.scheduler.schedule(event, TYPING_SEARCH_DELAY, false, timer_id);
+                }
+
+                // Clear focused match.
+                self.search_state.focused_match = None;
+            },
+        }
+
+        *self.dirty = true;
+    }
+
+    /// Cleanup the search state.
+    fn exit_search(&mut self) {
+        let vi_mode = self.terminal.mode().contains(TermMode::VI);
+        self.window().set_ime_allowed(!vi_mode);
+
+        self.display.pending_update.dirty = true;
+        self.search_state.history_index = None;
+
+        // Clear focused match.
+        self.search_state.focused_match = None;
+    }
+
+    /// Run the notification command for a desktop notification.
+    ///
+    /// Notifications are throttled, while waiting for the next one to be shown only the latest
+    /// notification is kept.
+    fn show_notification(&mut self, notification: Notification) {
+        let config = &self.config.notification;
+        let command = match &config.command {
+            Some(command) if !config.only_when_unfocused || !self.terminal.is_focused => command,
+            _ => return,
+        };
+
+        let window_id = self.display.window.id();
+        let timer_id = TimerId::new(Topic::Notification, window_id);
+        if let Some(timer) = self.scheduler.unschedule(timer_id) {
+            let event = Event::new(TerminalEvent::Notification(notification).into(), window_id);
+            let delay = timer.deadline.saturating_duration_since(Instant::now());
+            self.scheduler.schedule(event, delay, false, timer_id);
+            return;
+        }
+
+        // Fall back to the window title for notifications without summary.
+        let title = if notification.title.is_empty() {
+            self.display.window.title().to_owned()
+        } else {
+            notification.title
+        };
+
+        // Separate the untrusted title and body from the configured options.
+        let mut args = command.args().to_vec();
+        args.push(String::from("--"));
+        args.push(title);
+        if !notification.body.is_empty() {
+            args.push(notification.body);
+        }
+
+        self.spawn_daemon(command.program(), &args);
+
+        let event = Event::new(EventType::NotificationTimeout, window_id);
+        self.scheduler.schedule(event, NOTIFICATION_INTERVAL, false, timer_id);
+    }
+
+    /// Show the child process exit status and offer to restart the shell.
+    fn child_exit(&mut self, status: Option<ExitStatus>) {
+        // Windows which aren't held are closed once the shell exits.
+        if !self.hold.should_hold(status) {
+            return;
+        }
+
+        *self.child_exited = true;
+
+        let (text, ty) = match status {
+            Some(status) if status.success() => {
+                (format!("Shell exited ({})", status), MessageType::Warning)
+            },
+            Some(status) => (format!("Shell exited ({})", status), MessageType::Error),
+            None => (String::from("Shell exited"), MessageType::Error),
+        };
+
+        let mut message = Message::new(format!("{}, press Enter to restart it", text), ty);
+        message.set_target(CHILD_EXIT_MESSAGE_TARGET.to_owned());
+        self.message_buffer.push(message);
+        self.display.pending_update.dirty = true;
+    }
+
+    /// Apply a window state change requested by the terminal.
+    fn window_operation(&mut self, operation: WindowOperation) {
+        let permission = match operation {
+            WindowOperation::Iconify | WindowOperation::Deiconify => {
+                WindowOperationPermission::Iconify
+            },
+            WindowOperation::Raise | WindowOperation::Lower => WindowOperationPermission::Raise,
+            WindowOperation::Maximize(_) => WindowOperationPermission::Maximize,
+            WindowOperation::Fullscreen(_) | WindowOperation::ToggleFullscreen => {
+                WindowOperationPermission::Fullscreen
+            },
+            WindowOperation::ResizePixels { .. } | WindowOperation::ResizeChars { .. } => {
+                WindowOperationPermission::Resize
+            },
+        };
+
+        // Prevent terminal output from changing the window unless explicitly allowed.
+        if !self.config.window.allowed_operations.contains(&permission) {
+            debug!("Ignoring window operation {:?}", operation);
+            return;
+        }
+
+        let size_info = self.display.size_info;
+        let window = &self.display.window;
+        match operation {
+            WindowOperation::Iconify => window.set_minimized(true),
+            WindowOperation::Deiconify => window.set_minimized(false),
+            WindowOperation::Raise => window.focus_window(),
+            WindowOperation::Lower => debug!("Lowering windows is not supported"),
+            WindowOperation::Maximize(maximized) => window.set_maximized(maximized),
+            WindowOperation::Fullscreen(fullscreen) => window.set_fullscreen(fullscreen),
+            WindowOperation::ToggleFullscreen => window.toggle_fullscreen(),
+            WindowOperation::ResizePixels { width, height } => {
+                self.resize_text_area(f32::from(width), f32::from(height));
+            },
+            WindowOperation::ResizeChars { columns, lines } => {
+                let width = f32::from(columns) * size_info.cell_width();
+                let height = f32::from(lines) * size_info.cell_height();
+                self.resize_text_area(width, height);
+            },
+        }
+    }
+
+    /// Resize the window to fit a text area of the specified size in pixels.
+    ///
+    /// A dimension of zero keeps the current size of the text area.
+    fn resize_text_area(&mut self, width: f32, height: f32) {
+        let size_info = self.display.size_info;
+        let width =
+            if width == 0. { size_info.columns() as f32 * size_info.cell_width() } else { width };
+        let height = if height == 0. {
+            size_info.screen_lines() as f32 * size_info.cell_height()
+        } else {
+            height
+        };
+
+        let scale_factor = self.display.window.scale_factor as f32;
+        let padding = self.config.window.padding(scale_factor);
+        let width = padding.0.mul_add(2., width).floor();
+        let height = padding.1.mul_add(2., height).floor();
+
+        self.display.window.set_inner_size(PhysicalSize::new(width as u32, height as u32));
+    }
+
     /// Update the cursor blinking state.
     fn update_cursor_blinking(&mut self) {
-        let cursor_style = self.config.terminal_config.cursor.style;
+        let mut cursor_style = self.config.terminal_config.cursor.style;
+        let vi_mode = self.terminal.mode().contains(TermMode::VI);
+        if vi_mode {
+            cursor_style = self.config.terminal_config.cursor.vi_mode_style.unwrap_or(cursor_style);
+        }
 
         // Check terminal cursor style.
         let terminal_blinking = self.terminal.cursor_style().blinking;
         let mut blinking = cursor_style.blinking_override().unwrap_or(terminal_blinking);
-        blinking &= (self.terminal().mode().contains(TermMode::SHOW_CURSOR))
+        blinking &= (vi_mode || self.terminal().mode().contains(TermMode::SHOW_CURSOR))
             && self.display().ime.preedit().is_none();
 
         // Update cursor blinking state.
@@ -690,6 +1400,7 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                     self.ctx.display.window.has_frame.store(true, Ordering::Relaxed);
                 },
                 EventType::Scroll(scroll) => self.ctx.scroll(scroll),
+                EventType::SearchNext => self.ctx.goto_match(None),
                 EventType::BlinkCursor => {
                     self.ctx.display.cursor_hidden ^= true;
                     *self.ctx.dirty = true;
@@ -720,6 +1431,24 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                         }
                     },
                     TerminalEvent::Wakeup => *self.ctx.dirty = true,
+                    TerminalEvent::Bell => {
+                        // Set window urgency.
+                        if self.ctx.terminal.mode().contains(TermMode::URGENCY_HINTS) {
+                            let focused = self.ctx.terminal.is_focused;
+                            self.ctx.window().set_urgent(!focused);
+                        }
+
+                        // Ring visual bell.
+                        self.ctx.display.visual_bell.ring();
+
+                        // Execute bell command.
+                        if let Some(bell_command) = &self.ctx.config.bell.command {
+                            self.ctx.spawn_daemon(bell_command.program(), bell_command.args());
+                        }
+                    },
+                    TerminalEvent::Notification(notification) => {
+                        self.ctx.show_notification(notification);
+                    },
                     TerminalEvent::ClipboardStore(clipboard_type, content) => {
                         if self.ctx.terminal.is_focused {
                             self.ctx.clipboard.store(clipboard_type, content);
@@ -740,15 +1469,55 @@ impl input::Processor<EventProxy, ActionContext<'_, Notifier, EventProxy>> {
                         let text = format(self.ctx.size_info().into());
                         self.ctx.write_to_pty(text.into_bytes());
                     },
+                    TerminalEvent::ScreenSizeRequest(format) => {
+                        let size = match self.ctx.display.window.current_monitor() {
+                            Some(monitor) => monitor.size(),
+                            None => self.ctx.display.window.inner_size(),
+                        };
+                        let text = format(size.width, size.height);
+                        self.ctx.write_to_pty(text.into_bytes());
+                    },
+                    TerminalEvent::WindowPositionRequest(format) => {
+                        let position = self.ctx.display.window.outer_position().unwrap_or_default();
+                        let text = format(position.x, position.y);
+                        self.ctx.write_to_pty(text.into_bytes());
+                    },
+                    TerminalEvent::TitleRequest(format) => {
+                        // Report an empty title unless title reports are explicitly allowed.
+                        let window_config = &self.ctx.config.window;
+                        let title = if window_config
+                            .allowed_operations
+                            .contains(&WindowOperationPermission::ReportTitle)
+                        {
+                            self.ctx.display.window.title()
+                        } else {
+                            ""
+                        };
+                        let text = format(title);
+                        self.ctx.write_to_pty(text.into_bytes());
+                    },
+                    TerminalEvent::WindowOperation(operation) => {
+                        self.ctx.window_operation(operation)
+                    },
                     TerminalEvent::PtyWrite(text) => self.ctx.write_to_pty(text.into_bytes()),
                     TerminalEvent::MouseCursorDirty => self.reset_mouse_cursor(),
+                    TerminalEvent::ChildExit(status) => self.ctx.child_exit(status),
                     TerminalEvent::Exit => (),
                     TerminalEvent::CursorBlinkingChange => self.ctx.update_cursor_blinking(),
+                    TerminalEvent::StatusLineChange => {
+                        self.ctx.display.pending_update.dirty = true;
+                    },
                 },
                 #[cfg(unix)]
-                EventType::IpcConfig(_) => (),
-                EventType::ConfigReload(_) | EventType::CreateWindow(_) | EventType::Message(_) => {
-                },
+                EventType::IpcConfig(_)
+                | EventType::IpcRequest(..)
+                | EventType::IpcSubscribe(_) => (),
+                EventType::ConfigReload(_)
+                | EventType::CreateWindow(_)
+                | EventType::RestartShell { .. }
+                | EventType::RespawnShell { .. }
+                | EventType::NotificationTimeout
+                | EventType::Message(_) => {},
             },
             WinitEvent::RedrawRequested(_) => *self.ctx.dirty = true,
             WinitEvent::WindowEvent { event, .. } => {
@@ -871,6 +1640,8 @@ pub struct Processor {
     #[cfg(all(feature = "wayland", not(any(target_os = "macos", windows))))]
     wayland_event_queue: Option<EventQueue>,
     windows: HashMap<WindowId, WindowContext>,
+    #[cfg(unix)]
+    ipc_subscribers: IpcSubscribers,
     cli_options: CliOptions,
     config: Rc<UiConfig>,
 }
@@ -893,6 +1664,8 @@ impl Processor {
 
         Processor {
             windows: HashMap::new(),
+            #[cfg(unix)]
+            ipc_subscribers: Default::default(),
             config: Rc::new(config),
             cli_options,
             #[cfg(all(feature = "wayland", not(any(target_os = "macos", windows))))]
@@ -919,6 +1692,10 @@ impl Processor {
             self.wayland_event_queue.as_ref(),
         )?;
 
+        #[cfg(unix)]
+        self.ipc_subscribers
+            .publish(IpcEvent::WindowCreated { window_id: window_context.id().into() });
+
         self.windows.insert(window_context.id(), window_context);
 
         Ok(())
@@ -941,6 +1718,10 @@ impl Processor {
             self.wayland_event_queue.as_ref(),
         )?;
 
+        #[cfg(unix)]
+        self.ipc_subscribers
+            .publish(IpcEvent::WindowCreated { window_id: window_context.id().into() });
+
         self.windows.insert(window_context.id(), window_context);
         Ok(())
     }
@@ -1014,6 +1795,10 @@ impl Processor {
                     // Unschedule pending events.
                     scheduler.unschedule_window(window_context.id());
 
+                    #[cfg(unix)]
+                    self.ipc_subscribers
+                        .publish(IpcEvent::WindowDestroyed { window_id: window_id.into() });
+
                     // Shutdown if no more terminals are open.
                     if self.windows.is_empty() {
                         // Write ref tests of last window to disk.
@@ -1036,6 +1821,11 @@ impl Processor {
 
                     // Dispatch event to all windows.
                     for window_context in self.windows.values_mut() {
+                        // Keep track of the title to notify subscribers about changes.
+                        #[cfg(unix)]
+                        let old_title = (!self.ipc_subscribers.is_empty())
+                            .then(|| window_context.display.window.title().to_owned());
+
                         window_context.handle_event(
                             event_loop,
                             &proxy,
@@ -1043,6 +1833,16 @@ impl Processor {
                             &mut scheduler,
                             WinitEvent::RedrawEventsCleared,
                         );
+
+                        #[cfg(unix)]
+                        if let Some(old_title) = old_title {
+                            let title = window_context.display.window.title();
+                            if title != old_title {
+                                let window_id = window_context.id().into();
+                                let title = title.to_owned();
+                                self.ipc_subscribers.publish(IpcEvent::Title { window_id, title });
+                            }
+                        }
                     }
 
                     // Update the scheduler after event processing to ensure
@@ -1069,6 +1869,9 @@ impl Processor {
                         for window_context in self.windows.values_mut() {
                             window_context.update_config(self.config.clone());
                         }
+
+                        #[cfg(unix)]
+                        self.ipc_subscribers.publish(IpcEvent::ConfigReload { path });
                     }
                 },
                 // Process IPC config update.
@@ -1085,6 +1888,41 @@ impl Processor {
                         window_context.update_ipc_config(self.config.clone(), ipc_config.clone());
                     }
                 },
+                // Stream events to IPC subscribers.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::IpcSubscribe(stream), ..
+                }) => self.ipc_subscribers.subscribe(stream),
+                // Process IPC requests and reply to the client.
+                #[cfg(unix)]
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::IpcRequest(message, reply_tx),
+                    ..
+                }) => {
+                    let reply = self.handle_ipc_request(&proxy, message);
+                    let _ = reply_tx.send(reply);
+                },
+                // Replace the shell of a terminal window.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::RestartShell { keep_scrollback },
+                    window_id: Some(window_id),
+                }) => {
+                    if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        window_context.restart_shell(proxy.clone(), keep_scrollback);
+                    }
+                },
+                // Spawn a new shell once the old one has been shut down.
+                WinitEvent::UserEvent(Event {
+                    payload: EventType::RespawnShell { keep_scrollback },
+                    window_id: Some(window_id),
+                }) => {
+                    if let Some(window_context) = self.windows.get_mut(&window_id) {
+                        let proxy = proxy.clone();
+                        if let Err(err) = window_context.respawn_shell(proxy, keep_scrollback) {
+                            error!("Could not restart shell: {}", err);
+                        }
+                    }
+                },
                 // Create a new terminal window.
                 WinitEvent::UserEvent(Event {
                     payload: EventType::CreateWindow(options), ..
@@ -1116,6 +1954,9 @@ impl Processor {
                 WinitEvent::WindowEvent { window_id, .. }
                 | WinitEvent::UserEvent(Event { window_id: Some(window_id), .. })
                 | WinitEvent::RedrawRequested(window_id) => {
+                    #[cfg(unix)]
+                    self.publish_ipc_event(window_id, &event);
+
                     if let Some(window_context) = self.windows.get_mut(&window_id) {
                         window_context.handle_event(
                             event_loop,
@@ -1137,6 +1978,109 @@ impl Processor {
         }
     }
 
+    /// Handle IPC requests which require access to the terminal windows.
+    #[cfg(unix)]
+    fn handle_ipc_request(
+        &mut self,
+        proxy: &EventLoopProxy<Event>,
+        message: SocketMessage,
+    ) -> SocketReply {
+        let window_id = match &message {
+            SocketMessage::ListWindows => {
+                let mut windows: Vec<_> = self.windows.values().map(WindowContext::info).collect();
+                windows.sort_unstable_by_key(|window| window.id);
+                return SocketReply::Windows(windows);
+            },
+            SocketMessage::FocusWindow(window) | SocketMessage::CloseWindow(window) => {
+                window.window_id
+            },
+            SocketMessage::ResizeWindow(resize) => resize.window.window_id,
+            SocketMessage::SendText(send_text) => send_text.window.window_id,
+            SocketMessage::GetText(get_text) => get_text.window.window_id,
+            SocketMessage::RestartShell(restart) => restart.window.window_id,
+            // These are handled directly by the IPC socket thread or the event loop.
+            SocketMessage::CreateWindow(_)
+            | SocketMessage::Config(_)
+            | SocketMessage::Subscribe => return SocketReply::Ok,
+        };
+
+        let window_context = match self.ipc_window(window_id) {
+            Ok(window_context) => window_context,
+            Err(err) => return SocketReply::Error(err),
+        };
+
+        match message {
+            SocketMessage::FocusWindow(_) => window_context.display.window.focus_window(),
+            SocketMessage::CloseWindow(_) => {
+                let event = Event::new(TerminalEvent::Exit.into(), window_context.id());
+                let _ = proxy.send_event(event);
+            },
+            SocketMessage::ResizeWindow(resize) => {
+                let (columns, lines) = (resize.columns, resize.lines);
+                if columns == 0 || lines == 0 {
+                    return SocketReply::Error(IpcError::InvalidDimensions { columns, lines });
+                }
+
+                let dimensions = WindowDimensions { columns: Column(columns), lines };
+                window_context.display.resize_to_dimensions(&self.config, dimensions);
+            },
+            SocketMessage::SendText(send_text) => {
+                window_context.write_to_pty(send_text.text.into_bytes());
+            },
+            SocketMessage::GetText(get_text) => {
+                return SocketReply::Text(window_context.text(get_text.scrollback));
+            },
+            SocketMessage::RestartShell(restart) => {
+                let event = EventType::RestartShell { keep_scrollback: restart.keep_scrollback };
+                let _ = proxy.send_event(Event::new(event, window_context.id()));
+            },
+            SocketMessage::ListWindows
+            | SocketMessage::CreateWindow(_)
+            | SocketMessage::Config(_)
+            | SocketMessage::Subscribe => (),
+        }
+
+        SocketReply::Ok
+    }
+
+    /// Notify IPC subscribers about window events.
+    #[cfg(unix)]
+    fn publish_ipc_event(&mut self, window_id: WindowId, event: &WinitEvent<'_, Event>) {
+        let window_id = window_id.into();
+        let event = match event {
+            WinitEvent::WindowEvent { event: WindowEvent::Focused(focused), .. } => {
+                IpcEvent::Focus { window_id, focused: *focused }
+            },
+            WinitEvent::UserEvent(Event { payload, .. }) => match payload {
+                EventType::Terminal(TerminalEvent::Bell) => IpcEvent::Bell { window_id },
+                EventType::Terminal(TerminalEvent::ChildExit(status)) => {
+                    let code = status.and_then(|status| status.code());
+                    let signal = status.and_then(|status| status.signal());
+                    IpcEvent::ChildExit { window_id, code, signal }
+                },
+                _ => return,
+            },
+            _ => return,
+        };
+
+        self.ipc_subscribers.publish(event);
+    }
+
+    /// Get the window targeted by an IPC request.
+    ///
+    /// Without an explicit window ID, this only succeeds if there is exactly one window.
+    #[cfg(unix)]
+    fn ipc_window(&self, window_id: Option<i128>) -> Result<&WindowContext, IpcError> {
+        match window_id {
+            Some(id) => u64::try_from(id)
+                .ok()
+                .and_then(|window_id| self.windows.get(&WindowId::from(window_id)))
+                .ok_or(IpcError::WindowNotFound(id)),
+            None if self.windows.len() == 1 => Ok(self.windows.values().next().unwrap()),
+            None => Err(IpcError::AmbiguousWindow),
+        }
+    }
+
     /// Check if an event is irrelevant and can be skipped.
     fn skip_event(event: &WinitEvent<'_, Event>) -> bool {
         match event {
@@ -5,32 +5,36 @@
 
 use std::borrow::Cow;
 use std::cmp::{max, min, Ordering};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::mem;
+use std::ops::RangeInclusive;
 use std::time::{Duration, Instant};
 
 use winit::dpi::PhysicalPosition;
 use winit::event::{
     ElementState, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
-    Touch as TouchEvent, TouchPhase,
+    Touch as TouchEvent, TouchPhase, VirtualKeyCode,
 };
 use winit::event_loop::EventLoopWindowTarget;
 #[cfg(target_os = "macos")]
 use winit::platform::macos::{EventLoopWindowTargetExtMacOS, OptionAsAlt};
 use winit::window::CursorIcon;
 
-use alacritty_terminal::ansi::{ClearMode, Handler};
+use alacritty_terminal::ansi::{ClearMode, Handler, ModifyOtherKeys, StatusLineType};
 use alacritty_terminal::event::EventListener;
 use alacritty_terminal::grid::{Dimensions, Scroll};
-use alacritty_terminal::index::{Column, Point, Side};
+use alacritty_terminal::index::{Boundary, Column, Direction, Line, Point, Side};
 use alacritty_terminal::selection::SelectionType;
+use alacritty_terminal::term::search::Match;
 use alacritty_terminal::term::{ClipboardType, Term, TermMode};
+use alacritty_terminal::vi_mode::ViMotion;
 
 use crate::clipboard::Clipboard;
-use crate::config::{Action, BindingMode, Key, MouseAction, UiConfig};
+use crate::config::{Action, BindingMode, Key, MouseAction, SearchAction, UiConfig, ViAction};
+use crate::display::hint::HintMatch;
 use crate::display::window::Window;
 use crate::display::{Display, SizeInfo};
 use crate::event::{ClickState, Event, EventType, Mouse, TouchPurpose, TouchZoom};
@@ -72,6 +76,7 @@ pub trait ActionContext<T: EventListener> {
     fn start_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn toggle_selection(&mut self, _ty: SelectionType, _point: Point, _side: Side) {}
     fn update_selection(&mut self, _point: Point, _side: Side) {}
+    fn select_lines(&mut self, _lines: RangeInclusive<Line>) {}
     fn clear_selection(&mut self) {}
     fn selection_is_empty(&self) -> bool;
     fn mouse_mut(&mut self) -> &mut Mouse;
@@ -79,6 +84,7 @@ pub trait ActionContext<T: EventListener> {
     fn touch_purpose(&mut self) -> &mut TouchPurpose;
     fn received_count(&mut self) -> &mut usize;
     fn suppress_chars(&mut self) -> &mut bool;
+    fn keyboard(&mut self) -> &mut KeyboardState;
     fn modifiers(&mut self) -> &mut ModifiersState;
     fn scroll(&mut self, _scroll: Scroll) {}
     fn window(&mut self) -> &mut Window;
@@ -96,7 +102,26 @@ pub trait ActionContext<T: EventListener> {
     fn mouse_mode(&self) -> bool;
     fn clipboard_mut(&mut self) -> &mut Clipboard;
     fn scheduler_mut(&mut self) -> &mut Scheduler;
+    fn toggle_vi_mode(&mut self) {}
     fn on_typing_start(&mut self) {}
+    fn search_next(&mut self, origin: Point, direction: Direction, side: Side) -> Option<Match>;
+    fn search_direction(&self) -> Direction;
+    fn search_active(&self) -> bool;
+    fn start_search(&mut self, _direction: Direction) {}
+    fn confirm_search(&mut self) {}
+    fn cancel_search(&mut self) {}
+    fn search_input(&mut self, _c: char) {}
+    fn search_pop_word(&mut self) {}
+    fn search_history_previous(&mut self) {}
+    fn search_history_next(&mut self) {}
+    fn advance_search_origin(&mut self, _direction: Direction) {}
+    fn hint_input(&mut self, _character: char) {}
+    fn trigger_hint(&mut self, _hint: &HintMatch) {}
+    fn copy_hyperlink(&mut self) {}
+    fn child_exited(&self) -> bool {
+        false
+    }
+    fn restart_shell(&mut self, _keep_scrollback: bool) {}
     fn expand_selection(&mut self) {}
     fn paste(&mut self, _text: &str) {}
     fn spawn_daemon<I, S>(&self, _program: &str, _args: I)
@@ -107,6 +132,43 @@ pub trait ActionContext<T: EventListener> {
     }
 }
 
+impl Action {
+    fn toggle_selection<T, A>(ctx: &mut A, ty: SelectionType)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        ctx.toggle_selection(ty, ctx.terminal().vi_mode_cursor.point, Side::Left);
+
+        // Make sure initial selection is not empty.
+        if let Some(selection) = &mut ctx.terminal_mut().selection {
+            selection.include_all();
+        }
+    }
+
+    fn scroll_to_prompt<T, A>(ctx: &mut A, direction: Direction)
+    where
+        A: ActionContext<T>,
+        T: EventListener,
+    {
+        let term = ctx.terminal();
+        let topmost_visible_line = Line(-(term.grid().display_offset() as i32));
+        let line = match term.prompt_line(topmost_visible_line, direction) {
+            Some(line) => line,
+            None => return,
+        };
+
+        ctx.scroll(Scroll::Delta(topmost_visible_line.0 - line.0));
+
+        // Move vi mode cursor.
+        let term = ctx.terminal_mut();
+        if term.mode().contains(TermMode::VI) {
+            term.vi_mode_cursor.point = Point::new(line, Column(0));
+            ctx.mark_dirty();
+        }
+    }
+}
+
 trait Execute<T: EventListener> {
     fn execute<A: ActionContext<T>>(&self, ctx: &mut A);
 }
@@ -122,8 +184,118 @@ impl<T: EventListener> Execute<T> for Action {
                 ctx.write_to_pty(s.clone().into_bytes())
             },
             Action::Command(program) => ctx.spawn_daemon(program.program(), program.args()),
+            Action::Hint(hint) => {
+                ctx.display().hint_state.start(hint.clone());
+                ctx.mark_dirty();
+            },
+            Action::ToggleViMode => {
+                ctx.on_typing_start();
+                ctx.toggle_vi_mode()
+            },
+            Action::ViMotion(motion) => {
+                ctx.on_typing_start();
+                ctx.terminal_mut().vi_motion(*motion);
+                ctx.mark_dirty();
+            },
+            Action::Vi(ViAction::ToggleNormalSelection) => {
+                Self::toggle_selection(ctx, SelectionType::Simple);
+            },
+            Action::Vi(ViAction::ToggleLineSelection) => {
+                Self::toggle_selection(ctx, SelectionType::Lines);
+            },
+            Action::Vi(ViAction::ToggleBlockSelection) => {
+                Self::toggle_selection(ctx, SelectionType::Block);
+            },
+            Action::Vi(ViAction::ToggleSemanticSelection) => {
+                Self::toggle_selection(ctx, SelectionType::Semantic);
+            },
+            Action::Vi(ViAction::CenterAroundViCursor) => {
+                let term = ctx.terminal();
+                let display_offset = term.grid().display_offset() as i32;
+                let target = -display_offset + term.screen_lines() as i32 / 2 - 1;
+                let line = term.vi_mode_cursor.point.line;
+                let scroll_lines = target - line.0;
+
+                ctx.scroll(Scroll::Delta(scroll_lines));
+            },
+            Action::Vi(ViAction::Open) => {
+                let hint = ctx.display().vi_highlighted_hint.take();
+                if let Some(hint) = &hint {
+                    ctx.mouse_mut().block_hint_launcher = false;
+                    ctx.trigger_hint(hint);
+                }
+                ctx.display().vi_highlighted_hint = hint;
+            },
+            Action::Vi(ViAction::SearchNext) => {
+                ctx.on_typing_start();
+
+                let terminal = ctx.terminal();
+                let direction = ctx.search_direction();
+                let vi_point = terminal.vi_mode_cursor.point;
+                let origin = match direction {
+                    Direction::Right => vi_point.add(terminal, Boundary::None, 1),
+                    Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
+                };
+
+                if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
+                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.mark_dirty();
+                }
+            },
+            Action::Vi(ViAction::SearchPrevious) => {
+                ctx.on_typing_start();
+
+                let terminal = ctx.terminal();
+                let direction = ctx.search_direction().opposite();
+                let vi_point = terminal.vi_mode_cursor.point;
+                let origin = match direction {
+                    Direction::Right => vi_point.add(terminal, Boundary::None, 1),
+                    Direction::Left => vi_point.sub(terminal, Boundary::None, 1),
+                };
+
+                if let Some(regex_match) = ctx.search_next(origin, direction, Side::Left) {
+                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.mark_dirty();
+                }
+            },
+            Action::Vi(ViAction::SearchStart) => {
+                let terminal = ctx.terminal();
+                let origin = terminal.vi_mode_cursor.point.sub(terminal, Boundary::None, 1);
+
+                if let Some(regex_match) = ctx.search_next(origin, Direction::Left, Side::Left) {
+                    ctx.terminal_mut().vi_goto_point(*regex_match.start());
+                    ctx.mark_dirty();
+                }
+            },
+            Action::Vi(ViAction::SearchEnd) => {
+                let terminal = ctx.terminal();
+                let origin = terminal.vi_mode_cursor.point.add(terminal, Boundary::None, 1);
+
+                if let Some(regex_match) = ctx.search_next(origin, Direction::Right, Side::Right) {
+                    ctx.terminal_mut().vi_goto_point(*regex_match.end());
+                    ctx.mark_dirty();
+                }
+            },
+            Action::Search(SearchAction::SearchFocusNext) => {
+                ctx.advance_search_origin(ctx.search_direction());
+            },
+            Action::Search(SearchAction::SearchFocusPrevious) => {
+                let direction = ctx.search_direction().opposite();
+                ctx.advance_search_origin(direction);
+            },
+            Action::Search(SearchAction::SearchConfirm) => ctx.confirm_search(),
+            Action::Search(SearchAction::SearchCancel) => ctx.cancel_search(),
+            Action::Search(SearchAction::SearchClear) => {
+                let direction = ctx.search_direction();
+                ctx.cancel_search();
+                ctx.start_search(direction);
+            },
+            Action::Search(SearchAction::SearchDeleteWord) => ctx.search_pop_word(),
+            Action::Search(SearchAction::SearchHistoryPrevious) => ctx.search_history_previous(),
+            Action::Search(SearchAction::SearchHistoryNext) => ctx.search_history_next(),
             Action::Mouse(MouseAction::ExpandSelection) => ctx.expand_selection(),
             Action::Copy => ctx.copy_selection(ClipboardType::Clipboard),
+            Action::CopyHyperlink => ctx.copy_hyperlink(),
             #[cfg(not(any(target_os = "macos", windows)))]
             Action::CopySelection => ctx.copy_selection(ClipboardType::Selection),
             Action::ClearSelection => ctx.clear_selection(),
@@ -151,37 +323,75 @@ impl<T: EventListener> Execute<T> for Action {
             Action::DecreaseFontSize => ctx.change_font_size(FONT_SIZE_STEP * -1.),
             Action::ResetFontSize => ctx.reset_font_size(),
             Action::ScrollPageUp => {
+                // Move vi mode cursor.
+                let term = ctx.terminal_mut();
+                let scroll_lines = term.screen_lines() as i32;
+                term.vi_mode_cursor = term.vi_mode_cursor.scroll(term, scroll_lines);
+
                 ctx.scroll(Scroll::PageUp);
             },
             Action::ScrollPageDown => {
+                // Move vi mode cursor.
+                let term = ctx.terminal_mut();
+                let scroll_lines = -(term.screen_lines() as i32);
+                term.vi_mode_cursor = term.vi_mode_cursor.scroll(term, scroll_lines);
+
                 ctx.scroll(Scroll::PageDown);
             },
             Action::ScrollHalfPageUp => {
                 // Move vi mode cursor.
                 let term = ctx.terminal_mut();
                 let scroll_lines = term.screen_lines() as i32 / 2;
+                term.vi_mode_cursor = term.vi_mode_cursor.scroll(term, scroll_lines);
+
                 ctx.scroll(Scroll::Delta(scroll_lines));
             },
             Action::ScrollHalfPageDown => {
                 // Move vi mode cursor.
                 let term = ctx.terminal_mut();
                 let scroll_lines = -(term.screen_lines() as i32 / 2);
+                term.vi_mode_cursor = term.vi_mode_cursor.scroll(term, scroll_lines);
+
                 ctx.scroll(Scroll::Delta(scroll_lines));
             },
             Action::ScrollLineUp => ctx.scroll(Scroll::Delta(1)),
             Action::ScrollLineDown => ctx.scroll(Scroll::Delta(-1)),
             Action::ScrollToTop => {
                 ctx.scroll(Scroll::Top);
+
+                // Move vi mode cursor.
+                let topmost_line = ctx.terminal().topmost_line();
+                ctx.terminal_mut().vi_mode_cursor.point.line = topmost_line;
+                ctx.terminal_mut().vi_motion(ViMotion::FirstOccupied);
                 ctx.mark_dirty();
             },
             Action::ScrollToBottom => {
                 ctx.scroll(Scroll::Bottom);
+
+                // Move vi mode cursor.
+                let term = ctx.terminal_mut();
+                term.vi_mode_cursor.point.line = term.bottommost_line();
+
+                // Move to beginning twice, to always jump across linewraps.
+                term.vi_motion(ViMotion::FirstOccupied);
+                term.vi_motion(ViMotion::FirstOccupied);
                 ctx.mark_dirty();
             },
+            Action::ScrollToPreviousPrompt => Self::scroll_to_prompt(ctx, Direction::Left),
+            Action::ScrollToNextPrompt => Self::scroll_to_prompt(ctx, Direction::Right),
+            Action::SelectLastCommandOutput => {
+                if let Some(lines) = ctx.terminal().last_command_output() {
+                    ctx.select_lines(lines);
+                }
+            },
+            Action::SearchForward => ctx.start_search(Direction::Right),
+            Action::SearchBackward => ctx.start_search(Direction::Left),
             Action::ClearHistory => ctx.terminal_mut().clear_screen(ClearMode::Saved),
             Action::ClearLogNotice => ctx.pop_message(),
             Action::SpawnNewInstance => ctx.spawn_new_instance(),
             Action::CreateNewWindow => ctx.create_new_window(),
+            // Keep the output of exited shells, since it might explain why they exited.
+            Action::RestartShell => ctx.restart_shell(ctx.child_exited()),
             Action::ReceiveChar | Action::None => (),
         }
     }
@@ -206,6 +416,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
         let display_offset = self.ctx.terminal().grid().display_offset();
         let old_point = self.ctx.mouse().point(&size_info, display_offset);
+        let old_position = (self.ctx.mouse().x, self.ctx.mouse().y);
 
         let x = x.clamp(0, size_info.width() as i32 - 1) as usize;
         let y = y.clamp(0, size_info.height() as i32 - 1) as usize;
@@ -218,8 +429,12 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         let point = self.ctx.mouse().point(&size_info, display_offset);
         let cell_changed = old_point != point;
 
+        // With pixel mouse reports every change in position is reported as motion.
+        let pixel_mouse = self.ctx.terminal().mode().contains(TermMode::SGR_PIXELS_MOUSE);
+        let position_changed = cell_changed || (pixel_mouse && old_position != (x, y));
+
         // If the mouse hasn't changed cells, do nothing.
-        if !cell_changed
+        if !position_changed
             && self.ctx.mouse().cell_side == cell_side
             && self.ctx.mouse().inside_text_area == inside_text_area
         {
@@ -242,7 +457,7 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         if (lmb_pressed || rmb_pressed) && (self.ctx.modifiers().shift() || !self.ctx.mouse_mode())
         {
             self.ctx.update_selection(point, cell_side);
-        } else if cell_changed
+        } else if position_changed
             && self.ctx.terminal().mode().intersects(TermMode::MOUSE_MOTION | TermMode::MOUSE_DRAG)
         {
             if lmb_pressed {
@@ -302,8 +517,14 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         }
 
         // Report mouse events.
-        if self.ctx.terminal().mode().contains(TermMode::SGR_MOUSE) {
+        let mode = *self.ctx.terminal().mode();
+        if mode.contains(TermMode::SGR_MOUSE) {
             self.sgr_mouse_report(point, button + mods, state);
+        } else if mode.contains(TermMode::SGR_PIXELS_MOUSE) {
+            self.sgr_pixels_mouse_report(button + mods, state);
+        } else if mode.contains(TermMode::URXVT_MOUSE) {
+            let button = if state == ElementState::Released { 3 } else { button };
+            self.urxvt_mouse_report(point, button + mods);
         } else if let ElementState::Released = state {
             self.normal_mouse_report(point, 3 + mods);
         } else {
@@ -355,6 +576,30 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
         self.ctx.write_to_pty(msg.into_bytes());
     }
 
+    fn sgr_pixels_mouse_report(&mut self, button: u8, state: ElementState) {
+        let c = match state {
+            ElementState::Pressed => 'M',
+            ElementState::Released => 'm',
+        };
+
+        // Pixel coordinates are relative to the text area, excluding the padding.
+        let size_info = self.ctx.size_info();
+        let max_x = size_info.columns() * size_info.cell_width() as usize;
+        let max_y = size_info.screen_lines() * size_info.cell_height() as usize;
+        let x = self.ctx.mouse().x.saturating_sub(size_info.padding_x() as usize);
+        let y = self.ctx.mouse().y.saturating_sub(size_info.padding_y() as usize);
+        let x = min(x, max_x.saturating_sub(1));
+        let y = min(y, max_y.saturating_sub(1));
+
+        let msg = format!("\x1b[<{};{};{}{}", button, x + 1, y + 1, c);
+        self.ctx.write_to_pty(msg.into_bytes());
+    }
+
+    fn urxvt_mouse_report(&mut self, point: Point, button: u8) {
+        let msg = format!("\x1b[{};{};{}M", 32 + button, point.column + 1, point.line + 1);
+        self.ctx.write_to_pty(msg.into_bytes());
+    }
+
     fn on_mouse_press(&mut self, button: MouseButton) {
         // Handle mouse mode.
         if !self.ctx.modifiers().shift() && self.ctx.mouse_mode() {
@@ -426,10 +671,21 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             },
             ClickState::TripleClick => {
                 self.ctx.mouse_mut().block_hint_launcher = true;
-                self.ctx.start_selection(SelectionType::Lines, point, side);
+
+                // Select the entire output when clicking on the output of a command.
+                match self.ctx.terminal().command_output(point.line) {
+                    Some(lines) => self.ctx.select_lines(lines),
+                    None => self.ctx.start_selection(SelectionType::Lines, point, side),
+                }
             },
             ClickState::None => (),
         };
+
+        // Move vi mode cursor to mouse click position.
+        if self.ctx.terminal().mode().contains(TermMode::VI) {
+            self.ctx.terminal_mut().vi_mode_cursor.point = point;
+            self.ctx.mark_dirty();
+        }
     }
 
     fn on_mouse_release(&mut self, button: MouseButton) {
@@ -445,6 +701,13 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Trigger hints highlighted by the mouse.
+        let hint = self.ctx.display().highlighted_hint.take();
+        if let Some(hint) = hint.as_ref().filter(|_| button == MouseButton::Left) {
+            self.ctx.trigger_hint(hint);
+        }
+        self.ctx.display().highlighted_hint = hint;
+
         let timer_id = TimerId::new(Topic::SelectionScrolling, self.ctx.window().id());
         self.ctx.scheduler_mut().unschedule(timer_id);
 
@@ -734,12 +997,129 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Report text keys which didn't receive any text from the platform.
+        self.report_pending_key(None);
+
+        let mods = *self.ctx.modifiers();
         match input.state {
             ElementState::Pressed => {
                 *self.ctx.received_count() = 0;
-                self.process_key_bindings(input);
+
+                // Winit doesn't report repeats, so detect them from the last pressed key.
+                let keyboard = self.ctx.keyboard();
+                let event_type =
+                    if keyboard.pressed_key.replace(input.scancode) == Some(input.scancode) {
+                        KeyEventType::Repeat
+                    } else {
+                        KeyEventType::Press
+                    };
+
+                let key = input.virtual_keycode;
+                let sequence = key.and_then(|key| self.key_sequence(key, mods, event_type, None));
+                self.process_key_bindings(input, sequence.is_some());
+
+                // Releases of keys consumed by a binding aren't reported either.
+                if *self.ctx.suppress_chars() {
+                    self.ctx.keyboard().consumed_keys.insert(input.scancode);
+                    return;
+                }
+
+                match (key, sequence) {
+                    // Text keys are reported once the platform provides their text.
+                    (Some(key), Some(_)) if text_key(key).is_some() => {
+                        let pending =
+                            PendingKey { scancode: input.scancode, key, mods, event_type };
+                        self.ctx.keyboard().pending_key = Some(pending);
+                    },
+                    (_, Some(sequence)) => self.write_key_sequence(sequence),
+                    (_, None) => (),
+                }
+            },
+            ElementState::Released => {
+                *self.ctx.suppress_chars() = false;
+
+                let keyboard = self.ctx.keyboard();
+                if keyboard.pressed_key == Some(input.scancode) {
+                    keyboard.pressed_key = None;
+                }
+
+                if keyboard.consumed_keys.remove(&input.scancode) {
+                    return;
+                }
+
+                let text = keyboard.key_text.get(&input.scancode).copied();
+                let sequence = input
+                    .virtual_keycode
+                    .and_then(|key| self.key_sequence(key, mods, KeyEventType::Release, text));
+                if let Some(sequence) = sequence {
+                    self.ctx.write_to_pty(sequence.into_bytes());
+                }
+            },
+        }
+    }
+
+    /// Report the pending text key press, using the text provided by the platform.
+    ///
+    /// Returns `true` if an escape sequence was written for the key.
+    fn report_pending_key(&mut self, text: Option<char>) -> bool {
+        let keyboard = self.ctx.keyboard();
+        let pending = match keyboard.pending_key.take() {
+            Some(pending) => pending,
+            None => return false,
+        };
+
+        let text = keyboard.resolve_text(&pending, text);
+        match self.key_sequence(pending.key, pending.mods, pending.event_type, text) {
+            Some(sequence) => {
+                self.write_key_sequence(sequence);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Write the escape sequence of a key press.
+    fn write_key_sequence(&mut self, sequence: String) {
+        self.ctx.on_typing_start();
+        if self.ctx.terminal().grid().display_offset() != 0 {
+            self.ctx.scroll(Scroll::Bottom);
+        }
+        self.ctx.clear_selection();
+
+        self.ctx.write_to_pty(sequence.into_bytes());
+        *self.ctx.suppress_chars() = true;
+    }
+
+    /// Encode a key event with the kitty keyboard protocol or xterm's modifyOtherKeys.
+    ///
+    /// The unshifted and shifted `text` of text keys falls back to a US layout if it's unknown.
+    ///
+    /// Returns `None` when neither is enabled or the key keeps its legacy encoding.
+    fn key_sequence(
+        &mut self,
+        key: VirtualKeyCode,
+        mods: ModifiersState,
+        event_type: KeyEventType,
+        text: Option<(char, char)>,
+    ) -> Option<String> {
+        let mode = *self.ctx.terminal().mode();
+        if mode.contains(TermMode::VI)
+            || self.ctx.search_active()
+            || self.ctx.display().hint_state.active()
+        {
+            return None;
+        }
+
+        if mode.intersects(TermMode::KITTY_KEYBOARD_PROTOCOL) {
+            return kitty_key_sequence(mode, key, text, mods, event_type);
+        }
+
+        match event_type {
+            KeyEventType::Release => None,
+            _ => {
+                let modify_other_keys = self.ctx.terminal().modify_other_keys();
+                modify_other_keys_sequence(modify_other_keys, key, text, mods)
             },
-            ElementState::Released => *self.ctx.suppress_chars() = false,
         }
     }
 
@@ -764,6 +1144,10 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
     /// Process a received character.
     pub fn received_char(&mut self, c: char) {
+        if self.report_pending_key(Some(c)) {
+            return;
+        }
+
         let suppress_chars = *self.ctx.suppress_chars();
 
         // Don't insert chars when we have IME running.
@@ -771,8 +1155,19 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             return;
         }
 
+        // Handle hint selection over anything else.
+        if self.ctx.display().hint_state.active() && !suppress_chars {
+            self.ctx.hint_input(c);
+            return;
+        }
+
         // Pass keys to search and ignore them during `suppress_chars`.
-        if suppress_chars || self.ctx.terminal().mode().contains(TermMode::VI) {
+        let search_active = self.ctx.search_active();
+        if suppress_chars || search_active || self.ctx.terminal().mode().contains(TermMode::VI) {
+            if search_active && !suppress_chars {
+                self.ctx.search_input(c);
+            }
+
             return;
         }
 
@@ -811,9 +1206,14 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     /// Attempt to find a binding and execute its action.
     ///
     /// The provided mode, mods, and key must match what is allowed by a binding
-    /// for its action to be executed.
-    fn process_key_bindings(&mut self, input: KeyboardInput) {
-        let mode = BindingMode::new(self.ctx.terminal().mode());
+    /// for its action to be executed. Bindings sending escapes are skipped with
+    /// `skip_escapes`, since the key is reported with its own escape sequence.
+    fn process_key_bindings(&mut self, input: KeyboardInput, skip_escapes: bool) {
+        let mode = BindingMode::new(
+            self.ctx.terminal().mode(),
+            self.ctx.search_active(),
+            self.ctx.child_exited(),
+        );
         let mods = *self.ctx.modifiers();
         let mut suppress_chars = None;
 
@@ -827,11 +1227,17 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
             };
 
             if binding.is_triggered_by(mode, mods, &key) {
+                // Skipped escapes pass the key through, like the `ReceiveChar` action.
+                let skipped = skip_escapes && matches!(binding.action, Action::Esc(_));
+
                 // Pass through the key if any of the bindings has the `ReceiveChar` action.
-                *suppress_chars.get_or_insert(true) &= binding.action != Action::ReceiveChar;
+                *suppress_chars.get_or_insert(true) &=
+                    binding.action != Action::ReceiveChar && !skipped;
 
                 // Binding was triggered; run the action.
-                binding.action.clone().execute(&mut self.ctx);
+                if !skipped {
+                    binding.action.clone().execute(&mut self.ctx);
+                }
             }
         }
 
@@ -844,7 +1250,11 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     /// The provided mode, mods, and key must match what is allowed by a binding
     /// for its action to be executed.
     fn process_mouse_bindings(&mut self, button: MouseButton) {
-        let mode = BindingMode::new(self.ctx.terminal().mode());
+        let mode = BindingMode::new(
+            self.ctx.terminal().mode(),
+            self.ctx.search_active(),
+            self.ctx.child_exited(),
+        );
         let mouse_mode = self.ctx.mouse_mode();
         let mods = *self.ctx.modifiers();
 
@@ -864,10 +1274,14 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
     /// Check mouse icon state in relation to the message bar.
     fn message_bar_cursor_state(&self) -> Option<CursorIcon> {
-        // Calculate Y position of the end of the last terminal line.
+        // Calculate Y position of the end of the last terminal and status line.
         let size = self.ctx.size_info();
-        let terminal_end =
-            size.padding_y() as usize + size.cell_height() as usize * size.screen_lines();
+        let status_lines = usize::from(
+            self.ctx.config().window.indicator_status_line
+                || self.ctx.terminal().status_line_type() != StatusLineType::None,
+        );
+        let terminal_end = size.padding_y() as usize
+            + size.cell_height() as usize * (size.screen_lines() + status_lines);
 
         let mouse = self.ctx.mouse();
         let display_offset = self.ctx.terminal().grid().display_offset();
@@ -886,8 +1300,17 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
 
     /// Icon state of the cursor.
     fn cursor_state(&mut self) -> CursorIcon {
+        let display_offset = self.ctx.terminal().grid().display_offset();
+        let point = self.ctx.mouse().point(&self.ctx.size_info(), display_offset);
+        let hyperlink = self.ctx.terminal().grid()[point].hyperlink();
+
+        // Function to check if mouse is on top of a hint.
+        let hint_highlighted = |hint: &HintMatch| hint.should_highlight(point, hyperlink.as_ref());
+
         if let Some(mouse_state) = self.message_bar_cursor_state() {
             mouse_state
+        } else if self.ctx.display().highlighted_hint.as_ref().map_or(false, hint_highlighted) {
+            CursorIcon::Hand
         } else if !self.ctx.modifiers().shift() && self.ctx.mouse_mode() {
             CursorIcon::Default
         } else {
@@ -931,13 +1354,391 @@ impl<T: EventListener, A: ActionContext<T>> Processor<T, A> {
     }
 }
 
+/// Type of a key event reported by the kitty keyboard protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum KeyEventType {
+    Press,
+    Repeat,
+    Release,
+}
+
+/// Text key press waiting for its text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct PendingKey {
+    scancode: u32,
+    key: VirtualKeyCode,
+    mods: ModifiersState,
+    event_type: KeyEventType,
+}
+
+/// Keyboard state used for reporting key events.
+#[derive(Debug, Default)]
+pub struct KeyboardState {
+    /// Scancode of the last pressed key, used to detect repeats.
+    pressed_key: Option<u32>,
+
+    /// Text key press which is reported once its text has been received.
+    pending_key: Option<PendingKey>,
+
+    /// Unshifted and shifted text of the previously pressed keys, by scancode.
+    key_text: HashMap<u32, (char, char)>,
+
+    /// Keys whose press was consumed by a binding.
+    consumed_keys: HashSet<u32>,
+}
+
+impl KeyboardState {
+    /// Unshifted and shifted text of a key, based on the `text` received from the platform.
+    fn resolve_text(&mut self, key: &PendingKey, text: Option<char>) -> Option<(char, char)> {
+        let known = self.key_text.get(&key.scancode).copied();
+        let fallback = text_key(key.key);
+
+        // Control characters don't represent the key, so only known text can be used.
+        let key_text = match text.filter(|c| !c.is_control()) {
+            Some(text) if key.mods.shift() => {
+                let base = known
+                    .map(|(base, _)| base)
+                    .or_else(|| case_variant(text, text.to_lowercase()))
+                    .or_else(|| fallback.map(|(base, _)| base));
+                (base.unwrap_or(text), text)
+            },
+            Some(text) => {
+                let shifted = case_variant(text, text.to_uppercase())
+                    .or_else(|| known.map(|(_, shifted)| shifted))
+                    .or_else(|| fallback.map(|(_, shifted)| shifted));
+                (text, shifted.unwrap_or(text))
+            },
+            None => known.or(fallback)?,
+        };
+
+        self.key_text.insert(key.scancode, key_text);
+
+        Some(key_text)
+    }
+}
+
+/// Single character resulting from a case conversion of `c`, if it differs from `c`.
+fn case_variant(c: char, mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(variant), None) if variant != c => Some(variant),
+        _ => None,
+    }
+}
+
+/// Category of a key in the kitty keyboard protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum KittyKeyKind {
+    /// Key producing text, with its unshifted and shifted character.
+    Text(char, char),
+    /// Enter, Tab and Backspace, which keep their legacy encoding unless modified.
+    Legacy,
+    /// Key which is always reported as an escape sequence.
+    Functional,
+    /// Modifier and lock keys, which are only reported with all keys as escapes.
+    Modifier,
+}
+
+/// Key representation in the kitty keyboard protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct KittyKey {
+    number: u32,
+    terminator: char,
+    kind: KittyKeyKind,
+}
+
+impl KittyKey {
+    fn new(key: VirtualKeyCode, text: Option<(char, char)>) -> Option<Self> {
+        if let Some((base, shifted)) = text_key(key).map(|fallback| text.unwrap_or(fallback)) {
+            return Some(Self {
+                number: base as u32,
+                terminator: 'u',
+                kind: KittyKeyKind::Text(base, shifted),
+            });
+        }
+
+        let (number, terminator, kind) = match key {
+            VirtualKeyCode::Escape => (27, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Return => (13, 'u', KittyKeyKind::Legacy),
+            VirtualKeyCode::Tab => (9, 'u', KittyKeyKind::Legacy),
+            VirtualKeyCode::Back => (127, 'u', KittyKeyKind::Legacy),
+            VirtualKeyCode::Insert => (2, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::Delete => (3, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::Left => (1, 'D', KittyKeyKind::Functional),
+            VirtualKeyCode::Right => (1, 'C', KittyKeyKind::Functional),
+            VirtualKeyCode::Up => (1, 'A', KittyKeyKind::Functional),
+            VirtualKeyCode::Down => (1, 'B', KittyKeyKind::Functional),
+            VirtualKeyCode::PageUp => (5, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::PageDown => (6, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::Home => (1, 'H', KittyKeyKind::Functional),
+            VirtualKeyCode::End => (1, 'F', KittyKeyKind::Functional),
+            VirtualKeyCode::F1 => (1, 'P', KittyKeyKind::Functional),
+            VirtualKeyCode::F2 => (1, 'Q', KittyKeyKind::Functional),
+            VirtualKeyCode::F3 => (13, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F4 => (1, 'S', KittyKeyKind::Functional),
+            VirtualKeyCode::F5 => (15, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F6 => (17, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F7 => (18, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F8 => (19, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F9 => (20, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F10 => (21, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F11 => (23, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F12 => (24, '~', KittyKeyKind::Functional),
+            VirtualKeyCode::F13 => (57376, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F14 => (57377, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F15 => (57378, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F16 => (57379, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F17 => (57380, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F18 => (57381, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F19 => (57382, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F20 => (57383, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F21 => (57384, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F22 => (57385, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F23 => (57386, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::F24 => (57387, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Snapshot => (57361, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Pause => (57362, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Apps => (57363, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::PlayPause => (57430, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::MediaStop => (57432, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::NextTrack => (57435, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::PrevTrack => (57436, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::VolumeDown => (57438, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::VolumeUp => (57439, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Mute => (57440, 'u', KittyKeyKind::Functional),
+            VirtualKeyCode::Numpad0 => (57399, 'u', KittyKeyKind::Text('0', '0')),
+            VirtualKeyCode::Numpad1 => (57400, 'u', KittyKeyKind::Text('1', '1')),
+            VirtualKeyCode::Numpad2 => (57401, 'u', KittyKeyKind::Text('2', '2')),
+            VirtualKeyCode::Numpad3 => (57402, 'u', KittyKeyKind::Text('3', '3')),
+            VirtualKeyCode::Numpad4 => (57403, 'u', KittyKeyKind::Text('4', '4')),
+            VirtualKeyCode::Numpad5 => (57404, 'u', KittyKeyKind::Text('5', '5')),
+            VirtualKeyCode::Numpad6 => (57405, 'u', KittyKeyKind::Text('6', '6')),
+            VirtualKeyCode::Numpad7 => (57406, 'u', KittyKeyKind::Text('7', '7')),
+            VirtualKeyCode::Numpad8 => (57407, 'u', KittyKeyKind::Text('8', '8')),
+            VirtualKeyCode::Numpad9 => (57408, 'u', KittyKeyKind::Text('9', '9')),
+            VirtualKeyCode::NumpadDecimal => (57409, 'u', KittyKeyKind::Text('.', '.')),
+            VirtualKeyCode::NumpadDivide => (57410, 'u', KittyKeyKind::Text('/', '/')),
+            VirtualKeyCode::NumpadMultiply => (57411, 'u', KittyKeyKind::Text('*', '*')),
+            VirtualKeyCode::NumpadSubtract => (57412, 'u', KittyKeyKind::Text('-', '-')),
+            VirtualKeyCode::NumpadAdd => (57413, 'u', KittyKeyKind::Text('+', '+')),
+            VirtualKeyCode::NumpadEnter => (57414, 'u', KittyKeyKind::Legacy),
+            VirtualKeyCode::NumpadEquals => (57415, 'u', KittyKeyKind::Text('=', '=')),
+            VirtualKeyCode::NumpadComma => (57416, 'u', KittyKeyKind::Text(',', ',')),
+            VirtualKeyCode::Capital => (57358, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::Scroll => (57359, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::Numlock => (57360, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::LShift => (57441, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::LControl => (57442, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::LAlt => (57443, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::LWin => (57444, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::RShift => (57447, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::RControl => (57448, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::RAlt => (57449, 'u', KittyKeyKind::Modifier),
+            VirtualKeyCode::RWin => (57450, 'u', KittyKeyKind::Modifier),
+            _ => return None,
+        };
+
+        Some(Self { number, terminator, kind })
+    }
+}
+
+/// Unshifted and shifted character of a text key on a US layout.
+///
+/// This is only used for keys without any text received from the platform, like some keys pressed
+/// together with Control.
+fn text_key(key: VirtualKeyCode) -> Option<(char, char)> {
+    let letter = key as u32;
+    if (VirtualKeyCode::A as u32..=VirtualKeyCode::Z as u32).contains(&letter) {
+        let base = char::from(b'a' + (letter - VirtualKeyCode::A as u32) as u8);
+        return Some((base, base.to_ascii_uppercase()));
+    }
+
+    let chars = match key {
+        VirtualKeyCode::Key1 => ('1', '!'),
+        VirtualKeyCode::Key2 => ('2', '@'),
+        VirtualKeyCode::Key3 => ('3', '#'),
+        VirtualKeyCode::Key4 => ('4', '$'),
+        VirtualKeyCode::Key5 => ('5', '%'),
+        VirtualKeyCode::Key6 => ('6', '^'),
+        VirtualKeyCode::Key7 => ('7', '&'),
+        VirtualKeyCode::Key8 => ('8', '*'),
+        VirtualKeyCode::Key9 => ('9', '('),
+        VirtualKeyCode::Key0 => ('0', ')'),
+        VirtualKeyCode::Space => (' ', ' '),
+        VirtualKeyCode::Minus => ('-', '_'),
+        VirtualKeyCode::Equals => ('=', '+'),
+        VirtualKeyCode::LBracket => ('[', '{'),
+        VirtualKeyCode::RBracket => (']', '}'),
+        VirtualKeyCode::Backslash => ('\\', '|'),
+        VirtualKeyCode::Semicolon => (';', ':'),
+        VirtualKeyCode::Apostrophe => ('\'', '"'),
+        VirtualKeyCode::Grave => ('`', '~'),
+        VirtualKeyCode::Comma => (',', '<'),
+        VirtualKeyCode::Period => ('.', '>'),
+        VirtualKeyCode::Slash => ('/', '?'),
+        VirtualKeyCode::At => ('@', '@'),
+        VirtualKeyCode::Colon => (':', ':'),
+        VirtualKeyCode::Asterisk => ('*', '*'),
+        VirtualKeyCode::Plus => ('+', '+'),
+        VirtualKeyCode::Caret => ('^', '^'),
+        VirtualKeyCode::Underline => ('_', '_'),
+        _ => return None,
+    };
+
+    Some(chars)
+}
+
+/// Build the kitty keyboard protocol escape sequence for a key event.
+///
+/// Returns `None` if the event isn't reported by the protocol's active modes, in which case a
+/// press should fall back to the legacy key encoding.
+fn kitty_key_sequence(
+    mode: TermMode,
+    key: VirtualKeyCode,
+    text: Option<(char, char)>,
+    mods: ModifiersState,
+    event_type: KeyEventType,
+) -> Option<String> {
+    let key = KittyKey::new(key, text)?;
+
+    let all_as_esc = mode.contains(TermMode::REPORT_ALL_KEYS_AS_ESC);
+    let report_event_types = mode.contains(TermMode::REPORT_EVENT_TYPES);
+    let text_mods = mods.ctrl() || mods.alt() || mods.logo();
+
+    let reported = match key.kind {
+        KittyKeyKind::Functional => true,
+        KittyKeyKind::Modifier => all_as_esc,
+        KittyKeyKind::Legacy => all_as_esc || !mods.is_empty(),
+        KittyKeyKind::Text(..) => all_as_esc || text_mods,
+    };
+
+    // Releasing Enter, Tab or Backspace is only reported with all keys as escapes.
+    let release_reported = report_event_types && (all_as_esc || key.kind != KittyKeyKind::Legacy);
+
+    if !reported || (event_type == KeyEventType::Release && !release_reported) {
+        return None;
+    }
+
+    let modifiers = modifiers_code(mods);
+
+    let event_suffix = match event_type {
+        _ if !report_event_types => "",
+        KeyEventType::Press => "",
+        KeyEventType::Repeat => ":2",
+        KeyEventType::Release => ":3",
+    };
+
+    let (alternate, text) = match key.kind {
+        KittyKeyKind::Text(base, shifted) => {
+            let alternate = Some(shifted).filter(|&shifted| {
+                mode.contains(TermMode::REPORT_ALTERNATE_KEYS) && mods.shift() && shifted != base
+            });
+            let text = Some(if mods.shift() { shifted } else { base }).filter(|_| {
+                all_as_esc
+                    && mode.contains(TermMode::REPORT_ASSOCIATED_TEXT)
+                    && event_type != KeyEventType::Release
+                    && !text_mods
+            });
+            (alternate, text)
+        },
+        _ => (None, None),
+    };
+
+    let mut sequence = String::from("\x1b[");
+    let with_modifiers = modifiers > 1 || !event_suffix.is_empty() || text.is_some();
+
+    if key.number != 1 || with_modifiers {
+        sequence.push_str(&key.number.to_string());
+    }
+
+    if let Some(alternate) = alternate {
+        sequence.push_str(&format!(":{}", alternate as u32));
+    }
+
+    if with_modifiers {
+        sequence.push_str(&format!(";{}{}", modifiers, event_suffix));
+    }
+
+    if let Some(text) = text {
+        sequence.push_str(&format!(";{}", text as u32));
+    }
+
+    sequence.push(key.terminator);
+
+    Some(sequence)
+}
+
+/// Build the xterm modifyOtherKeys escape sequence for a key press.
+///
+/// Returns `None` if the key should use its legacy encoding.
+fn modify_other_keys_sequence(
+    mode: ModifyOtherKeys,
+    key: VirtualKeyCode,
+    text: Option<(char, char)>,
+    mods: ModifiersState,
+) -> Option<String> {
+    if mode == ModifyOtherKeys::Reset || mods.is_empty() {
+        return None;
+    }
+
+    let code = match key {
+        VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => 13,
+        VirtualKeyCode::Tab => 9,
+        VirtualKeyCode::Back => 127,
+        VirtualKeyCode::Escape => 27,
+        _ => {
+            let (base, shifted) = text_key(key).map(|fallback| text.unwrap_or(fallback))?;
+            u32::from(if mods.shift() { shifted } else { base })
+        },
+    };
+
+    // Shift alone only changes the text of printable keys.
+    let printable = code >= u32::from(b' ') && code != 127;
+    if printable && mods == ModifiersState::SHIFT {
+        return None;
+    }
+
+    // Keep keys with a well-known legacy encoding.
+    if mode == ModifyOtherKeys::EnableExceptWellDefined {
+        let control_char = char::from_u32(code)
+            .map_or(false, |c| c.is_ascii_lowercase() || "@[\\]^_ ".contains(c));
+        let well_defined = match code {
+            // Shift+Tab is reported as backtab.
+            9 => mods == ModifiersState::SHIFT,
+            // Control and Alt with printable keys produce control characters and escapes.
+            _ if printable => {
+                (mods.ctrl() && !mods.shift() && control_char)
+                    || (mods == ModifiersState::ALT)
+                    || (mods == ModifiersState::ALT | ModifiersState::SHIFT)
+            },
+            _ => false,
+        };
+
+        if well_defined {
+            return None;
+        }
+    }
+
+    Some(format!("\x1b[27;{};{}~", modifiers_code(mods), code))
+}
+
+/// Xterm modifier parameter for key escape sequences.
+fn modifiers_code(mods: ModifiersState) -> u8 {
+    1 + mods.shift() as u8
+        + ((mods.alt() as u8) << 1)
+        + ((mods.ctrl() as u8) << 2)
+        + ((mods.logo() as u8) << 3)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    use std::cell::RefCell;
+
     use winit::event::{DeviceId, Event as WinitEvent, VirtualKeyCode, WindowEvent};
     use winit::window::WindowId;
 
+    use alacritty_terminal::ansi;
     use alacritty_terminal::event::Event as TerminalEvent;
 
     use crate::config::Binding;
@@ -956,11 +1757,34 @@ mod tests {
         pub message_buffer: &'a mut MessageBuffer,
         pub received_count: usize,
         pub suppress_chars: bool,
+        pub keyboard: KeyboardState,
         pub modifiers: ModifiersState,
+        pub written: RefCell<Vec<u8>>,
         config: &'a UiConfig,
     }
 
     impl<'a, T: EventListener> super::ActionContext<T> for ActionContext<'a, T> {
+        fn search_next(
+            &mut self,
+            _origin: Point,
+            _direction: Direction,
+            _side: Side,
+        ) -> Option<Match> {
+            None
+        }
+
+        fn search_direction(&self) -> Direction {
+            Direction::Right
+        }
+
+        fn search_active(&self) -> bool {
+            false
+        }
+
+        fn write_to_pty<B: Into<Cow<'static, [u8]>>>(&self, data: B) {
+            self.written.borrow_mut().extend_from_slice(&data.into());
+        }
+
         fn terminal(&self) -> &Term<T> {
             self.terminal
         }
@@ -1008,6 +1832,10 @@ mod tests {
             &mut self.suppress_chars
         }
 
+        fn keyboard(&mut self) -> &mut KeyboardState {
+            &mut self.keyboard
+        }
+
         fn modifiers(&mut self) -> &mut ModifiersState {
             &mut self.modifiers
         }
@@ -1084,8 +1912,10 @@ mod tests {
                     clipboard: &mut clipboard,
                     received_count: 0,
                     suppress_chars: false,
+                    keyboard: Default::default(),
                     modifiers: Default::default(),
                     message_buffer: &mut message_buffer,
+                    written: Default::default(),
                     config: &cfg,
                 };
 
@@ -1109,6 +1939,58 @@ mod tests {
         }
     }
 
+    /// Report a mouse press at the pixel position `(x, y)` using `mode`.
+    fn mouse_report(mode: ansi::Mode, x: usize, y: usize) -> Vec<u8> {
+        let mut clipboard = Clipboard::new_nop();
+        let cfg = UiConfig::default();
+        let size = SizeInfo::new(21.0, 51.0, 3.0, 3.0, 2., 2., false);
+
+        let mut terminal = Term::new(&cfg.terminal_config, &size, MockEventProxy);
+        terminal.set_mode(mode);
+
+        let mut mouse = Mouse { x, y, ..Mouse::default() };
+        let mut message_buffer = MessageBuffer::default();
+
+        let context = ActionContext {
+            terminal: &mut terminal,
+            mouse: &mut mouse,
+            size_info: &size,
+            clipboard: &mut clipboard,
+            received_count: 0,
+            suppress_chars: false,
+            keyboard: Default::default(),
+            modifiers: Default::default(),
+            message_buffer: &mut message_buffer,
+            written: Default::default(),
+            config: &cfg,
+        };
+
+        let mut processor = Processor::new(context);
+        processor.mouse_report(0, ElementState::Pressed);
+        processor.mouse_report(0, ElementState::Released);
+
+        processor.ctx.written.into_inner()
+    }
+
+    #[test]
+    fn urxvt_mouse_report() {
+        assert_eq!(mouse_report(ansi::Mode::UrxvtMouse, 9, 5), b"\x1b[32;3;2M\x1b[35;3;2M");
+    }
+
+    #[test]
+    fn sgr_pixels_mouse_report() {
+        assert_eq!(mouse_report(ansi::Mode::SgrPixelsMouse, 9, 5), b"\x1b[<0;8;4M\x1b[<0;8;4m");
+    }
+
+    #[test]
+    fn sgr_pixels_mouse_report_clamped() {
+        // Positions inside the padding are clamped to the text area.
+        assert_eq!(
+            mouse_report(ansi::Mode::SgrPixelsMouse, 20, 50),
+            b"\x1b[<0;15;45M\x1b[<0;15;45m"
+        );
+    }
+
     macro_rules! test_process_binding {
         {
             name: $name:ident,
@@ -1287,4 +2169,133 @@ mod tests {
         mode: BindingMode::empty(),
         mods: ModifiersState::ALT | ModifiersState::LOGO,
     }
+
+    #[test]
+    fn kitty_disambiguate_escape_codes() {
+        let mode = TermMode::DISAMBIGUATE_ESC_CODES;
+        let press = |key, mods| kitty_key_sequence(mode, key, None, mods, KeyEventType::Press);
+
+        // Unmodified text and legacy keys keep their legacy encoding.
+        assert_eq!(press(VirtualKeyCode::A, ModifiersState::empty()), None);
+        assert_eq!(press(VirtualKeyCode::A, ModifiersState::SHIFT), None);
+        assert_eq!(press(VirtualKeyCode::Return, ModifiersState::empty()), None);
+
+        // Escape and modified keys are reported unambiguously.
+        assert_eq!(press(VirtualKeyCode::Escape, ModifiersState::empty()), Some("\x1b[27u".into()));
+        assert_eq!(press(VirtualKeyCode::A, ModifiersState::CTRL), Some("\x1b[97;5u".into()));
+        assert_eq!(press(VirtualKeyCode::Tab, ModifiersState::SHIFT), Some("\x1b[9;2u".into()));
+        assert_eq!(press(VirtualKeyCode::Up, ModifiersState::empty()), Some("\x1b[A".into()));
+        assert_eq!(press(VirtualKeyCode::Up, ModifiersState::ALT), Some("\x1b[1;3A".into()));
+        assert_eq!(press(VirtualKeyCode::F5, ModifiersState::CTRL), Some("\x1b[15;5~".into()));
+
+        // Release events are not reported.
+        let release = kitty_key_sequence(
+            mode,
+            VirtualKeyCode::Escape,
+            None,
+            ModifiersState::empty(),
+            KeyEventType::Release,
+        );
+        assert_eq!(release, None);
+    }
+
+    #[test]
+    fn kitty_report_event_types() {
+        let mode = TermMode::DISAMBIGUATE_ESC_CODES | TermMode::REPORT_EVENT_TYPES;
+        let mods = ModifiersState::empty();
+
+        let repeat =
+            kitty_key_sequence(mode, VirtualKeyCode::Left, None, mods, KeyEventType::Repeat);
+        assert_eq!(repeat, Some("\x1b[1;1:2D".into()));
+
+        let release =
+            kitty_key_sequence(mode, VirtualKeyCode::Delete, None, mods, KeyEventType::Release);
+        assert_eq!(release, Some("\x1b[3;1:3~".into()));
+
+        // Legacy keys only report releases with all keys as escapes.
+        let release =
+            kitty_key_sequence(mode, VirtualKeyCode::Back, None, mods, KeyEventType::Release);
+        assert_eq!(release, None);
+    }
+
+    #[test]
+    fn kitty_report_all_keys_as_escapes() {
+        let mode = TermMode::REPORT_ALL_KEYS_AS_ESC
+            | TermMode::REPORT_ALTERNATE_KEYS
+            | TermMode::REPORT_ASSOCIATED_TEXT;
+        let press = |key, mods| kitty_key_sequence(mode, key, None, mods, KeyEventType::Press);
+
+        assert_eq!(press(VirtualKeyCode::A, ModifiersState::empty()), Some("\x1b[97;1;97u".into()));
+        assert_eq!(
+            press(VirtualKeyCode::Key1, ModifiersState::SHIFT),
+            Some("\x1b[49:33;2;33u".into())
+        );
+        assert_eq!(press(VirtualKeyCode::A, ModifiersState::CTRL), Some("\x1b[97;5u".into()));
+        assert_eq!(press(VirtualKeyCode::Return, ModifiersState::empty()), Some("\x1b[13u".into()));
+        assert_eq!(
+            press(VirtualKeyCode::LShift, ModifiersState::SHIFT),
+            Some("\x1b[57441;2u".into())
+        );
+    }
+
+    #[test]
+    fn kitty_platform_text() {
+        let mode = TermMode::REPORT_ALL_KEYS_AS_ESC
+            | TermMode::REPORT_ALTERNATE_KEYS
+            | TermMode::REPORT_ASSOCIATED_TEXT;
+        let mut keyboard = KeyboardState::default();
+        let mut press = |mods, text| {
+            let key = PendingKey {
+                scancode: 16,
+                key: VirtualKeyCode::A,
+                mods,
+                event_type: KeyEventType::Press,
+            };
+            let text = keyboard.resolve_text(&key, text);
+            kitty_key_sequence(mode, key.key, text, mods, KeyEventType::Press)
+        };
+
+        // Keys are reported with the text of the active layout.
+        assert_eq!(press(ModifiersState::empty(), Some('q')), Some("\x1b[113;1;113u".into()));
+        assert_eq!(press(ModifiersState::SHIFT, Some('Q')), Some("\x1b[113:81;2;81u".into()));
+
+        // Control characters are replaced by the text of the previous press.
+        assert_eq!(press(ModifiersState::CTRL, Some('\x11')), Some("\x1b[113;5u".into()));
+    }
+
+    #[test]
+    fn modify_other_keys() {
+        let all =
+            |key, mods| modify_other_keys_sequence(ModifyOtherKeys::EnableAll, key, None, mods);
+        let except_well_defined = |key, mods| {
+            modify_other_keys_sequence(ModifyOtherKeys::EnableExceptWellDefined, key, None, mods)
+        };
+        let ctrl_shift = ModifiersState::CTRL | ModifiersState::SHIFT;
+
+        // Disabled or unmodified keys keep their legacy encoding.
+        let reset = modify_other_keys_sequence(
+            ModifyOtherKeys::Reset,
+            VirtualKeyCode::Return,
+            None,
+            ModifiersState::CTRL,
+        );
+        assert_eq!(reset, None);
+        assert_eq!(all(VirtualKeyCode::A, ModifiersState::empty()), None);
+        assert_eq!(all(VirtualKeyCode::A, ModifiersState::SHIFT), None);
+
+        // Keys without a distinct legacy encoding are always reported.
+        assert_eq!(all(VirtualKeyCode::Return, ModifiersState::CTRL), Some("\x1b[27;5;13~".into()));
+        let shift_enter = except_well_defined(VirtualKeyCode::Return, ModifiersState::SHIFT);
+        assert_eq!(shift_enter, Some("\x1b[27;2;13~".into()));
+        let ctrl_period = except_well_defined(VirtualKeyCode::Period, ModifiersState::CTRL);
+        assert_eq!(ctrl_period, Some("\x1b[27;5;46~".into()));
+        assert_eq!(all(VirtualKeyCode::Key1, ctrl_shift), Some("\x1b[27;6;33~".into()));
+
+        // Well-defined keys are only reported when all keys are modified.
+        assert_eq!(except_well_defined(VirtualKeyCode::A, ModifiersState::CTRL), None);
+        assert_eq!(except_well_defined(VirtualKeyCode::A, ModifiersState::ALT), None);
+        assert_eq!(except_well_defined(VirtualKeyCode::Tab, ModifiersState::SHIFT), None);
+        assert_eq!(all(VirtualKeyCode::A, ModifiersState::CTRL), Some("\x1b[27;5;97~".into()));
+        assert_eq!(all(VirtualKeyCode::Tab, ModifiersState::SHIFT), Some("\x1b[27;2;9~".into()));
+    }
 }
@@ -1,12 +1,16 @@
 //! Alacritty socket IPC.
 
 use std::ffi::OsStr;
-use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Result as IoResult, Write};
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, BufReader, Error as IoError, ErrorKind, Read, Result as IoResult, Write};
+use std::net::Shutdown;
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
 use std::{env, fs, process};
 
 use log::warn;
+use serde::{Deserialize, Serialize};
 use winit::event_loop::EventLoopProxy;
 use winit::window::WindowId;
 
@@ -38,55 +42,333 @@ pub fn spawn_ipc_socket(options: &Options, event_proxy: EventLoopProxy<Event>) -
 
     // Spawn a thread to listen on the IPC socket.
     thread::spawn_named("socket listener", move || {
-        let mut data = String::new();
         for stream in listener.incoming().filter_map(Result::ok) {
-            data.clear();
-            let mut stream = BufReader::new(stream);
-
-            match stream.read_line(&mut data) {
-                Ok(0) | Err(_) => continue,
-                Ok(_) => (),
-            };
-
-            // Read pending events on socket.
-            let message: SocketMessage = match serde_json::from_str(&data) {
-                Ok(message) => message,
-                Err(err) => {
-                    warn!("Failed to convert data from socket: {}", err);
-                    continue;
-                },
-            };
-
-            // Handle IPC events.
-            match message {
-                SocketMessage::CreateWindow(options) => {
-                    let event = Event::new(EventType::CreateWindow(options), None);
-                    let _ = event_proxy.send_event(event);
-                },
-                SocketMessage::Config(ipc_config) => {
-                    let window_id = ipc_config
-                        .window_id
-                        .and_then(|id| u64::try_from(id).ok())
-                        .map(WindowId::from);
-                    let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
-                    let _ = event_proxy.send_event(event);
-                },
-            }
+            // Handle every client on its own thread, so slow clients can't block others.
+            let event_proxy = event_proxy.clone();
+            thread::spawn_named("socket client", move || handle_client(stream, &event_proxy));
         }
     });
 
     Some(socket_path)
 }
 
-/// Send a message to the active Alacritty socket.
-pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<()> {
+/// Read a message from an IPC client and reply to it.
+fn handle_client(stream: UnixStream, event_proxy: &EventLoopProxy<Event>) {
+    // Read pending events on socket.
+    let (message, stream) = match read_message(stream) {
+        Some((Ok(message), stream)) => (message, stream),
+        Some((Err(err), stream)) => {
+            warn!("Failed to convert data from socket: {}", err);
+            send_reply(&stream, SocketReply::Error(err));
+            return;
+        },
+        None => return,
+    };
+
+    // Handle IPC events.
+    match message {
+        SocketMessage::CreateWindow(options) => {
+            let event = Event::new(EventType::CreateWindow(options), None);
+            let _ = event_proxy.send_event(event);
+            send_reply(&stream, SocketReply::Ok);
+        },
+        SocketMessage::Config(ipc_config) => {
+            let window_id =
+                ipc_config.window_id.and_then(|id| u64::try_from(id).ok()).map(WindowId::from);
+            let event = Event::new(EventType::IpcConfig(ipc_config), window_id);
+            let _ = event_proxy.send_event(event);
+            send_reply(&stream, SocketReply::Ok);
+        },
+        SocketMessage::Subscribe => {
+            let event = EventType::IpcSubscribe(Arc::new(stream));
+            let _ = event_proxy.send_event(Event::new(event, None));
+        },
+        // Requests which need access to the windows are answered by the event loop.
+        message => {
+            let (reply_tx, reply_rx) = mpsc::channel();
+            let event = EventType::IpcRequest(message, reply_tx);
+            let _ = event_proxy.send_event(Event::new(event, None));
+
+            // Write the reply on this thread, to avoid blocking the event loop on the client.
+            if let Ok(reply) = reply_rx.recv() {
+                send_reply(&stream, reply);
+            }
+        },
+    }
+}
+
+/// Reply sent back to the client for every socket message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum SocketReply {
+    /// Message was handled successfully.
+    Ok,
+
+    /// All windows of the Alacritty instance.
+    Windows(Vec<WindowInfo>),
+
+    /// Text content of a terminal.
+    Text(String),
+
+    /// Message could not be handled.
+    Error(IpcError),
+}
+
+/// Event streamed to subscribed IPC clients.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum IpcEvent {
+    /// New window was created.
+    WindowCreated { window_id: u64 },
+
+    /// Window was closed.
+    WindowDestroyed { window_id: u64 },
+
+    /// Window title has changed.
+    Title { window_id: u64, title: String },
+
+    /// Terminal bell rang.
+    Bell { window_id: u64 },
+
+    /// Shell process of a window exited.
+    ///
+    /// Contains the exit code, or the signal which terminated the process.
+    ChildExit { window_id: u64, code: Option<i32>, signal: Option<i32> },
+
+    /// Window focus has changed.
+    Focus { window_id: u64, focused: bool },
+
+    /// Configuration file was reloaded.
+    ConfigReload { path: PathBuf },
+}
+
+/// Maximum number of unsent bytes buffered for a single IPC subscriber.
+const MAX_SUBSCRIBER_BACKLOG: usize = 1024 * 1024;
+
+/// IPC clients subscribed to terminal events.
+#[derive(Default)]
+pub struct IpcSubscribers {
+    subscribers: Vec<IpcSubscriber>,
+}
+
+impl IpcSubscribers {
+    /// Start streaming events to a client.
+    pub fn subscribe(&mut self, stream: Arc<UnixStream>) {
+        send_reply(&stream, SocketReply::Ok);
+
+        // Avoid blocking the event loop on clients which stopped reading.
+        if stream.set_nonblocking(true).is_ok() {
+            self.subscribers.push(IpcSubscriber { stream, backlog: Vec::new() });
+        }
+    }
+
+    /// Check if there are any subscribed clients.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Send an event to all subscribers.
+    ///
+    /// Events which can't be written immediately are buffered until the next event is published.
+    /// Clients which disconnected or fell too far behind are dropped.
+    pub fn publish(&mut self, event: IpcEvent) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        let mut event = match serde_json::to_string(&event) {
+            Ok(event) => event,
+            Err(_) => return,
+        };
+        event.push('\n');
+
+        let mut i = 0;
+        while i < self.subscribers.len() {
+            if self.subscribers[i].send(event.as_bytes()) {
+                i += 1;
+            } else {
+                self.subscribers.swap_remove(i);
+            }
+        }
+    }
+}
+
+/// Single client subscribed to terminal events.
+struct IpcSubscriber {
+    stream: Arc<UnixStream>,
+
+    /// Bytes which could not be written to the client yet.
+    backlog: Vec<u8>,
+}
+
+impl IpcSubscriber {
+    /// Queue data for the client and write as much of the backlog as possible.
+    ///
+    /// Returns `false` if the client should be dropped.
+    fn send(&mut self, data: &[u8]) -> bool {
+        if self.backlog.len() + data.len() > MAX_SUBSCRIBER_BACKLOG {
+            warn!("Dropping IPC subscriber which stopped reading events");
+            return false;
+        }
+        self.backlog.extend_from_slice(data);
+
+        let mut stream: &UnixStream = &self.stream;
+        let mut written = 0;
+        let connected = loop {
+            if written == self.backlog.len() {
+                break true;
+            }
+
+            match stream.write(&self.backlog[written..]) {
+                Ok(0) => break false,
+                Ok(n) => written += n,
+                Err(err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break true,
+                Err(_) => break false,
+            }
+        };
+        self.backlog.drain(..written);
+
+        connected
+    }
+}
+
+/// Description of an Alacritty window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WindowInfo {
+    /// Window ID, matching the `ALACRITTY_WINDOW_ID` of its shell.
+    pub id: u64,
+
+    /// Current window title.
+    pub title: String,
+
+    /// Terminal width in character columns.
+    pub columns: usize,
+
+    /// Terminal height in character lines.
+    pub lines: usize,
+
+    /// Window width in pixels.
+    pub width: u32,
+
+    /// Window height in pixels.
+    pub height: u32,
+
+    /// PID of the window's shell.
+    pub pid: u32,
+
+    /// Working directory reported by the shell using OSC 7.
+    pub working_directory: Option<PathBuf>,
+}
+
+/// Errors reported to IPC clients.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum IpcError {
+    /// Message could not be parsed.
+    InvalidMessage(String),
+
+    /// No window with the requested ID exists.
+    WindowNotFound(i128),
+
+    /// No window ID was specified while multiple windows are open.
+    AmbiguousWindow,
+
+    /// Requested terminal dimensions are invalid.
+    InvalidDimensions { columns: usize, lines: usize },
+}
+
+impl std::error::Error for IpcError {}
+
+impl Display for IpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidMessage(err) => write!(f, "invalid message: {}", err),
+            Self::WindowNotFound(id) => write!(f, "no window with ID {}", id),
+            Self::AmbiguousWindow => {
+                write!(f, "multiple windows are open, please specify a window ID")
+            },
+            Self::InvalidDimensions { columns, lines } => {
+                write!(f, "invalid terminal dimensions {}x{}", columns, lines)
+            },
+        }
+    }
+}
+
+/// Write a reply to an IPC client.
+///
+/// Errors are ignored, since clients are free to disconnect without waiting for the reply.
+pub fn send_reply(mut stream: &UnixStream, reply: SocketReply) {
+    if let Ok(mut reply) = serde_json::to_string(&reply) {
+        reply.push('\n');
+        let _ = stream.write_all(reply.as_bytes());
+        let _ = stream.flush();
+    }
+}
+
+/// Send a message to the active Alacritty socket and wait for its reply.
+pub fn send_message(socket: Option<PathBuf>, message: SocketMessage) -> IoResult<SocketReply> {
     let mut socket = find_socket(socket)?;
+    write_message(&mut socket, &message)?;
+
+    let mut reply = String::new();
+    socket.read_to_string(&mut reply)?;
 
-    let message = serde_json::to_string(&message)?;
+    // Older versions of Alacritty close the socket without replying.
+    if reply.trim().is_empty() {
+        return Ok(SocketReply::Ok);
+    }
+
+    Ok(serde_json::from_str(&reply)?)
+}
+
+/// Subscribe to terminal events.
+///
+/// The callback is invoked with the JSON of every received event, until the socket is closed.
+pub fn subscribe<F: FnMut(&str)>(socket: Option<PathBuf>, mut callback: F) -> IoResult<()> {
+    let mut socket = find_socket(socket)?;
+    write_message(&mut socket, &SocketMessage::Subscribe)?;
+
+    let mut lines = BufReader::new(socket).lines();
+
+    // Abort if the subscription was rejected.
+    if let Some(reply) = lines.next() {
+        if let SocketReply::Error(err) = serde_json::from_str(&reply?)? {
+            return Err(IoError::new(ErrorKind::Other, err));
+        }
+    }
+
+    for line in lines {
+        callback(&line?);
+    }
+
+    Ok(())
+}
+
+/// Write a message to the socket.
+fn write_message(socket: &mut UnixStream, message: &SocketMessage) -> IoResult<()> {
+    let message = serde_json::to_string(message)?;
     socket.write_all(message[..].as_bytes())?;
     let _ = socket.flush();
 
-    Ok(())
+    // Signal the end of the message, so the server can start processing it.
+    socket.shutdown(Shutdown::Write)
+}
+
+/// Read a single message from an IPC client.
+///
+/// Returns `None` if the client disconnected without sending a message.
+fn read_message(stream: UnixStream) -> Option<(Result<SocketMessage, IpcError>, UnixStream)> {
+    let mut data = String::new();
+    let mut stream = BufReader::new(stream);
+
+    match stream.read_line(&mut data) {
+        Ok(0) | Err(_) => return None,
+        Ok(_) => (),
+    };
+
+    let message =
+        serde_json::from_str(&data).map_err(|err| IpcError::InvalidMessage(err.to_string()));
+
+    Some((message, stream.into_inner()))
 }
 
 /// Directory for the IPC socket file.
@@ -169,3 +451,191 @@ fn socket_prefix() -> String {
 fn socket_prefix() -> String {
     String::from("Alacritty")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::cli::{IpcGetText, IpcWindow};
+
+    /// Create a listener on a unique socket path.
+    fn test_listener(name: &str) -> (UnixListener, PathBuf) {
+        let path = env::temp_dir().join(format!("alacritty-test-{}-{}.sock", process::id(), name));
+        let _ = fs::remove_file(&path);
+        (UnixListener::bind(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn message_reply_roundtrip() {
+        let (listener, path) = test_listener("roundtrip");
+
+        let window = WindowInfo {
+            id: 3,
+            title: String::from("Alacritty"),
+            columns: 80,
+            lines: 24,
+            width: 644,
+            height: 388,
+            pid: 1337,
+            working_directory: Some(PathBuf::from("/tmp")),
+        };
+        let expected = SocketReply::Windows(vec![window]);
+
+        let reply = expected.clone();
+        let server = std::thread::spawn(move || {
+            let stream = listener.incoming().next().unwrap().unwrap();
+            let (message, stream) = read_message(stream).unwrap();
+            assert_eq!(message, Ok(SocketMessage::ListWindows));
+            send_reply(&stream, reply);
+        });
+
+        let reply = send_message(Some(path.clone()), SocketMessage::ListWindows).unwrap();
+        server.join().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(reply, expected);
+    }
+
+    #[test]
+    fn message_with_window_id() {
+        let (listener, path) = test_listener("window-id");
+
+        let window = IpcWindow { window_id: Some(7) };
+        let message = SocketMessage::GetText(IpcGetText { window, scrollback: true });
+
+        let expected = message.clone();
+        let server = std::thread::spawn(move || {
+            let stream = listener.incoming().next().unwrap().unwrap();
+            let (message, stream) = read_message(stream).unwrap();
+            assert_eq!(message, Ok(expected));
+            send_reply(&stream, SocketReply::Text(String::from("$ echo")));
+        });
+
+        let reply = send_message(Some(path.clone()), message).unwrap();
+        server.join().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(reply, SocketReply::Text(String::from("$ echo")));
+    }
+
+    #[test]
+    fn invalid_message_error_reply() {
+        let (listener, path) = test_listener("invalid");
+
+        let server = std::thread::spawn(move || {
+            let stream = listener.incoming().next().unwrap().unwrap();
+            let (message, stream) = read_message(stream).unwrap();
+            send_reply(&stream, SocketReply::Error(message.unwrap_err()));
+        });
+
+        let mut stream = UnixStream::connect(&path).unwrap();
+        stream.write_all(b"{\"Unknown\":null}\n").unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        server.join().unwrap();
+        let _ = fs::remove_file(&path);
+
+        let reply: SocketReply = serde_json::from_str(&reply).unwrap();
+        assert!(matches!(reply, SocketReply::Error(IpcError::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn missing_reply_is_ok() {
+        let (listener, path) = test_listener("no-reply");
+
+        // Emulate older versions, which close the socket without replying.
+        let server = std::thread::spawn(move || {
+            let stream = listener.incoming().next().unwrap().unwrap();
+            let (message, _stream) = read_message(stream).unwrap();
+            assert_eq!(message, Ok(SocketMessage::ListWindows));
+        });
+
+        let reply = send_message(Some(path.clone()), SocketMessage::ListWindows).unwrap();
+        server.join().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(reply, SocketReply::Ok);
+    }
+
+    #[test]
+    fn subscribe_streams_events() {
+        let (listener, path) = test_listener("subscribe");
+
+        let server = std::thread::spawn(move || {
+            let stream = listener.incoming().next().unwrap().unwrap();
+            let (message, stream) = read_message(stream).unwrap();
+            assert_eq!(message, Ok(SocketMessage::Subscribe));
+
+            let mut subscribers = IpcSubscribers::default();
+            subscribers.subscribe(Arc::new(stream));
+            subscribers.publish(IpcEvent::WindowCreated { window_id: 1 });
+            subscribers.publish(IpcEvent::Bell { window_id: 1 });
+        });
+
+        let mut events = Vec::new();
+        subscribe(Some(path.clone()), |event| events.push(event.to_owned())).unwrap();
+        server.join().unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(
+            events,
+            vec![r#"{"WindowCreated":{"window_id":1}}"#, r#"{"Bell":{"window_id":1}}"#,]
+        );
+    }
+
+    #[test]
+    fn disconnected_subscribers_are_dropped() {
+        let (server, client) = UnixStream::pair().unwrap();
+
+        let mut subscribers = IpcSubscribers::default();
+        subscribers.subscribe(Arc::new(server));
+        assert!(!subscribers.is_empty());
+
+        drop(client);
+        subscribers.publish(IpcEvent::Focus { window_id: 1, focused: true });
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn slow_subscribers_are_buffered() {
+        let (server, client) = UnixStream::pair().unwrap();
+
+        let mut subscribers = IpcSubscribers::default();
+        subscribers.subscribe(Arc::new(server));
+
+        // Publish more data than the socket can buffer without the client reading.
+        let title = "x".repeat(64 * 1024);
+        for _ in 0..8 {
+            subscribers.publish(IpcEvent::Title { window_id: 1, title: title.clone() });
+        }
+        assert!(!subscribers.is_empty());
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let reader = std::thread::spawn(move || {
+            let mut lines = BufReader::new(client).lines();
+            assert_eq!(lines.next().unwrap().unwrap(), r#""Ok""#);
+
+            let expected = serde_json::to_string(&IpcEvent::Title { window_id: 1, title }).unwrap();
+            for _ in 0..8 {
+                assert_eq!(lines.next().unwrap().unwrap(), expected);
+            }
+            assert_eq!(lines.next().unwrap().unwrap(), r#"{"Bell":{"window_id":1}}"#);
+            done_tx.send(()).unwrap();
+        });
+
+        // Buffered events are flushed whenever a new event is published.
+        while done_rx.recv_timeout(std::time::Duration::from_millis(10)).is_err() {
+            subscribers.publish(IpcEvent::Bell { window_id: 1 });
+        }
+        reader.join().unwrap();
+    }
+
+    #[test]
+    fn structured_error_json() {
+        let reply = SocketReply::Error(IpcError::WindowNotFound(42));
+        let json = serde_json::to_string(&reply).unwrap();
+        assert_eq!(json, r#"{"Error":{"WindowNotFound":42}}"#);
+    }
+}
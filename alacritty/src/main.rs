@@ -56,9 +56,11 @@ mod gl {
 
 use crate::cli::Options;
 #[cfg(unix)]
-use crate::cli::{MessageOptions, Subcommands};
+use crate::cli::{MessageOptions, SocketMessage, Subcommands};
 use crate::config::{monitor, UiConfig};
 use crate::event::{Event, Processor};
+#[cfg(unix)]
+use crate::ipc::SocketReply;
 #[cfg(target_os = "macos")]
 use crate::macos::locale;
 
@@ -90,7 +92,18 @@ fn main() -> Result<(), Box<dyn Error>> {
 /// `msg` subcommand entrypoint.
 #[cfg(unix)]
 fn msg(options: MessageOptions) -> Result<(), Box<dyn Error>> {
-    ipc::send_message(options.socket, options.message).map_err(|err| err.into())
+    if options.message == SocketMessage::Subscribe {
+        return ipc::subscribe(options.socket, |event| println!("{}", event)).map_err(Into::into);
+    }
+
+    match ipc::send_message(options.socket, options.message)? {
+        SocketReply::Ok => (),
+        SocketReply::Windows(windows) => println!("{}", serde_json::to_string(&windows)?),
+        SocketReply::Text(text) => println!("{}", text),
+        SocketReply::Error(err) => return Err(err.into()),
+    }
+
+    Ok(())
 }
 
 /// Temporary files stored for Alacritty.
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::mem;
+
+use alacritty_terminal::graphics::{GraphicCell, GraphicId, UpdateQueues};
+use alacritty_terminal::index::Point;
+
+use crate::display::SizeInfo;
+use crate::gl;
+use crate::gl::types::*;
+use crate::renderer::shader::{ShaderProgram, ShaderVersion};
+use crate::renderer::{self, cstr};
+
+/// Shader sources for graphics rendering program.
+static GRAPHICS_SHADER_F: &str = include_str!("../../res/graphics.f.glsl");
+static GRAPHICS_SHADER_V: &str = include_str!("../../res/graphics.v.glsl");
+
+/// Part of an image visible in the viewport.
+#[derive(Debug)]
+pub struct RenderGraphic {
+    pub point: Point<usize>,
+    pub graphic: GraphicCell,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct Vertex {
+    // Normalized screen coordinates.
+    x: f32,
+    y: f32,
+
+    // Texture coordinates.
+    u: f32,
+    v: f32,
+}
+
+#[derive(Debug)]
+pub struct GraphicsRenderer {
+    // GL buffer objects.
+    vao: GLuint,
+    vbo: GLuint,
+
+    program: ShaderProgram,
+
+    /// Uploaded image textures.
+    textures: HashMap<GraphicId, GLuint>,
+
+    vertices: Vec<Vertex>,
+}
+
+impl GraphicsRenderer {
+    pub fn new(shader_version: ShaderVersion) -> Result<Self, renderer::Error> {
+        let mut vao: GLuint = 0;
+        let mut vbo: GLuint = 0;
+
+        let program =
+            ShaderProgram::new(shader_version, None, GRAPHICS_SHADER_V, GRAPHICS_SHADER_F)?;
+        let u_texture = program.get_uniform_location(cstr!("graphicTexture"))?;
+
+        unsafe {
+            // Always sample images from the first texture unit.
+            gl::UseProgram(program.id());
+            gl::Uniform1i(u_texture, 0);
+            gl::UseProgram(0);
+
+            // Allocate buffers.
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+
+            gl::BindVertexArray(vao);
+
+            // VBO binding is not part of VAO itself, but VBO binding is stored in attributes.
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+            // Position.
+            gl::VertexAttribPointer(
+                0,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                mem::size_of::<Vertex>() as i32,
+                std::ptr::null(),
+            );
+            gl::EnableVertexAttribArray(0);
+
+            // Texture coordinates.
+            gl::VertexAttribPointer(
+                1,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                mem::size_of::<Vertex>() as i32,
+                (mem::size_of::<f32>() * 2) as *const _,
+            );
+            gl::EnableVertexAttribArray(1);
+
+            // Reset buffer bindings.
+            gl::BindVertexArray(0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+        }
+
+        Ok(Self { vao, vbo, program, textures: HashMap::new(), vertices: Vec::new() })
+    }
+
+    /// Upload new images and delete unused textures.
+    pub fn update_textures(&mut self, queues: UpdateQueues) {
+        unsafe {
+            for (id, graphic) in queues.pending {
+                let mut texture: GLuint = 0;
+
+                gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+                gl::GenTextures(1, &mut texture);
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexImage2D(
+                    gl::TEXTURE_2D,
+                    0,
+                    gl::RGBA as i32,
+                    graphic.width as i32,
+                    graphic.height as i32,
+                    0,
+                    gl::RGBA,
+                    gl::UNSIGNED_BYTE,
+                    graphic.pixels.as_ptr() as *const _,
+                );
+
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+
+                self.textures.insert(id, texture);
+            }
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+
+            for id in queues.remove_queue {
+                if let Some(texture) = self.textures.remove(&id) {
+                    gl::DeleteTextures(1, &texture);
+                }
+            }
+        }
+    }
+
+    pub fn draw(&mut self, size_info: &SizeInfo, mut graphics: Vec<RenderGraphic>) {
+        // Draw all cells of an image with a single draw call.
+        graphics.sort_by_key(|graphic| {
+            let placement = &graphic.graphic.placement;
+            (placement.z_index, placement.texture.id)
+        });
+
+        unsafe {
+            gl::UseProgram(self.program.id());
+            gl::ActiveTexture(gl::TEXTURE0);
+
+            // Bind VAO to enable vertex attribute slots.
+            gl::BindVertexArray(self.vao);
+
+            // Bind VBO only once for buffer data upload only.
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.vbo);
+        }
+
+        let half_width = size_info.width() / 2.;
+        let half_height = size_info.height() / 2.;
+
+        let mut start = 0;
+        while start < graphics.len() {
+            // Find all cells using the same texture and z-index.
+            let placement = &graphics[start].graphic.placement;
+            let (z_index, id) = (placement.z_index, placement.texture.id);
+            let end = graphics[start..]
+                .iter()
+                .map(|graphic| &graphic.graphic.placement)
+                .position(|placement| placement.z_index != z_index || placement.texture.id != id)
+                .map_or(graphics.len(), |len| start + len);
+            let batch = &graphics[start..end];
+            start = end;
+
+            let texture = match self.textures.get(&id) {
+                Some(texture) => *texture,
+                None => continue,
+            };
+
+            self.vertices.clear();
+            for graphic in batch {
+                Self::add_cell(&mut self.vertices, size_info, half_width, half_height, graphic);
+            }
+
+            unsafe {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+
+                gl::BufferData(
+                    gl::ARRAY_BUFFER,
+                    (self.vertices.len() * mem::size_of::<Vertex>()) as isize,
+                    self.vertices.as_ptr() as *const _,
+                    gl::STREAM_DRAW,
+                );
+
+                gl::DrawArrays(gl::TRIANGLES, 0, self.vertices.len() as i32);
+            }
+        }
+
+        unsafe {
+            // Reset state to nothing.
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+            gl::BindVertexArray(0);
+            gl::UseProgram(0);
+        }
+    }
+
+    /// Add the vertices for the part of an image inside a single cell.
+    fn add_cell(
+        vertices: &mut Vec<Vertex>,
+        size_info: &SizeInfo,
+        half_width: f32,
+        half_height: f32,
+        graphic: &RenderGraphic,
+    ) {
+        let placement = &graphic.graphic.placement;
+        let texture = &placement.texture;
+        let cell_width = placement.cell_width as f32;
+        let cell_height = placement.cell_height as f32;
+
+        // Area of the placement covered by this cell, in pixels.
+        let cell_left = graphic.graphic.column as f32 * cell_width;
+        let cell_top = graphic.graphic.line as f32 * cell_height;
+        let destination = &placement.destination;
+        let left = cell_left.max(destination.x as f32);
+        let top = cell_top.max(destination.y as f32);
+        let right = (cell_left + cell_width).min((destination.x + destination.width) as f32);
+        let bottom = (cell_top + cell_height).min((destination.y + destination.height) as f32);
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        // Scale the image with the cell size, in case the font size has changed.
+        let scale_x = size_info.cell_width() / cell_width;
+        let scale_y = size_info.cell_height() / cell_height;
+        let x = size_info.padding_x()
+            + graphic.point.column.0 as f32 * size_info.cell_width()
+            + (left - cell_left) * scale_x;
+        let y = size_info.padding_y()
+            + graphic.point.line as f32 * size_info.cell_height()
+            + (top - cell_top) * scale_y;
+        let width = (right - left) * scale_x;
+        let height = (bottom - top) * scale_y;
+
+        // Calculate vertex positions in normalized device coordinates.
+        // NDC range from -1 to +1, with Y pointing up.
+        let x0 = x / half_width - 1.0;
+        let y0 = -y / half_height + 1.0;
+        let x1 = x0 + width / half_width;
+        let y1 = y0 - height / half_height;
+
+        // Map the covered area to the displayed part of the texture.
+        let source = &placement.source;
+        let source_scale_x = source.width as f32 / destination.width as f32;
+        let source_scale_y = source.height as f32 / destination.height as f32;
+        let source_x = |x: f32| source.x as f32 + (x - destination.x as f32) * source_scale_x;
+        let source_y = |y: f32| source.y as f32 + (y - destination.y as f32) * source_scale_y;
+        let u0 = source_x(left) / texture.width as f32;
+        let v0 = source_y(top) / texture.height as f32;
+        let u1 = source_x(right) / texture.width as f32;
+        let v1 = source_y(bottom) / texture.height as f32;
+
+        // Make quad vertices.
+        let quad = [
+            Vertex { x: x0, y: y0, u: u0, v: v0 },
+            Vertex { x: x0, y: y1, u: u0, v: v1 },
+            Vertex { x: x1, y: y0, u: u1, v: v0 },
+            Vertex { x: x1, y: y1, u: u1, v: v1 },
+        ];
+
+        // Append the vertices to form two triangles.
+        vertices.push(quad[0]);
+        vertices.push(quad[1]);
+        vertices.push(quad[2]);
+        vertices.push(quad[2]);
+        vertices.push(quad[3]);
+        vertices.push(quad[1]);
+    }
+}
+
+impl Drop for GraphicsRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            for texture in self.textures.values() {
+                gl::DeleteTextures(1, texture);
+            }
+
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteVertexArrays(1, &self.vao);
+        }
+    }
+}
@@ -9,6 +9,8 @@ use glutin::display::{GetGlDisplay, GlDisplay};
 use log::info;
 use once_cell::sync::OnceCell;
 
+use alacritty_terminal::graphics::UpdateQueues;
+use alacritty_terminal::grid::LineAttr;
 use alacritty_terminal::index::Point;
 use alacritty_terminal::term::cell::Flags;
 use alacritty_terminal::term::color::Rgb;
@@ -17,9 +19,11 @@ use crate::config::debug::RendererPreference;
 use crate::display::content::RenderableCell;
 use crate::display::SizeInfo;
 use crate::gl;
+use crate::renderer::graphics::{GraphicsRenderer, RenderGraphic};
 use crate::renderer::rects::{RectRenderer, RenderRect};
 use crate::renderer::shader::ShaderError;
 
+pub mod graphics;
 pub mod platform;
 pub mod rects;
 mod shader;
@@ -81,6 +85,7 @@ enum TextRendererProvider {
 pub struct Renderer {
     text_renderer: TextRendererProvider,
     rect_renderer: RectRenderer,
+    graphics_renderer: GraphicsRenderer,
 }
 
 impl Renderer {
@@ -120,18 +125,18 @@ impl Renderer {
             None => (version.as_ref() >= "3.3" && !is_gles_context, true),
         };
 
-        let (text_renderer, rect_renderer) = if use_glsl3 {
+        let (text_renderer, shader_version) = if use_glsl3 {
             let text_renderer = TextRendererProvider::Glsl3(Glsl3Renderer::new()?);
-            let rect_renderer = RectRenderer::new(ShaderVersion::Glsl3)?;
-            (text_renderer, rect_renderer)
+            (text_renderer, ShaderVersion::Glsl3)
         } else {
             let text_renderer =
                 TextRendererProvider::Gles2(Gles2Renderer::new(allow_dsb, is_gles_context)?);
-            let rect_renderer = RectRenderer::new(ShaderVersion::Gles2)?;
-            (text_renderer, rect_renderer)
+            (text_renderer, ShaderVersion::Gles2)
         };
+        let rect_renderer = RectRenderer::new(shader_version)?;
+        let graphics_renderer = GraphicsRenderer::new(shader_version)?;
 
-        Ok(Self { text_renderer, rect_renderer })
+        Ok(Self { text_renderer, rect_renderer, graphics_renderer })
     }
 
     pub fn draw_cells<I: Iterator<Item = RenderableCell>>(
@@ -166,6 +171,7 @@ impl Renderer {
             character,
             extra: None,
             flags: Flags::empty(),
+            line_attr: LineAttr::Single,
             bg_alpha: 1.0,
             fg,
             bg,
@@ -210,6 +216,36 @@ impl Renderer {
         }
     }
 
+    /// Upload new images and free the textures of removed ones.
+    pub fn update_graphics(&mut self, queues: UpdateQueues) {
+        self.graphics_renderer.update_textures(queues);
+    }
+
+    /// Draw the visible parts of all images.
+    pub fn draw_graphics(&mut self, size_info: &SizeInfo, graphics: Vec<RenderGraphic>) {
+        if graphics.is_empty() {
+            return;
+        }
+
+        // Prepare graphics rendering state.
+        unsafe {
+            // Remove padding from viewport.
+            gl::Viewport(0, 0, size_info.width() as i32, size_info.height() as i32);
+            gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::SRC_ALPHA, gl::ONE);
+        }
+
+        self.graphics_renderer.draw(size_info, graphics);
+
+        // Activate regular state again.
+        unsafe {
+            // Reset blending strategy.
+            gl::BlendFunc(gl::SRC1_COLOR, gl::ONE_MINUS_SRC1_COLOR);
+
+            // Restore viewport with padding.
+            self.set_viewport(size_info);
+        }
+    }
+
     /// Fill the window with `color` and `alpha`.
     pub fn clear(&self, color: Rgb, alpha: f32) {
         unsafe {
@@ -196,11 +196,9 @@ impl RenderLines {
         // The underline color escape does not apply to strikeout.
         let color = if flag.contains(Flags::STRIKEOUT) { cell.fg } else { cell.underline };
 
-        // Include wide char spacer if the current cell is a wide char.
+        // Include wide char spacer and the second half of double-width cells.
         let mut end = cell.point;
-        if cell.flags.contains(Flags::WIDE_CHAR) {
-            end.column += 1;
-        }
+        end.column += cell.columns() - 1;
 
         // Check if there's an active line.
         if let Some(line) = self.inner.get_mut(&flag).and_then(|lines| lines.last_mut()) {
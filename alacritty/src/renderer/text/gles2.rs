@@ -4,6 +4,7 @@ use std::ptr;
 use crossfont::RasterizedGlyph;
 use log::info;
 
+use alacritty_terminal::grid::LineAttr;
 use alacritty_terminal::term::cell::Flags;
 
 use crate::display::content::RenderableCell;
@@ -277,13 +278,41 @@ impl TextRenderBatch for Batch {
             self.tex = glyph.tex_id;
         }
 
+        let cell_width = size_info.cell_width() as i16;
+        let cell_height = size_info.cell_height() as i16;
+
         // Calculate the cell position.
-        let x = cell.point.column.0 as i16 * size_info.cell_width() as i16;
-        let y = cell.point.line as i16 * size_info.cell_height() as i16;
+        let x = cell.point.column.0 as i16 * cell_width;
+        let y = cell.point.line as i16 * cell_height;
+
+        // Glyphs of double-size lines are scaled, with the bottom half moved up by one cell.
+        let (scale_x, scale_y, shift) = match cell.line_attr {
+            LineAttr::Single => (1, 1, 0),
+            LineAttr::DoubleWidth => (2, 1, 0),
+            LineAttr::DoubleTop => (2, 2, 0),
+            LineAttr::DoubleBottom => (2, 2, cell_height),
+        };
 
         // Calculate the glyph position.
-        let glyph_x = cell.point.column.0 as i16 * size_info.cell_width() as i16 + glyph.left;
-        let glyph_y = (cell.point.line + 1) as i16 * size_info.cell_height() as i16 - glyph.top;
+        let glyph_x = x + glyph.left * scale_x;
+        let mut glyph_y = y + (cell_height - glyph.top) * scale_y - shift;
+        let glyph_width = glyph.width * scale_x;
+        let mut glyph_height = glyph.height * scale_y;
+
+        // Clip double-height glyphs to their half, adjusting the texture coordinates to match.
+        let mut uv_bot = glyph.uv_bot;
+        let mut uv_height = glyph.uv_height;
+        if scale_y > 1 && glyph_height > 0 {
+            let top = glyph_y.clamp(y, y + cell_height);
+            let bottom = (glyph_y + glyph_height).clamp(y, y + cell_height);
+
+            let uv_scale = glyph.uv_height / glyph_height as f32;
+            uv_bot += (top - glyph_y) as f32 * uv_scale;
+            uv_height = (bottom - top) as f32 * uv_scale;
+
+            glyph_y = top;
+            glyph_height = bottom - top;
+        }
 
         let colored = if glyph.multicolor {
             RenderingGlyphFlags::COLORED
@@ -292,16 +321,17 @@ impl TextRenderBatch for Batch {
         };
 
         let is_wide = if cell.flags.contains(Flags::WIDE_CHAR) { 2 } else { 1 };
+        let cell_columns = is_wide * scale_x;
 
         let mut vertex = TextVertex {
             x,
-            y: y + size_info.cell_height() as i16,
+            y: y + cell_height,
 
             glyph_x,
-            glyph_y: glyph_y + glyph.height,
+            glyph_y: glyph_y + glyph_height,
 
             u: glyph.uv_left,
-            v: glyph.uv_bot + glyph.uv_height,
+            v: uv_bot + uv_height,
             r: cell.fg.r,
             g: cell.fg.g,
             b: cell.fg.b,
@@ -317,21 +347,21 @@ impl TextRenderBatch for Batch {
         vertex.y = y;
         vertex.glyph_y = glyph_y;
         vertex.u = glyph.uv_left;
-        vertex.v = glyph.uv_bot;
+        vertex.v = uv_bot;
         self.vertices.push(vertex);
 
-        vertex.x = x + is_wide * size_info.cell_width() as i16;
-        vertex.glyph_x = glyph_x + glyph.width;
+        vertex.x = x + cell_columns * cell_width;
+        vertex.glyph_x = glyph_x + glyph_width;
         vertex.u = glyph.uv_left + glyph.uv_width;
-        vertex.v = glyph.uv_bot;
+        vertex.v = uv_bot;
         self.vertices.push(vertex);
 
-        vertex.x = x + is_wide * size_info.cell_width() as i16;
-        vertex.y = y + size_info.cell_height() as i16;
-        vertex.glyph_x = glyph_x + glyph.width;
-        vertex.glyph_y = glyph_y + glyph.height;
+        vertex.x = x + cell_columns * cell_width;
+        vertex.y = y + cell_height;
+        vertex.glyph_x = glyph_x + glyph_width;
+        vertex.glyph_y = glyph_y + glyph_height;
         vertex.u = glyph.uv_left + glyph.uv_width;
-        vertex.v = glyph.uv_bot + glyph.uv_height;
+        vertex.v = uv_bot + uv_height;
         self.vertices.push(vertex);
     }
 }
@@ -347,6 +347,7 @@ impl TextRenderBatch for Batch {
         let mut cell_flags = RenderingGlyphFlags::empty();
         cell_flags.set(RenderingGlyphFlags::COLORED, glyph.multicolor);
         cell_flags.set(RenderingGlyphFlags::WIDE_CHAR, cell.flags.contains(Flags::WIDE_CHAR));
+        cell_flags |= RenderingGlyphFlags::from(cell.line_attr);
 
         self.instances.push(InstanceData {
             col: cell.point.column.0 as u16,
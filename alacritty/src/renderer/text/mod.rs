@@ -1,6 +1,7 @@
 use bitflags::bitflags;
 use crossfont::{GlyphKey, RasterizedGlyph};
 
+use alacritty_terminal::grid::LineAttr;
 use alacritty_terminal::term::cell::Flags;
 
 use crate::display::content::RenderableCell;
@@ -24,8 +25,22 @@ use glyph_cache::{Glyph, LoadGlyph};
 bitflags! {
     #[repr(C)]
     struct RenderingGlyphFlags: u8 {
-        const COLORED   = 0b0000_0001;
-        const WIDE_CHAR = 0b0000_0010;
+        const COLORED       = 0b0000_0001;
+        const WIDE_CHAR     = 0b0000_0010;
+        const DOUBLE_WIDTH  = 0b0000_0100;
+        const DOUBLE_TOP    = 0b0000_1000;
+        const DOUBLE_BOTTOM = 0b0001_0000;
+    }
+}
+
+impl From<LineAttr> for RenderingGlyphFlags {
+    fn from(line_attr: LineAttr) -> Self {
+        match line_attr {
+            LineAttr::Single => Self::empty(),
+            LineAttr::DoubleWidth => Self::DOUBLE_WIDTH,
+            LineAttr::DoubleTop => Self::DOUBLE_WIDTH | Self::DOUBLE_TOP,
+            LineAttr::DoubleBottom => Self::DOUBLE_WIDTH | Self::DOUBLE_BOTTOM,
+        }
     }
 }
 
@@ -25,8 +25,10 @@ impl TimerId {
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Topic {
     SelectionScrolling,
+    DelayedSearch,
     BlinkCursor,
     BlinkTimeout,
+    Notification,
     Frame,
 }
 
@@ -94,6 +96,11 @@ impl Scheduler {
         self.timers.remove(index)
     }
 
+    /// Check if a timer is already scheduled.
+    pub fn scheduled(&mut self, id: TimerId) -> bool {
+        self.timers.iter().any(|timer| timer.id == id)
+    }
+
     /// Remove all timers scheduled for a window.
     ///
     /// This must be called when a window is removed to ensure that timers on intervals do not
@@ -6,9 +6,12 @@ use std::io::Write;
 use std::mem;
 #[cfg(not(windows))]
 use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(unix)]
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::thread::JoinHandle;
 
 use crossfont::Size;
 use glutin::config::GetGlConfig;
@@ -26,13 +29,19 @@ use winit::event_loop::{EventLoopProxy, EventLoopWindowTarget};
 use winit::window::WindowId;
 
 use alacritty_config::SerdeReplace;
+use alacritty_terminal::config::PtyConfig;
 use alacritty_terminal::event::Event as TerminalEvent;
-use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier};
-use alacritty_terminal::grid::Dimensions;
+#[cfg(unix)]
+use alacritty_terminal::event::Notify;
+use alacritty_terminal::event_loop::{EventLoop as PtyEventLoop, Msg, Notifier, State as PtyState};
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::Direction;
+#[cfg(unix)]
+use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::test::TermSize;
-use alacritty_terminal::term::Term;
-use alacritty_terminal::tty;
+use alacritty_terminal::term::{Term, TermMode};
+use alacritty_terminal::{thread, tty};
 
 #[cfg(unix)]
 use crate::cli::IpcConfig;
@@ -40,8 +49,14 @@ use crate::cli::WindowOptions;
 use crate::clipboard::Clipboard;
 use crate::config::UiConfig;
 use crate::display::window::Window;
-use crate::display::Display;
-use crate::event::{ActionContext, Event, EventProxy, EventType, Mouse, TouchPurpose};
+use crate::display::{Display, SizeInfo};
+use crate::event::{
+    ActionContext, Event, EventProxy, EventType, Mouse, SearchState, TouchPurpose,
+    CHILD_EXIT_MESSAGE_TARGET,
+};
+use crate::input::KeyboardState;
+#[cfg(unix)]
+use crate::ipc::WindowInfo;
 use crate::logging::LOG_TARGET_IPC_CONFIG;
 use crate::message_bar::MessageBuffer;
 use crate::scheduler::Scheduler;
@@ -54,10 +69,15 @@ pub struct WindowContext {
     event_queue: Vec<WinitEvent<'static, Event>>,
     terminal: Arc<FairMutex<Term<EventProxy>>>,
     cursor_blink_timed_out: bool,
+    search_state: SearchState,
     modifiers: ModifiersState,
     received_count: usize,
     suppress_chars: bool,
+    keyboard: KeyboardState,
     notifier: Notifier,
+    io_thread: Option<IoThread>,
+    pty_config: PtyConfig,
+    child_exited: bool,
     font_size: Size,
     mouse: Mouse,
     touch: TouchPurpose,
@@ -190,41 +210,20 @@ impl WindowContext {
         // This object contains all of the state about what's being displayed. It's
         // wrapped in a clonable mutex since both the I/O loop and display need to
         // access it.
-        let terminal = Term::new(&config.terminal_config, &display.size_info, event_proxy.clone());
+        let mut terminal =
+            Term::new(&config.terminal_config, &display.size_info, event_proxy.clone());
+        let size_info = &display.size_info;
+        terminal.set_cell_size(size_info.cell_width() as usize, size_info.cell_height() as usize);
         let terminal = Arc::new(FairMutex::new(terminal));
 
-        // Create the PTY.
-        //
-        // The PTY forks a process to run the shell on the slave side of the
-        // pseudoterminal. A file descriptor for the master side is retained for
-        // reading/writing to the shell.
-        let pty = tty::new(&pty_config, display.size_info.into(), display.window.id().into())?;
-
-        #[cfg(not(windows))]
-        let master_fd = pty.file().as_raw_fd();
-        #[cfg(not(windows))]
-        let shell_pid = pty.child().id();
-
-        // Create the pseudoterminal I/O loop.
-        //
-        // PTY I/O is ran on another thread as to not occupy cycles used by the
-        // renderer and input processing. Note that access to the terminal state is
-        // synchronized since the I/O loop updates the state, and the display
-        // consumes it periodically.
-        let event_loop = PtyEventLoop::new(
-            Arc::clone(&terminal),
+        let pty = Self::spawn_pty(
+            &terminal,
             event_proxy.clone(),
-            pty,
-            pty_config.hold,
+            &pty_config,
+            &display.size_info,
+            display.window.id(),
             config.debug.ref_test,
-        );
-
-        // The event loop channel allows write requests from the event processor
-        // to be sent to the pty loop and ultimately written to the pty.
-        let loop_tx = event_loop.channel();
-
-        // Kick off the I/O thread.
-        let _io_thread = event_loop.spawn();
+        )?;
 
         // Start cursor blinking, in case `Focused` isn't sent on startup.
         if config.terminal_config.cursor.style().blinking {
@@ -240,14 +239,19 @@ impl WindowContext {
             terminal,
             display,
             #[cfg(not(windows))]
-            master_fd,
+            master_fd: pty.master_fd,
             #[cfg(not(windows))]
-            shell_pid,
+            shell_pid: pty.shell_pid,
             config,
-            notifier: Notifier(loop_tx),
+            pty_config,
+            notifier: pty.notifier,
+            io_thread: Some(pty.io_thread),
+            child_exited: Default::default(),
             cursor_blink_timed_out: Default::default(),
             suppress_chars: Default::default(),
+            keyboard: Default::default(),
             message_buffer: Default::default(),
+            search_state: Default::default(),
             received_count: Default::default(),
             event_queue: Default::default(),
             ipc_config: Default::default(),
@@ -259,6 +263,116 @@ impl WindowContext {
         })
     }
 
+    /// Spawn a new shell with its PTY and I/O event loop.
+    fn spawn_pty(
+        terminal: &Arc<FairMutex<Term<EventProxy>>>,
+        event_proxy: EventProxy,
+        pty_config: &PtyConfig,
+        size_info: &SizeInfo,
+        window_id: WindowId,
+        ref_test: bool,
+    ) -> Result<PtyHandle, Box<dyn Error>> {
+        // Create the PTY.
+        //
+        // The PTY forks a process to run the shell on the slave side of the
+        // pseudoterminal. A file descriptor for the master side is retained for
+        // reading/writing to the shell.
+        let pty = tty::new(pty_config, (*size_info).into(), window_id.into())?;
+
+        #[cfg(not(windows))]
+        let master_fd = pty.file().as_raw_fd();
+        #[cfg(not(windows))]
+        let shell_pid = pty.child().id();
+
+        // Create the pseudoterminal I/O loop.
+        //
+        // PTY I/O is ran on another thread as to not occupy cycles used by the
+        // renderer and input processing. Note that access to the terminal state is
+        // synchronized since the I/O loop updates the state, and the display
+        // consumes it periodically.
+        let event_loop =
+            PtyEventLoop::new(Arc::clone(terminal), event_proxy, pty, pty_config.hold, ref_test);
+
+        // The event loop channel allows write requests from the event processor
+        // to be sent to the pty loop and ultimately written to the pty.
+        let loop_tx = event_loop.channel();
+
+        // Kick off the I/O thread.
+        let io_thread = event_loop.spawn();
+
+        Ok(PtyHandle {
+            notifier: Notifier(loop_tx),
+            io_thread,
+            #[cfg(not(windows))]
+            master_fd,
+            #[cfg(not(windows))]
+            shell_pid,
+        })
+    }
+
+    /// Terminate the shell, to replace it with a new one.
+    ///
+    /// The old PTY's event loop is stopped on a separate thread, since dropping the PTY blocks
+    /// until the shell has exited. Once it has stopped, a [`EventType::RespawnShell`] event is
+    /// sent to spawn the new shell.
+    pub fn restart_shell(&mut self, proxy: EventLoopProxy<Event>, keep_scrollback: bool) {
+        // Ignore restart requests while the old shell is still shutting down.
+        let io_thread = match self.io_thread.take() {
+            Some(io_thread) => io_thread,
+            None => return,
+        };
+
+        // Shutdown the old PTY's event loop, which terminates the old shell.
+        let _ = self.notifier.0.send(Msg::Shutdown);
+
+        let event_proxy = EventProxy::new(proxy, self.id());
+        thread::spawn_named("PTY shutdown", move || {
+            let event_loop = io_thread.join();
+
+            // The old event loop can't write to the terminal anymore, so it's safe to reset it.
+            event_proxy.send_event(EventType::RespawnShell { keep_scrollback });
+
+            drop(event_loop);
+        });
+    }
+
+    /// Replace the terminated shell with a new one.
+    ///
+    /// The terminal state is reset, the scrollback history is only kept with `keep_scrollback`.
+    pub fn respawn_shell(
+        &mut self,
+        proxy: EventLoopProxy<Event>,
+        keep_scrollback: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        self.terminal.lock().reset_for_new_child(keep_scrollback);
+
+        let event_proxy = EventProxy::new(proxy, self.id());
+        let pty = Self::spawn_pty(
+            &self.terminal,
+            event_proxy,
+            &self.pty_config,
+            &self.display.size_info,
+            self.id(),
+            self.config.debug.ref_test,
+        )?;
+
+        self.notifier = pty.notifier;
+        self.io_thread = Some(pty.io_thread);
+        #[cfg(not(windows))]
+        {
+            self.master_fd = pty.master_fd;
+            self.shell_pid = pty.shell_pid;
+        }
+
+        // Remove the exit status banner.
+        self.child_exited = false;
+        self.message_buffer.remove_target(CHILD_EXIT_MESSAGE_TARGET);
+        self.display.pending_update.dirty = true;
+        self.dirty = true;
+
+        Ok(())
+    }
+
     /// Update the terminal window to the latest config.
     pub fn update_config(&mut self, new_config: Rc<UiConfig>) {
         let old_config = mem::replace(&mut self.config, new_config);
@@ -309,11 +423,12 @@ impl WindowContext {
             self.display.pending_update.set_font(font);
         }
 
-        // Update display if either padding options or resize increments were changed.
+        // Update display if padding, resize increments or status line options were changed.
         let window_config = &old_config.window;
         if window_config.padding(1.) != self.config.window.padding(1.)
             || window_config.dynamic_padding != self.config.window.dynamic_padding
             || window_config.resize_increments != self.config.window.resize_increments
+            || window_config.indicator_status_line != self.config.window.indicator_status_line
         {
             self.display.pending_update.dirty = true;
         }
@@ -425,14 +540,19 @@ impl WindowContext {
 
         let mut terminal = self.terminal.lock();
 
+        let old_is_searching = self.search_state.history_index.is_some();
+
         let context = ActionContext {
             cursor_blink_timed_out: &mut self.cursor_blink_timed_out,
+            search_state: &mut self.search_state,
             message_buffer: &mut self.message_buffer,
             received_count: &mut self.received_count,
             suppress_chars: &mut self.suppress_chars,
+            keyboard: &mut self.keyboard,
             modifiers: &mut self.modifiers,
             font_size: &mut self.font_size,
             notifier: &mut self.notifier,
+            child_exited: &mut self.child_exited,
             display: &mut self.display,
             mouse: &mut self.mouse,
             touch: &mut self.touch,
@@ -444,6 +564,7 @@ impl WindowContext {
             #[cfg(not(windows))]
             shell_pid: self.shell_pid,
             preserve_title: self.preserve_title,
+            hold: self.pty_config.hold,
             config: &self.config,
             event_proxy,
             event_loop,
@@ -463,12 +584,21 @@ impl WindowContext {
                 &mut self.display,
                 &mut self.notifier,
                 &self.message_buffer,
+                &self.search_state,
+                old_is_searching,
                 &self.config,
             );
             self.dirty = true;
         }
 
+        // Update highlighted hints & re-draw on change.
         if self.dirty || self.mouse.hint_highlight_dirty {
+            self.dirty |= self.display.update_highlighted_hints(
+                &terminal,
+                &self.config,
+                &self.mouse,
+                self.modifiers,
+            );
             self.mouse.hint_highlight_dirty = false;
         }
 
@@ -483,8 +613,19 @@ impl WindowContext {
 
             self.dirty = false;
 
+            // Request immediate re-draw if visual bell animation is not finished yet.
+            if !self.display.visual_bell.completed() {
+                self.display.window.request_redraw();
+            }
+
             // Redraw the window.
-            self.display.draw(terminal, scheduler, &self.message_buffer, &self.config);
+            self.display.draw(
+                terminal,
+                scheduler,
+                &self.message_buffer,
+                &self.config,
+                &self.search_state,
+            );
         }
     }
 
@@ -493,6 +634,45 @@ impl WindowContext {
         self.display.window.id()
     }
 
+    /// Description of this window for IPC clients.
+    #[cfg(unix)]
+    pub fn info(&self) -> WindowInfo {
+        let size_info = &self.display.size_info;
+        WindowInfo {
+            id: self.id().into(),
+            title: self.display.window.title().to_owned(),
+            columns: size_info.columns(),
+            lines: size_info.screen_lines(),
+            width: size_info.width() as u32,
+            height: size_info.height() as u32,
+            pid: self.shell_pid,
+            working_directory: self.terminal.lock().working_directory().map(Path::to_path_buf),
+        }
+    }
+
+    /// Write bytes to the PTY.
+    #[cfg(unix)]
+    pub fn write_to_pty(&self, bytes: Vec<u8>) {
+        self.notifier.notify(bytes);
+    }
+
+    /// Text content of the visible screen, or the entire scrollback history.
+    #[cfg(unix)]
+    pub fn text(&self, scrollback: bool) -> String {
+        let terminal = self.terminal.lock();
+
+        let (start, end) = if scrollback {
+            (terminal.topmost_line(), terminal.bottommost_line())
+        } else {
+            let display_offset = terminal.grid().display_offset() as i32;
+            (Line(-display_offset), Line(terminal.screen_lines() as i32 - 1 - display_offset))
+        };
+
+        let start = Point::new(start, Column(0));
+        let end = Point::new(end, terminal.last_column());
+        terminal.bounds_to_string(start, end)
+    }
+
     /// Write the ref test results to the disk.
     pub fn write_ref_test_results(&self) {
         // Dump grid state.
@@ -527,12 +707,47 @@ impl WindowContext {
         display: &mut Display,
         notifier: &mut Notifier,
         message_buffer: &MessageBuffer,
+        search_state: &SearchState,
+        old_is_searching: bool,
         config: &UiConfig,
     ) {
-        display.handle_update(terminal, notifier, message_buffer, config);
+        // Compute cursor positions before resize.
+        let num_lines = terminal.screen_lines();
+        let cursor_at_bottom = terminal.grid().cursor.point.line + 1 == num_lines;
+        let origin_at_bottom = if terminal.mode().contains(TermMode::VI) {
+            terminal.vi_mode_cursor.point.line == num_lines - 1
+        } else {
+            search_state.direction == Direction::Left
+        };
+
+        display.handle_update(terminal, notifier, message_buffer, search_state, config);
+
+        let new_is_searching = search_state.history_index.is_some();
+        if !old_is_searching && new_is_searching {
+            // Scroll on search start to make sure origin is visible with minimal viewport motion.
+            let display_offset = terminal.grid().display_offset();
+            if display_offset == 0 && cursor_at_bottom && !origin_at_bottom {
+                terminal.scroll_display(Scroll::Delta(1));
+            } else if display_offset != 0 && origin_at_bottom {
+                terminal.scroll_display(Scroll::Delta(-1));
+            }
+        }
     }
 }
 
+/// Thread running the PTY I/O event loop.
+type IoThread = JoinHandle<(PtyEventLoop<tty::Pty, EventProxy>, PtyState)>;
+
+/// Handles of a shell spawned by [`WindowContext::spawn_pty`].
+struct PtyHandle {
+    notifier: Notifier,
+    io_thread: IoThread,
+    #[cfg(not(windows))]
+    master_fd: RawFd,
+    #[cfg(not(windows))]
+    shell_pid: u32,
+}
+
 impl Drop for WindowContext {
     fn drop(&mut self) {
         // Shutdown the terminal's PTY.
@@ -1,15 +1,22 @@
 //! ANSI Terminal Stream Parsing.
 
 use std::convert::TryFrom;
+use std::fmt::{self, Write};
+#[cfg(unix)]
+use std::os::unix::ffi::OsStringExt;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
-use std::{iter, str};
+use std::{iter, mem, str};
 
+use bitflags::bitflags;
 use log::{debug, trace};
 use serde::{Deserialize, Serialize};
 use vte::{Params, ParamsIter};
 
 use alacritty_config_derive::ConfigDeserialize;
 
+use crate::graphics::{kitty, sixel, GraphicData};
+use crate::grid::LineAttr;
 use crate::index::{Column, Line};
 use crate::term::cell::Hyperlink;
 use crate::term::color::Rgb;
@@ -20,14 +27,14 @@ const SYNC_UPDATE_TIMEOUT: Duration = Duration::from_millis(150);
 /// Maximum number of bytes read in one synchronized update (2MiB).
 const SYNC_BUFFER_SIZE: usize = 0x20_0000;
 
-/// Number of bytes in the synchronized update DCS sequence before the passthrough parameters.
-const SYNC_ESCAPE_START_LEN: usize = 5;
+/// Maximum number of bytes in a single APC sequence.
+const MAX_APC_SIZE: usize = kitty::MAX_PAYLOAD_SIZE + 0x1000;
 
-/// Start of the DCS sequence for beginning synchronized updates.
-const SYNC_START_ESCAPE_START: [u8; SYNC_ESCAPE_START_LEN] = [b'\x1b', b'P', b'=', b'1', b's'];
+/// Maximum number of bytes in a DECRQSS or XTGETTCAP request.
+const MAX_DCS_REQUEST_SIZE: usize = 0x1000;
 
-/// Start of the DCS sequence for terminating synchronized updates.
-const SYNC_END_ESCAPE_START: [u8; SYNC_ESCAPE_START_LEN] = [b'\x1b', b'P', b'=', b'2', b's'];
+/// Maximum number of bytes in the title and body of a desktop notification.
+const MAX_NOTIFICATION_SIZE: usize = 0x1_0000;
 
 /// Internal state for VTE processor.
 #[derive(Debug, Default)]
@@ -38,17 +45,37 @@ struct ProcessorState {
     /// DCS sequence waiting for termination.
     dcs: Option<Dcs>,
 
+    /// Whether the last processed byte was an escape.
+    escape: bool,
+
+    /// APC sequence waiting for termination.
+    apc: Option<Vec<u8>>,
+
+    /// Kitty graphics command waiting for the remaining chunks of its payload.
+    kitty_chunks: Option<kitty::Command>,
+
+    /// Defer kitty graphics commands which need to load an image.
+    defer_graphics: bool,
+
+    /// Kitty graphics command waiting for its image to be loaded.
+    deferred_graphic: Option<kitty::Command>,
+
+    /// Desktop notification and its ID, waiting for the remaining chunks of its OSC 99 payload.
+    notification_chunks: Option<(String, Notification)>,
+
     /// State for synchronized terminal updates.
     sync_state: SyncState,
 }
 
-#[derive(Debug)]
 struct SyncState {
     /// Expiration time of the synchronized update.
     timeout: Option<Instant>,
 
-    /// Sync DCS waiting for termination sequence.
-    pending_dcs: Option<Dcs>,
+    /// Parser looking for the extension or termination of the synchronized update.
+    parser: vte::Parser,
+
+    /// Sync sequences found by the parser.
+    performer: SyncPerformer,
 
     /// Bytes read during the synchronized update.
     buffer: Vec<u8>,
@@ -56,7 +83,71 @@ struct SyncState {
 
 impl Default for SyncState {
     fn default() -> Self {
-        Self { buffer: Vec::with_capacity(SYNC_BUFFER_SIZE), pending_dcs: None, timeout: None }
+        Self {
+            buffer: Vec::with_capacity(SYNC_BUFFER_SIZE),
+            parser: Default::default(),
+            performer: Default::default(),
+            timeout: None,
+        }
+    }
+}
+
+impl fmt::Debug for SyncState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SyncState")
+            .field("timeout", &self.timeout)
+            .field("performer", &self.performer)
+            .field("buffer", &self.buffer.len())
+            .finish()
+    }
+}
+
+/// Synchronized update sequence found while buffering.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SyncAction {
+    /// Extend the synchronized update.
+    Extend,
+
+    /// Terminate the synchronized update.
+    End,
+}
+
+/// Helper type that implements `vte::Perform` for buffered synchronized updates.
+///
+/// This only looks for the sequences extending or terminating the synchronized update, all other
+/// sequences are processed once the buffer is flushed.
+#[derive(Debug, Default)]
+struct SyncPerformer {
+    /// Sync sequence dispatched by the last byte.
+    action: Option<SyncAction>,
+
+    /// Sync DCS waiting for termination.
+    pending_dcs: Option<SyncAction>,
+}
+
+impl vte::Perform for SyncPerformer {
+    fn hook(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        self.pending_dcs = match (action, intermediates, params.iter().next()) {
+            ('s', [b'='], Some([1])) => Some(SyncAction::Extend),
+            ('s', [b'='], Some([2])) => Some(SyncAction::End),
+            _ => None,
+        };
+    }
+
+    fn unhook(&mut self) {
+        self.action = self.pending_dcs.take();
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], _ignore: bool, action: char) {
+        let sync_action = match (action, intermediates) {
+            ('h', [b'?']) => SyncAction::Extend,
+            ('l', [b'?']) => SyncAction::End,
+            _ => return,
+        };
+
+        if params.iter().any(|param| param[0] == Mode::SyncUpdate as u16) {
+            self.action = Some(sync_action);
+        }
     }
 }
 
@@ -66,8 +157,18 @@ enum Dcs {
     /// Begin of the synchronized update.
     SyncStart,
 
-    /// End of the synchronized update.
-    SyncEnd,
+    /// Sixel image data.
+    SixelData(Box<sixel::Parser>),
+
+    /// DECRQSS request for the value of a setting.
+    ///
+    /// This is `None` if the request exceeded the maximum request size.
+    StatusRequest(Option<Vec<u8>>),
+
+    /// XTGETTCAP request for hex encoded terminfo capabilities.
+    ///
+    /// This is `None` if the request exceeded the maximum request size.
+    TerminfoRequest(Option<Vec<u8>>),
 }
 
 /// The processor wraps a `vte::Parser` to ultimately call methods on a Handler.
@@ -90,28 +191,97 @@ impl Processor {
         H: Handler,
     {
         if self.state.sync_state.timeout.is_none() {
-            let mut performer = Performer::new(&mut self.state, handler);
-            self.parser.advance(&mut performer, byte);
+            self.advance_parser(handler, byte);
         } else {
             self.advance_sync(handler, byte);
         }
     }
 
+    /// Process a new byte from the PTY, without loading the images of kitty graphics commands.
+    ///
+    /// A returned command must be passed to [`Handler::kitty_graphics_command`] before processing
+    /// the next byte, which allows loading its image without holding the terminal lock.
+    #[inline]
+    pub fn advance_deferred<H>(&mut self, handler: &mut H, byte: u8) -> Option<kitty::Command>
+    where
+        H: Handler,
+    {
+        self.state.defer_graphics = true;
+        self.advance(handler, byte);
+        self.state.defer_graphics = false;
+
+        self.state.deferred_graphic.take()
+    }
+
     /// End a synchronized update.
     pub fn stop_sync<H>(&mut self, handler: &mut H)
     where
         H: Handler,
     {
+        // Synchronized updates are applied at once, including their images.
+        let defer_graphics = mem::replace(&mut self.state.defer_graphics, false);
+
         // Process all synchronized bytes.
         for i in 0..self.state.sync_state.buffer.len() {
             let byte = self.state.sync_state.buffer[i];
-            let mut performer = Performer::new(&mut self.state, handler);
-            self.parser.advance(&mut performer, byte);
+            self.advance_parser(handler, byte);
         }
 
+        self.state.defer_graphics = defer_graphics;
+
         // Resetting state after processing makes sure we don't interpret buffered sync escapes.
-        self.state.sync_state.buffer.clear();
-        self.state.sync_state.timeout = None;
+        let sync_state = &mut self.state.sync_state;
+        sync_state.buffer.clear();
+        sync_state.timeout = None;
+        sync_state.parser = Default::default();
+        sync_state.performer = Default::default();
+    }
+
+    /// Pass a byte to the VTE parser.
+    #[inline]
+    fn advance_parser<H>(&mut self, handler: &mut H, byte: u8)
+    where
+        H: Handler,
+    {
+        // APC sequences are ignored by the VTE parser, so they're collected separately.
+        if self.state.apc.is_some() || (byte == b'_' && self.state.escape) {
+            self.advance_apc(handler, byte);
+        }
+        self.state.escape = byte == 0x1b;
+
+        let mut performer = Performer::new(&mut self.state, handler);
+        self.parser.advance(&mut performer, byte);
+    }
+
+    /// Collect the contents of an APC sequence.
+    #[cold]
+    fn advance_apc<H>(&mut self, handler: &mut H, byte: u8)
+    where
+        H: Handler,
+    {
+        let apc = match &mut self.state.apc {
+            Some(apc) => apc,
+            None => {
+                self.state.apc = Some(Vec::new());
+                return;
+            },
+        };
+
+        match byte {
+            // Dispatch on ESC.
+            0x1b => {
+                let apc = self.state.apc.take().unwrap_or_default();
+                Performer::new(&mut self.state, handler).apc_dispatch(&apc);
+            },
+            // Cancel the APC sequence.
+            0x18 | 0x1a | 0x80..=0x9f => self.state.apc = None,
+            // Drop sequences which exceed the size limit.
+            _ if apc.len() >= MAX_APC_SIZE => {
+                debug!("Ignoring APC sequence exceeding {} bytes", MAX_APC_SIZE);
+                self.state.apc = None;
+            },
+            _ => apc.push(byte),
+        }
     }
 
     /// Synchronized update expiration time.
@@ -132,48 +302,18 @@ impl Processor {
     where
         H: Handler,
     {
-        self.state.sync_state.buffer.push(byte);
-
-        // Handle sync DCS escape sequences.
-        match self.state.sync_state.pending_dcs {
-            Some(_) => self.advance_sync_dcs_end(handler, byte),
-            None => self.advance_sync_dcs_start(),
-        }
-    }
-
-    /// Find the start of sync DCS sequences.
-    fn advance_sync_dcs_start(&mut self) {
-        // Get the last few bytes for comparison.
-        let len = self.state.sync_state.buffer.len();
-        let offset = len.saturating_sub(SYNC_ESCAPE_START_LEN);
-        let end = &self.state.sync_state.buffer[offset..];
-
-        // Check for extension/termination of the synchronized update.
-        if end == SYNC_START_ESCAPE_START {
-            self.state.sync_state.pending_dcs = Some(Dcs::SyncStart);
-        } else if end == SYNC_END_ESCAPE_START || len >= SYNC_BUFFER_SIZE - 1 {
-            self.state.sync_state.pending_dcs = Some(Dcs::SyncEnd);
-        }
-    }
+        let sync_state = &mut self.state.sync_state;
+        sync_state.buffer.push(byte);
+        sync_state.parser.advance(&mut sync_state.performer, byte);
 
-    /// Parse the DCS termination sequence for synchronized updates.
-    fn advance_sync_dcs_end<H>(&mut self, handler: &mut H, byte: u8)
-    where
-        H: Handler,
-    {
-        match byte {
-            // Ignore DCS passthrough characters.
-            0x00..=0x17 | 0x19 | 0x1c..=0x7f | 0xa0..=0xff => (),
-            // Cancel the DCS sequence.
-            0x18 | 0x1a | 0x80..=0x9f => self.state.sync_state.pending_dcs = None,
-            // Dispatch on ESC.
-            0x1b => match self.state.sync_state.pending_dcs.take() {
-                Some(Dcs::SyncStart) => {
-                    self.state.sync_state.timeout = Some(Instant::now() + SYNC_UPDATE_TIMEOUT);
-                },
-                Some(Dcs::SyncEnd) => self.stop_sync(handler),
-                None => (),
+        match sync_state.performer.action.take() {
+            Some(SyncAction::Extend) => {
+                sync_state.timeout = Some(Instant::now() + SYNC_UPDATE_TIMEOUT);
             },
+            Some(SyncAction::End) => self.stop_sync(handler),
+            // Flush the buffer once it's full.
+            None if sync_state.buffer.len() >= SYNC_BUFFER_SIZE => self.stop_sync(handler),
+            None => (),
         }
     }
 }
@@ -193,6 +333,99 @@ impl<'a, H: Handler + 'a> Performer<'a, H> {
     pub fn new<'b>(state: &'b mut ProcessorState, handler: &'b mut H) -> Performer<'b, H> {
         Performer { state, handler }
     }
+
+    /// Dispatch a terminated APC sequence.
+    fn apc_dispatch(&mut self, apc: &[u8]) {
+        match apc {
+            [b'G', data @ ..] => self.kitty_graphics_dispatch(data),
+            _ => debug!("[unhandled apc_dispatch]: {:?}", String::from_utf8_lossy(apc)),
+        }
+    }
+
+    /// Handle a kitty graphics command, waiting for all chunks of its payload.
+    fn kitty_graphics_dispatch(&mut self, data: &[u8]) {
+        let chunk = match kitty::Command::parse(data) {
+            Some(chunk) => chunk,
+            None => return,
+        };
+
+        let command = match self.state.kitty_chunks.take() {
+            Some(mut command) => {
+                command.append_chunk(chunk);
+                command
+            },
+            None => chunk,
+        };
+
+        if command.more {
+            self.state.kitty_chunks = Some(command);
+        } else if self.state.defer_graphics && command.loads_image() {
+            self.state.deferred_graphic = Some(command);
+        } else {
+            self.handler.kitty_graphics_command(command);
+        }
+    }
+
+    /// Handle a kitty desktop notification chunk, waiting for all chunks of its payload.
+    fn kitty_notification_dispatch(&mut self, metadata: &[u8], payload: &[u8]) {
+        let mut id = "";
+        let mut done = true;
+        let mut is_body = false;
+        let mut is_base64 = false;
+
+        // Metadata is in the format `key1=value1:key2=value2`.
+        for key_value in metadata.split(|&b| b == b':').filter(|kv| !kv.is_empty()) {
+            let (key, value) = match key_value.iter().position(|&b| b == b'=') {
+                Some(index) => (&key_value[..index], &key_value[index + 1..]),
+                None => (key_value, &[][..]),
+            };
+
+            match key {
+                b"i" => id = str::from_utf8(value).unwrap_or_default(),
+                b"d" => done = value != b"0",
+                b"e" => is_base64 = value == b"1",
+                b"p" => match value {
+                    b"title" => is_body = false,
+                    b"body" => is_body = true,
+                    _ => {
+                        debug!("Ignoring unsupported notification payload {:?}", value);
+                        return;
+                    },
+                },
+                _ => (),
+            }
+        }
+
+        let payload = if is_base64 {
+            match base64::decode(payload) {
+                Ok(payload) => payload,
+                Err(_) => {
+                    debug!("Ignoring notification with invalid base64 payload");
+                    return;
+                },
+            }
+        } else {
+            payload.to_vec()
+        };
+
+        // Continue the pending notification only if it uses the same ID.
+        let mut notification = match self.state.notification_chunks.take() {
+            Some((pending_id, notification)) if pending_id == id => notification,
+            _ => Notification::default(),
+        };
+
+        let size = notification.title.len() + notification.body.len() + payload.len();
+        if size <= MAX_NOTIFICATION_SIZE {
+            let text = if is_body { &mut notification.body } else { &mut notification.title };
+            text.push_str(&String::from_utf8_lossy(&payload));
+        }
+
+        if done {
+            self.handler.desktop_notification(notification);
+        } else {
+            self.state.notification_chunks = Some((id.to_owned(), notification));
+        }
+    }
 }
 
 /// Type that handles actions from the parser.
@@ -257,6 +490,9 @@ pub trait Handler {
     /// Carriage return.
     fn carriage_return(&mut self) {}
 
+    /// Ring the bell.
+    fn bell(&mut self) {}
+
     /// Linefeed.
     fn linefeed(&mut self) {}
 
@@ -333,9 +569,38 @@ pub trait Handler {
     /// Unset mode.
     fn unset_mode(&mut self, _: Mode) {}
 
+    /// DECRQM - Report whether an ANSI or private mode is set.
+    fn report_mode(&mut self, _private: bool, _mode: u16) {}
+
+    /// DECRQM - Report whether a synchronized update is active.
+    fn report_sync_update(&mut self, _active: bool) {}
+
+    /// DECRQSS - Report the current value of a setting, `None` if it is not supported.
+    fn report_status_setting(&mut self, _setting: Option<StatusSetting>) {}
+
+    /// XTGETTCAP - Report the value of a terminfo capability.
+    fn report_terminfo_capability(&mut self, _name: &str) {}
+
+    /// XTVERSION - Report the name and version of the terminal.
+    fn report_version(&mut self) {}
+
+    /// DECSSDT - Select the type of status line.
+    fn set_status_line_type(&mut self, _ty: StatusLineType) {}
+
+    /// DECSASD - Select whether output is written to the status line or the main display.
+    fn set_status_line_active(&mut self, _active: bool) {}
+
     /// DECSTBM - Set the terminal scrolling region.
     fn set_scrolling_region(&mut self, _top: usize, _bottom: Option<usize>) {}
 
+    /// DECSLRM - Set the left and right margins.
+    ///
+    /// This shares its escape with saving the cursor position, which is used unless left and
+    /// right margins are enabled.
+    fn set_left_right_margins(&mut self, _left: usize, _right: Option<usize>) {
+        self.save_cursor_position();
+    }
+
     /// DECKPAM - Set keypad to applications mode (ESCape instead of digits).
     fn set_keypad_application_mode(&mut self) {}
 
@@ -372,6 +637,9 @@ pub trait Handler {
     /// Run the decaln routine.
     fn decaln(&mut self) {}
 
+    /// DECSWL/DECDWL/DECDHL - Set the character size of the cursor line.
+    fn set_line_attr(&mut self, _: LineAttr) {}
+
     /// Push a title onto the stack.
     fn push_title(&mut self) {}
 
@@ -384,8 +652,161 @@ pub trait Handler {
     /// Report text area size in characters.
     fn text_area_size_chars(&mut self) {}
 
+    /// Report cell size in pixels.
+    fn cell_size_pixels(&mut self) {}
+
+    /// Report screen size in pixels.
+    fn screen_size_pixels(&mut self) {}
+
+    /// Report screen size in characters.
+    fn screen_size_chars(&mut self) {}
+
+    /// Report window position in pixels.
+    fn window_position(&mut self) {}
+
+    /// Report the window title, or the icon label if `icon_label` is set.
+    fn report_title(&mut self, _icon_label: bool) {}
+
+    /// Change the state of the window.
+    fn window_operation(&mut self, _operation: WindowOperation) {}
+
     /// Set hyperlink.
     fn set_hyperlink(&mut self, _: Option<Hyperlink>) {}
+
+    /// Mark the boundary of a semantic prompt zone at the cursor position.
+    fn prompt_mark(&mut self, _mark: PromptMark) {}
+
+    /// Report the working directory of the shell on the specified host.
+    fn set_working_directory(&mut self, _hostname: &str, _path: PathBuf) {}
+
+    /// Show a desktop notification.
+    fn desktop_notification(&mut self, _notification: Notification) {}
+
+    /// Push keyboard modes onto the kitty keyboard protocol stack.
+    fn push_keyboard_mode(&mut self, _mode: KeyboardModes) {}
+
+    /// Pop keyboard modes from the kitty keyboard protocol stack.
+    fn pop_keyboard_modes(&mut self, _to_pop: u16) {}
+
+    /// Modify the active kitty keyboard protocol modes.
+    fn set_keyboard_mode(&mut self, _mode: KeyboardModes, _behavior: KeyboardModesApplyBehavior) {}
+
+    /// Report the active kitty keyboard protocol modes.
+    fn report_keyboard_mode(&mut self) {}
+
+    /// Set the xterm modifyOtherKeys key encoding.
+    fn set_modify_other_keys(&mut self, _mode: ModifyOtherKeys) {}
+
+    /// Report the xterm modifyOtherKeys key encoding.
+    fn report_modify_other_keys(&mut self) {}
+
+    /// Insert an image at the cursor position.
+    fn insert_graphic(&mut self, _graphic: GraphicData) {}
+
+    /// XTSMGRAPHICS - Report graphics attributes.
+    fn graphics_attribute(&mut self, _item: u16, _action: u16) {}
+
+    /// Handle a command of the kitty graphics protocol.
+    fn kitty_graphics_command(&mut self, _command: kitty::Command) {}
+}
+
+/// Boundaries of semantic prompt zones, reported by the shell through OSC 133.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PromptMark {
+    /// Start of the prompt (`A`).
+    PromptStart,
+
+    /// Start of the command typed by the user (`B`).
+    CommandStart,
+
+    /// Start of the command output (`C`).
+    OutputStart,
+
+    /// End of the command, with its exit status (`D`).
+    CommandEnd(Option<i32>),
+}
+
+/// Type of the status line below the main display, selected through DECSSDT.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StatusLineType {
+    /// No status line.
+    None,
+
+    /// Status line showing terminal state.
+    Indicator,
+
+    /// Status line written by the application using DECSASD.
+    HostWritable,
+}
+
+impl Default for StatusLineType {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+/// Setting requested through DECRQSS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StatusSetting {
+    /// Character attributes (SGR).
+    Sgr,
+
+    /// Top and bottom margins (DECSTBM).
+    ScrollingRegion,
+
+    /// Left and right margins (DECSLRM).
+    HorizontalMargins,
+
+    /// Cursor style (DECSCUSR).
+    CursorStyle,
+
+    /// Character protection attribute (DECSCA).
+    ProtectedAttribute,
+}
+
+/// Desktop notification requested through OSC 9, OSC 777 or OSC 99.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Summary of the notification, might be empty.
+    pub title: String,
+
+    /// Notification text.
+    pub body: String,
+}
+
+/// Window state change requested through XTWINOPS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WindowOperation {
+    /// Minimize the window.
+    Iconify,
+
+    /// Restore a minimized window.
+    Deiconify,
+
+    /// Raise the window to the front of the stacking order.
+    Raise,
+
+    /// Lower the window to the bottom of the stacking order.
+    Lower,
+
+    /// Maximize or restore the window.
+    Maximize(bool),
+
+    /// Enter or leave fullscreen mode.
+    Fullscreen(bool),
+
+    /// Toggle fullscreen mode.
+    ToggleFullscreen,
+
+    /// Resize the text area in pixels.
+    ///
+    /// A dimension of zero keeps its current size.
+    ResizePixels { width: u16, height: u16 },
+
+    /// Resize the text area in characters.
+    ///
+    /// A dimension of zero keeps its current size.
+    ResizeChars { columns: u16, lines: u16 },
 }
 
 /// Terminal cursor configuration.
@@ -446,6 +867,10 @@ pub enum Mode {
     /// * `CSI 4 h` change to insert mode
     /// * `CSI 4 l` reset to replacement mode
     Insert = 4,
+    /// ?5
+    ///
+    /// Reverse video (DECSCNM), swapping the default foreground and background colors.
+    ReverseVideo = 5,
     /// ?6
     Origin = 6,
     /// ?7
@@ -459,8 +884,16 @@ pub enum Mode {
     LineFeedNewLine = 20,
     /// ?25
     ShowCursor = 25,
+    /// ?47
+    SwapScreen = 47,
+    /// ?69
+    LeftRightMargin = 69,
     /// ?1000
     ReportMouseClicks = 1000,
+    /// ?1001
+    ///
+    /// Highlight mouse tracking is recognized, but never enabled.
+    HighlightMouseTracking = 1001,
     /// ?1002
     ReportCellMouseMotion = 1002,
     /// ?1003
@@ -473,12 +906,22 @@ pub enum Mode {
     SgrMouse = 1006,
     /// ?1007
     AlternateScroll = 1007,
+    /// ?1015
+    UrxvtMouse = 1015,
+    /// ?1016
+    SgrPixelsMouse = 1016,
     /// ?1042
     UrgencyHints = 1042,
+    /// ?1047
+    SwapScreenAndClear = 1047,
+    /// ?1048
+    SaveRestoreCursor = 1048,
     /// ?1049
     SwapScreenAndSetRestoreCursor = 1049,
     /// ?2004
     BracketedPaste = 2004,
+    /// ?2026
+    SyncUpdate = 2026,
 }
 
 impl Mode {
@@ -494,20 +937,29 @@ impl Mode {
             Some(match num {
                 1 => Mode::CursorKeys,
                 3 => Mode::ColumnMode,
+                5 => Mode::ReverseVideo,
                 6 => Mode::Origin,
                 7 => Mode::LineWrap,
                 12 => Mode::BlinkingCursor,
                 25 => Mode::ShowCursor,
+                47 => Mode::SwapScreen,
+                69 => Mode::LeftRightMargin,
                 1000 => Mode::ReportMouseClicks,
+                1001 => Mode::HighlightMouseTracking,
                 1002 => Mode::ReportCellMouseMotion,
                 1003 => Mode::ReportAllMouseMotion,
                 1004 => Mode::ReportFocusInOut,
                 1005 => Mode::Utf8Mouse,
                 1006 => Mode::SgrMouse,
                 1007 => Mode::AlternateScroll,
+                1015 => Mode::UrxvtMouse,
+                1016 => Mode::SgrPixelsMouse,
                 1042 => Mode::UrgencyHints,
+                1047 => Mode::SwapScreenAndClear,
+                1048 => Mode::SaveRestoreCursor,
                 1049 => Mode::SwapScreenAndSetRestoreCursor,
                 2004 => Mode::BracketedPaste,
+                2026 => Mode::SyncUpdate,
                 _ => {
                     trace!("[unimplemented] primitive mode: {}", num);
                     return None;
@@ -523,6 +975,71 @@ impl Mode {
     }
 }
 
+/// Setting of a mode reported by DECRPM.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModeState {
+    NotRecognized = 0,
+    Set = 1,
+    Reset = 2,
+    PermanentlySet = 3,
+    PermanentlyReset = 4,
+}
+
+impl From<bool> for ModeState {
+    fn from(set: bool) -> Self {
+        if set {
+            Self::Set
+        } else {
+            Self::Reset
+        }
+    }
+}
+
+bitflags! {
+    /// Progressive enhancements of the kitty keyboard protocol.
+    pub struct KeyboardModes: u8 {
+        const NO_MODE                 = 0b0000_0000;
+        /// Report escape and modified keys as unambiguous escape codes.
+        const DISAMBIGUATE_ESC_CODES  = 0b0000_0001;
+        /// Report key repeat and release events.
+        const REPORT_EVENT_TYPES      = 0b0000_0010;
+        /// Report the shifted key in addition to the base key.
+        const REPORT_ALTERNATE_KEYS   = 0b0000_0100;
+        /// Report every key, including text keys, as escape codes.
+        const REPORT_ALL_KEYS_AS_ESC  = 0b0000_1000;
+        /// Report the text generated by a key alongside its escape code.
+        const REPORT_ASSOCIATED_TEXT  = 0b0001_0000;
+    }
+}
+
+/// How a new set of keyboard modes is combined with the active one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyboardModesApplyBehavior {
+    /// Replace the active modes.
+    Replace,
+    /// Add the modes to the active ones.
+    Union,
+    /// Remove the modes from the active ones.
+    Difference,
+}
+
+/// XTerm modifyOtherKeys encoding of modified keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ModifyOtherKeys {
+    /// Use the legacy encoding for all keys.
+    Reset,
+    /// Encode modified keys, except those with a well-known legacy encoding.
+    EnableExceptWellDefined,
+    /// Encode all modified keys.
+    EnableAll,
+}
+
+impl Default for ModifyOtherKeys {
+    fn default() -> Self {
+        Self::Reset
+    }
+}
+
 /// Mode for clearing line.
 ///
 /// Relative to cursor.
@@ -835,6 +1352,7 @@ where
             C0::SUB => self.handler.substitute(),
             C0::SI => self.handler.set_active_charset(CharsetIndex::G0),
             C0::SO => self.handler.set_active_charset(CharsetIndex::G1),
+            C0::BEL => self.handler.bell(),
             _ => debug!("[unhandled] execute byte={:02x}", byte),
         }
     }
@@ -848,6 +1366,11 @@ where
                     self.state.dcs = Some(Dcs::SyncStart);
                 }
             },
+            ('q', []) if !ignore => {
+                self.state.dcs = Some(Dcs::SixelData(Box::new(sixel::Parser::new(params))));
+            },
+            ('q', [b'$']) => self.state.dcs = Some(Dcs::StatusRequest(Some(Vec::new()))),
+            ('q', [b'+']) => self.state.dcs = Some(Dcs::TerminfoRequest(Some(Vec::new()))),
             _ => debug!(
                 "[unhandled hook] params={:?}, ints: {:?}, ignore: {:?}, action: {:?}",
                 params, intermediates, ignore, action
@@ -857,71 +1380,353 @@ where
 
     #[inline]
     fn put(&mut self, byte: u8) {
-        debug!("[unhandled put] byte={:?}", byte);
+        match &mut self.state.dcs {
+            Some(Dcs::SixelData(parser)) => parser.put(byte),
+            Some(Dcs::StatusRequest(request) | Dcs::TerminfoRequest(request)) => match request {
+                Some(data) if data.len() < MAX_DCS_REQUEST_SIZE => data.push(byte),
+                // Discard oversized requests, instead of answering a truncated one.
+                _ => *request = None,
+            },
+            _ => debug!("[unhandled put] byte={:?}", byte),
+        }
     }
 
     #[inline]
     fn unhook(&mut self) {
-        match self.state.dcs {
+        match self.state.dcs.take() {
             Some(Dcs::SyncStart) => {
                 self.state.sync_state.timeout = Some(Instant::now() + SYNC_UPDATE_TIMEOUT);
             },
-            Some(Dcs::SyncEnd) => (),
+            Some(Dcs::SixelData(parser)) => match parser.finish() {
+                Some(graphic) => self.handler.insert_graphic(graphic),
+                None => debug!("Ignoring empty sixel image"),
+            },
+            Some(Dcs::StatusRequest(request)) => {
+                // Oversized requests are answered as invalid.
+                let setting = match request.as_deref() {
+                    Some(b"m") => Some(StatusSetting::Sgr),
+                    Some(b"r") => Some(StatusSetting::ScrollingRegion),
+                    Some(b"s") => Some(StatusSetting::HorizontalMargins),
+                    Some(b" q") => Some(StatusSetting::CursorStyle),
+                    Some(b"\"q") => Some(StatusSetting::ProtectedAttribute),
+                    _ => None,
+                };
+                self.handler.report_status_setting(setting);
+            },
+            Some(Dcs::TerminfoRequest(None)) => debug!("Ignoring oversized XTGETTCAP request"),
+            Some(Dcs::TerminfoRequest(Some(request))) => {
+                for name in request.split(|&b| b == b';') {
+                    match parse_hex_string(name) {
+                        Some(name) => self.handler.report_terminfo_capability(&name),
+                        None => debug!("Invalid XTGETTCAP capability name: {:?}", name),
+                    }
+                }
+            },
             _ => debug!("[unhandled unhook]"),
         }
     }
 
-    #[allow(clippy::cognitive_complexity)]
     #[inline]
-    fn csi_dispatch(
-        &mut self,
-        params: &Params,
-        intermediates: &[u8],
-        has_ignored_intermediates: bool,
-        action: char,
-    ) {
-        macro_rules! unhandled {
-            () => {{
-                debug!(
-                    "[Unhandled CSI] action={:?}, params={:?}, intermediates={:?}",
-                    action, params, intermediates
-                );
-            }};
+    fn osc_dispatch(&mut self, params: &[&[u8]], bell_terminated: bool) {
+        let terminator = if bell_terminated { "\x07" } else { "\x1b\\" };
+
+        fn unhandled(params: &[&[u8]]) {
+            let mut buf = String::new();
+            for items in params {
+                buf.push('[');
+                for item in *items {
+                    let _ = write!(buf, "{:?}", *item as char);
+                }
+                buf.push_str("],");
+            }
+            debug!("[unhandled osc_dispatch]: [{}] at line {}", &buf, line!());
         }
 
-        if has_ignored_intermediates || intermediates.len() > 1 {
-            unhandled!();
+        if params.is_empty() || params[0].is_empty() {
             return;
         }
 
-        let mut params_iter = params.iter();
-        let handler = &mut self.handler;
+        match params[0] {
+            // Set window title.
+            b"0" | b"2" => {
+                if params.len() >= 2 {
+                    let title = params[1..]
+                        .iter()
+                        .flat_map(|x| str::from_utf8(x))
+                        .collect::<Vec<&str>>()
+                        .join(";")
+                        .trim()
+                        .to_owned();
+                    self.handler.set_title(Some(title));
+                    return;
+                }
+                unhandled(params);
+            },
 
-        let mut next_param_or = |default: u16| match params_iter.next() {
-            Some(&[param, ..]) if param != 0 => param,
-            _ => default,
-        };
+            // Set color index.
+            b"4" => {
+                if params.len() <= 1 || params.len() % 2 == 0 {
+                    unhandled(params);
+                    return;
+                }
 
-        match (action, intermediates) {
-            ('@', []) => handler.insert_blank(next_param_or(1) as usize),
-            ('A', []) => handler.move_up(next_param_or(1) as usize),
-            ('B', []) | ('e', []) => handler.move_down(next_param_or(1) as usize),
-            ('b', []) => {
-                if let Some(c) = self.state.preceding_char {
-                    for _ in 0..next_param_or(1) {
-                        handler.input(c);
+                for chunk in params[1..].chunks(2) {
+                    let index = match parse_number(chunk[0]) {
+                        Some(index) => index,
+                        None => {
+                            unhandled(params);
+                            continue;
+                        },
+                    };
+
+                    if let Some(c) = xparse_color(chunk[1]) {
+                        self.handler.set_color(index as usize, c);
+                    } else if chunk[1] == b"?" {
+                        let prefix = format!("4;{index}");
+                        self.handler.dynamic_color_sequence(prefix, index as usize, terminator);
+                    } else {
+                        unhandled(params);
                     }
-                } else {
-                    debug!("tried to repeat with no preceding char");
                 }
             },
-            ('C', []) | ('a', []) => handler.move_forward(Column(next_param_or(1) as usize)),
-            ('c', intermediates) if next_param_or(0) == 0 => {
-                handler.identify_terminal(intermediates.first().map(|&i| i as char))
+
+            // Hyperlink.
+            b"8" if params.len() > 2 => {
+                let link_params = params[1];
+
+                // URIs may contain semicolons, which are treated as parameter separators.
+                let uri = params[2..]
+                    .iter()
+                    .flat_map(|x| str::from_utf8(x))
+                    .collect::<Vec<&str>>()
+                    .join(";");
+
+                // The OSC 8 escape sequence must be stopped when getting an empty `uri`.
+                if uri.is_empty() {
+                    self.handler.set_hyperlink(None);
+                    return;
+                }
+
+                // Link parameters are in format of `key1=value1:key2=value2`. Currently only key
+                // `id` is defined.
+                let id = link_params
+                    .split(|&b| b == b':')
+                    .find_map(|kv| kv.strip_prefix(b"id="))
+                    .and_then(|kv| str::from_utf8(kv).ok())
+                    .filter(|id| !id.is_empty());
+
+                self.handler.set_hyperlink(Some(Hyperlink::new(id, uri)));
             },
-            ('D', []) => handler.move_backward(Column(next_param_or(1) as usize)),
-            ('d', []) => handler.goto_line(Line(next_param_or(1) as i32 - 1)),
-            ('E', []) => handler.move_down_and_cr(next_param_or(1) as usize),
+
+            // Report current working directory.
+            b"7" if params.len() >= 2 => {
+                // Paths may contain semicolons, which are treated as parameter separators.
+                let uri = params[1..].join(&b';');
+
+                match parse_file_uri(&uri) {
+                    Some((hostname, path)) => self.handler.set_working_directory(hostname, path),
+                    None => unhandled(params),
+                }
+            },
+
+            // Desktop notification.
+            b"9" if params.len() >= 2 => {
+                // ConEmu uses numeric parameters for other features like progress reports.
+                if params.len() > 2 && params[1].iter().all(u8::is_ascii_digit) {
+                    unhandled(params);
+                    return;
+                }
+
+                // Messages may contain semicolons, which are treated as parameter separators.
+                let body = String::from_utf8_lossy(&params[1..].join(&b';')).into_owned();
+                self.handler.desktop_notification(Notification { title: String::new(), body });
+            },
+
+            // Desktop notification with title.
+            b"777" if params.len() >= 3 && params[1] == b"notify" => {
+                let title = String::from_utf8_lossy(params[2]).into_owned();
+                let body = String::from_utf8_lossy(&params[3..].join(&b';')).into_owned();
+                self.handler.desktop_notification(Notification { title, body });
+            },
+
+            // Kitty desktop notification.
+            b"99" if params.len() >= 3 => {
+                let payload = params[2..].join(&b';');
+                self.kitty_notification_dispatch(params[1], &payload);
+            },
+
+            // Shell integration prompt marks.
+            b"133" if params.len() >= 2 => {
+                let mark = match params[1] {
+                    b"A" => PromptMark::PromptStart,
+                    b"B" => PromptMark::CommandStart,
+                    b"C" => PromptMark::OutputStart,
+                    b"D" => {
+                        let exit_code = params
+                            .get(2)
+                            .and_then(|code| str::from_utf8(code).ok())
+                            .and_then(|code| code.parse().ok());
+                        PromptMark::CommandEnd(exit_code)
+                    },
+                    _ => {
+                        unhandled(params);
+                        return;
+                    },
+                };
+
+                self.handler.prompt_mark(mark);
+            },
+
+            // Get/set Foreground, Background, Cursor colors.
+            b"10" | b"11" | b"12" => {
+                if params.len() >= 2 {
+                    if let Some(mut dynamic_code) = parse_number(params[0]) {
+                        for param in &params[1..] {
+                            // 10 is the first dynamic color, also the foreground.
+                            let offset = dynamic_code as usize - 10;
+                            let index = NamedColor::Foreground as usize + offset;
+
+                            // End of setting dynamic colors.
+                            if index > NamedColor::Cursor as usize {
+                                unhandled(params);
+                                break;
+                            }
+
+                            if let Some(color) = xparse_color(param) {
+                                self.handler.set_color(index, color);
+                            } else if param == b"?" {
+                                self.handler.dynamic_color_sequence(
+                                    dynamic_code.to_string(),
+                                    index,
+                                    terminator,
+                                );
+                            } else {
+                                unhandled(params);
+                            }
+                            dynamic_code += 1;
+                        }
+                        return;
+                    }
+                }
+                unhandled(params);
+            },
+
+            // Set cursor style.
+            b"50" => {
+                if params.len() >= 2
+                    && params[1].len() >= 13
+                    && params[1][0..12] == *b"CursorShape="
+                {
+                    let shape = match params[1][12] as char {
+                        '0' => CursorShape::Block,
+                        '1' => CursorShape::Beam,
+                        '2' => CursorShape::Underline,
+                        _ => return unhandled(params),
+                    };
+                    self.handler.set_cursor_shape(shape);
+                    return;
+                }
+                unhandled(params);
+            },
+
+            // Set clipboard.
+            b"52" => {
+                if params.len() < 3 {
+                    return unhandled(params);
+                }
+
+                let clipboard = params[1].first().unwrap_or(&b'c');
+                match params[2] {
+                    b"?" => self.handler.clipboard_load(*clipboard, terminator),
+                    base64 => self.handler.clipboard_store(*clipboard, base64),
+                }
+            },
+
+            // Reset color index.
+            b"104" => {
+                // Reset all color indexes when no parameters are given.
+                if params.len() == 1 || params[1].is_empty() {
+                    for i in 0..256 {
+                        self.handler.reset_color(i);
+                    }
+                    return;
+                }
+
+                // Reset color indexes given as parameters.
+                for param in &params[1..] {
+                    match parse_number(param) {
+                        Some(index) => self.handler.reset_color(index as usize),
+                        None => unhandled(params),
+                    }
+                }
+            },
+
+            // Reset foreground color.
+            b"110" => self.handler.reset_color(NamedColor::Foreground as usize),
+
+            // Reset background color.
+            b"111" => self.handler.reset_color(NamedColor::Background as usize),
+
+            // Reset text cursor color.
+            b"112" => self.handler.reset_color(NamedColor::Cursor as usize),
+
+            _ => unhandled(params),
+        }
+    }
+
+    #[allow(clippy::cognitive_complexity)]
+    #[inline]
+    fn csi_dispatch(
+        &mut self,
+        params: &Params,
+        intermediates: &[u8],
+        has_ignored_intermediates: bool,
+        action: char,
+    ) {
+        macro_rules! unhandled {
+            () => {{
+                debug!(
+                    "[Unhandled CSI] action={:?}, params={:?}, intermediates={:?}",
+                    action, params, intermediates
+                );
+            }};
+        }
+
+        // Private DECRQM is the only sequence with multiple intermediates.
+        let decrqm = action == 'p' && intermediates == [b'?', b'$'];
+        if has_ignored_intermediates || (intermediates.len() > 1 && !decrqm) {
+            unhandled!();
+            return;
+        }
+
+        let mut params_iter = params.iter();
+        let handler = &mut self.handler;
+
+        let mut next_param_or = |default: u16| match params_iter.next() {
+            Some(&[param, ..]) if param != 0 => param,
+            _ => default,
+        };
+
+        match (action, intermediates) {
+            ('@', []) => handler.insert_blank(next_param_or(1) as usize),
+            ('A', []) => handler.move_up(next_param_or(1) as usize),
+            ('B', []) | ('e', []) => handler.move_down(next_param_or(1) as usize),
+            ('b', []) => {
+                if let Some(c) = self.state.preceding_char {
+                    for _ in 0..next_param_or(1) {
+                        handler.input(c);
+                    }
+                } else {
+                    debug!("tried to repeat with no preceding char");
+                }
+            },
+            ('C', []) | ('a', []) => handler.move_forward(Column(next_param_or(1) as usize)),
+            ('c', intermediates) if next_param_or(0) == 0 => {
+                handler.identify_terminal(intermediates.first().map(|&i| i as char))
+            },
+            ('D', []) => handler.move_backward(Column(next_param_or(1) as usize)),
+            ('d', []) => handler.goto_line(Line(next_param_or(1) as i32 - 1)),
+            ('E', []) => handler.move_down_and_cr(next_param_or(1) as usize),
             ('F', []) => handler.move_up_and_cr(next_param_or(1) as usize),
             ('G', []) | ('`', []) => handler.goto_col(Column(next_param_or(1) as usize - 1)),
             ('g', []) => {
@@ -944,6 +1749,11 @@ where
             ('h', intermediates) => {
                 for param in params_iter.map(|param| param[0]) {
                     match Mode::from_primitive(intermediates.first(), param) {
+                        // Synchronized updates are buffered by the processor.
+                        Some(Mode::SyncUpdate) => {
+                            self.state.sync_state.timeout =
+                                Some(Instant::now() + SYNC_UPDATE_TIMEOUT);
+                        },
                         Some(mode) => handler.set_mode(mode),
                         None => unhandled!(),
                     }
@@ -981,6 +1791,9 @@ where
             ('l', intermediates) => {
                 for param in params_iter.map(|param| param[0]) {
                     match Mode::from_primitive(intermediates.first(), param) {
+                        // Terminating synchronized updates is detected while buffering, this only
+                        // marks the end of the buffer for mode reports while it's flushed.
+                        Some(Mode::SyncUpdate) => self.state.sync_state.timeout = None,
                         Some(mode) => handler.unset_mode(mode),
                         None => unhandled!(),
                     }
@@ -999,8 +1812,61 @@ where
                     }
                 }
             },
+            ('m', [b'>']) => {
+                // XTMODKEYS without parameters resets all resources.
+                //
+                // The parser reports an omitted parameter as `0`, so this also covers the
+                // unsupported modifyKeyboard resource.
+                if params.len() == 1 && params.iter().next() == Some(&[0]) {
+                    handler.set_modify_other_keys(ModifyOtherKeys::Reset);
+                    return;
+                }
+
+                // Only the modifyOtherKeys resource is supported.
+                if next_param_or(0) != 4 {
+                    unhandled!();
+                    return;
+                }
+
+                let mode = match next_param_or(0) {
+                    0 => ModifyOtherKeys::Reset,
+                    1 => ModifyOtherKeys::EnableExceptWellDefined,
+                    2 => ModifyOtherKeys::EnableAll,
+                    _ => {
+                        unhandled!();
+                        return;
+                    },
+                };
+                handler.set_modify_other_keys(mode);
+            },
+            ('m', [b'?']) => match next_param_or(0) {
+                4 => handler.report_modify_other_keys(),
+                _ => unhandled!(),
+            },
             ('n', []) => handler.device_status(next_param_or(0) as usize),
+            ('n', [b'>']) => match next_param_or(0) {
+                4 => handler.set_modify_other_keys(ModifyOtherKeys::Reset),
+                _ => unhandled!(),
+            },
+            ('}', [b'$']) => match next_param_or(0) {
+                0 => handler.set_status_line_active(false),
+                1 => handler.set_status_line_active(true),
+                _ => unhandled!(),
+            },
+            ('~', [b'$']) => match next_param_or(0) {
+                0 => handler.set_status_line_type(StatusLineType::None),
+                1 => handler.set_status_line_type(StatusLineType::Indicator),
+                2 => handler.set_status_line_type(StatusLineType::HostWritable),
+                _ => unhandled!(),
+            },
             ('P', []) => handler.delete_chars(next_param_or(1) as usize),
+            ('p', [b'$']) => handler.report_mode(false, next_param_or(0)),
+            ('p', [b'?', b'$']) => match next_param_or(0) {
+                // Synchronized updates are buffered by the processor.
+                2026 => handler.report_sync_update(self.state.sync_state.timeout.is_some()),
+                mode => handler.report_mode(true, mode),
+            },
+            ('q', [b'>']) if next_param_or(0) == 0 => handler.report_version(),
             ('q', [b' ']) => {
                 // DECSCUSR (CSI Ps SP q) -- Set Cursor Style.
                 let cursor_style_id = next_param_or(0);
@@ -1027,16 +1893,78 @@ where
                 handler.set_scrolling_region(top, bottom);
             },
             ('S', []) => handler.scroll_up(next_param_or(1) as usize),
-            ('s', []) => handler.save_cursor_position(),
+            ('S', [b'?']) => {
+                let item = next_param_or(0);
+                let action = next_param_or(0);
+                handler.graphics_attribute(item, action);
+            },
+            ('s', []) => {
+                let left = next_param_or(1) as usize;
+                let right =
+                    params_iter.next().map(|param| param[0] as usize).filter(|&param| param != 0);
+
+                handler.set_left_right_margins(left, right);
+            },
             ('T', []) => handler.scroll_down(next_param_or(1) as usize),
-            ('t', []) => match next_param_or(1) as usize {
+            // Window operations without parameters are ignored.
+            ('t', []) => match next_param_or(0) as usize {
+                1 => handler.window_operation(WindowOperation::Deiconify),
+                2 => handler.window_operation(WindowOperation::Iconify),
+                4 => {
+                    let height = next_param_or(0);
+                    let width = next_param_or(0);
+                    handler.window_operation(WindowOperation::ResizePixels { width, height });
+                },
+                5 => handler.window_operation(WindowOperation::Raise),
+                6 => handler.window_operation(WindowOperation::Lower),
+                8 => {
+                    let lines = next_param_or(0);
+                    let columns = next_param_or(0);
+                    handler.window_operation(WindowOperation::ResizeChars { columns, lines });
+                },
+                9 => match next_param_or(0) {
+                    0 => handler.window_operation(WindowOperation::Maximize(false)),
+                    1 => handler.window_operation(WindowOperation::Maximize(true)),
+                    _ => unhandled!(),
+                },
+                10 => match next_param_or(0) {
+                    0 => handler.window_operation(WindowOperation::Fullscreen(false)),
+                    1 => handler.window_operation(WindowOperation::Fullscreen(true)),
+                    2 => handler.window_operation(WindowOperation::ToggleFullscreen),
+                    _ => unhandled!(),
+                },
+                13 => handler.window_position(),
                 14 => handler.text_area_size_pixels(),
+                15 => handler.screen_size_pixels(),
+                16 => handler.cell_size_pixels(),
                 18 => handler.text_area_size_chars(),
+                19 => handler.screen_size_chars(),
+                20 => handler.report_title(true),
+                21 => handler.report_title(false),
                 22 => handler.push_title(),
                 23 => handler.pop_title(),
                 _ => unhandled!(),
             },
             ('u', []) => handler.restore_cursor_position(),
+            ('u', [b'?']) => handler.report_keyboard_mode(),
+            ('u', [b'=']) => {
+                let mode = KeyboardModes::from_bits_truncate(next_param_or(0) as u8);
+                let behavior = match next_param_or(1) {
+                    1 => KeyboardModesApplyBehavior::Replace,
+                    2 => KeyboardModesApplyBehavior::Union,
+                    3 => KeyboardModesApplyBehavior::Difference,
+                    _ => {
+                        unhandled!();
+                        return;
+                    },
+                };
+                handler.set_keyboard_mode(mode, behavior);
+            },
+            ('u', [b'>']) => {
+                let mode = KeyboardModes::from_bits_truncate(next_param_or(0) as u8);
+                handler.push_keyboard_mode(mode);
+            },
+            ('u', [b'<']) => handler.pop_keyboard_modes(next_param_or(1)),
             ('X', []) => handler.erase_chars(Column(next_param_or(1) as usize)),
             ('Z', []) => handler.move_backward_tabs(next_param_or(1)),
             _ => unhandled!(),
@@ -1085,6 +2013,10 @@ where
                 configure_charset!(StandardCharset::SpecialCharacterAndLineDrawing, intermediates)
             },
             (b'7', []) => self.handler.save_cursor_position(),
+            (b'3', [b'#']) => self.handler.set_line_attr(LineAttr::DoubleTop),
+            (b'4', [b'#']) => self.handler.set_line_attr(LineAttr::DoubleBottom),
+            (b'5', [b'#']) => self.handler.set_line_attr(LineAttr::Single),
+            (b'6', [b'#']) => self.handler.set_line_attr(LineAttr::DoubleWidth),
             (b'8', [b'#']) => self.handler.decaln(),
             (b'8', []) => self.handler.restore_cursor_position(),
             (b'=', []) => self.handler.set_keypad_application_mode(),
@@ -1096,6 +2028,119 @@ where
     }
 }
 
+/// Parse colors in XParseColor format.
+fn xparse_color(color: &[u8]) -> Option<Rgb> {
+    if !color.is_empty() && color[0] == b'#' {
+        parse_legacy_color(&color[1..])
+    } else if color.len() >= 4 && &color[..4] == b"rgb:" {
+        parse_rgb_color(&color[4..])
+    } else {
+        None
+    }
+}
+
+/// Parse colors in `rgb:r(rrr)/g(ggg)/b(bbb)` format.
+fn parse_rgb_color(color: &[u8]) -> Option<Rgb> {
+    let colors = str::from_utf8(color).ok()?.split('/').collect::<Vec<_>>();
+
+    if colors.len() != 3 {
+        return None;
+    }
+
+    // Scale values instead of filling with `0`s.
+    let scale = |input: &str| {
+        if input.len() > 4 {
+            None
+        } else {
+            let max = u32::pow(16, input.len() as u32) - 1;
+            let value = u32::from_str_radix(input, 16).ok()?;
+            Some((255 * value / max) as u8)
+        }
+    };
+
+    Some(Rgb { r: scale(colors[0])?, g: scale(colors[1])?, b: scale(colors[2])? })
+}
+
+/// Parse colors in `#r(rrr)g(ggg)b(bbb)` format.
+fn parse_legacy_color(color: &[u8]) -> Option<Rgb> {
+    let item_len = color.len() / 3;
+
+    // Truncate/Fill to two byte precision.
+    let color_from_slice = |slice: &[u8]| {
+        let col = usize::from_str_radix(str::from_utf8(slice).ok()?, 16).ok()? << 4;
+        Some((col >> (4 * slice.len().saturating_sub(1))) as u8)
+    };
+
+    Some(Rgb {
+        r: color_from_slice(&color[0..item_len])?,
+        g: color_from_slice(&color[item_len..item_len * 2])?,
+        b: color_from_slice(&color[item_len * 2..])?,
+    })
+}
+
+/// Parse a decimal number that fits into a `u8`.
+fn parse_number(input: &[u8]) -> Option<u8> {
+    if input.is_empty() {
+        return None;
+    }
+    let mut num: u8 = 0;
+    for c in input {
+        let c = *c as char;
+        if let Some(digit) = c.to_digit(10) {
+            num = num.checked_mul(10).and_then(|v| v.checked_add(digit as u8))?
+        } else {
+            return None;
+        }
+    }
+    Some(num)
+}
+
+/// Parse a string of hex encoded bytes.
+fn parse_hex_string(hex: &[u8]) -> Option<String> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    let bytes = hex
+        .chunks(2)
+        .map(|byte| u8::from_str_radix(str::from_utf8(byte).ok()?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+/// Parse a `file://hostname/path` URI into its hostname and percent-decoded path.
+fn parse_file_uri(uri: &[u8]) -> Option<(&str, PathBuf)> {
+    let uri = uri.strip_prefix(b"file://")?;
+    let path_start = uri.iter().position(|&b| b == b'/')?;
+    let hostname = str::from_utf8(&uri[..path_start]).ok()?;
+
+    let mut path = Vec::with_capacity(uri.len() - path_start);
+    let mut bytes = uri[path_start..].iter();
+    while let Some(&byte) = bytes.next() {
+        if byte == b'%' {
+            let hex = [*bytes.next()?, *bytes.next()?];
+            path.push(u8::from_str_radix(str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            path.push(byte);
+        }
+    }
+
+    #[cfg(unix)]
+    let path = PathBuf::from(std::ffi::OsString::from_vec(path));
+
+    // Strip the leading slash in front of drive letters, like in `/C:/Users`.
+    #[cfg(windows)]
+    let path = {
+        let path = String::from_utf8(path).ok()?;
+        match path.get(2..3) {
+            Some(":") => PathBuf::from(&path[1..]),
+            _ => PathBuf::from(path),
+        }
+    };
+
+    Some((hostname, path))
+}
+
 #[inline]
 fn attrs_from_sgr_parameters(params: &mut ParamsIter<'_>) -> Vec<Option<Attr>> {
     let mut attrs = Vec::with_capacity(params.size_hint().0);
@@ -1295,6 +2340,31 @@ mod tests {
         identity_reported: bool,
         color: Option<Rgb>,
         reset_colors: Vec<usize>,
+        title: Option<String>,
+        color_requests: Vec<(String, usize, String)>,
+        clipboard: Option<(u8, Vec<u8>)>,
+        clipboard_request: Option<(u8, String)>,
+        hyperlink: Option<Hyperlink>,
+        cursor_shape: Option<CursorShape>,
+        bells: usize,
+        keyboard_modes: Vec<KeyboardModes>,
+        keyboard_mode_reported: bool,
+        modify_other_keys: ModifyOtherKeys,
+        reported_modes: Vec<(bool, u16)>,
+        sync_update_reports: Vec<bool>,
+        status_settings: Vec<Option<StatusSetting>>,
+        terminfo_capabilities: Vec<String>,
+        version_reported: bool,
+        graphics: Vec<GraphicData>,
+        graphics_attributes: Vec<(u16, u16)>,
+        kitty_commands: Vec<kitty::Command>,
+        prompt_marks: Vec<PromptMark>,
+        working_directories: Vec<(String, PathBuf)>,
+        notifications: Vec<Notification>,
+        window_operations: Vec<WindowOperation>,
+        status_line_type: StatusLineType,
+        status_line_active: bool,
+        line_attrs: Vec<LineAttr>,
     }
 
     impl Handler for MockHandler {
@@ -1326,6 +2396,120 @@ mod tests {
         fn reset_color(&mut self, index: usize) {
             self.reset_colors.push(index)
         }
+
+        fn set_title(&mut self, title: Option<String>) {
+            self.title = title;
+        }
+
+        fn dynamic_color_sequence(&mut self, prefix: String, index: usize, terminator: &str) {
+            self.color_requests.push((prefix, index, terminator.to_owned()));
+        }
+
+        fn clipboard_store(&mut self, clipboard: u8, base64: &[u8]) {
+            self.clipboard = Some((clipboard, base64.to_vec()));
+        }
+
+        fn clipboard_load(&mut self, clipboard: u8, terminator: &str) {
+            self.clipboard_request = Some((clipboard, terminator.to_owned()));
+        }
+
+        fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
+            self.hyperlink = hyperlink;
+        }
+
+        fn set_cursor_shape(&mut self, shape: CursorShape) {
+            self.cursor_shape = Some(shape);
+        }
+
+        fn bell(&mut self) {
+            self.bells += 1;
+        }
+
+        fn push_keyboard_mode(&mut self, mode: KeyboardModes) {
+            self.keyboard_modes.push(mode);
+        }
+
+        fn pop_keyboard_modes(&mut self, to_pop: u16) {
+            let new_len = self.keyboard_modes.len().saturating_sub(to_pop as usize);
+            self.keyboard_modes.truncate(new_len);
+        }
+
+        fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior) {
+            let active_mode = self.keyboard_modes.pop().unwrap_or(KeyboardModes::NO_MODE);
+            self.keyboard_modes.push(match behavior {
+                KeyboardModesApplyBehavior::Replace => mode,
+                KeyboardModesApplyBehavior::Union => active_mode | mode,
+                KeyboardModesApplyBehavior::Difference => active_mode - mode,
+            });
+        }
+
+        fn report_keyboard_mode(&mut self) {
+            self.keyboard_mode_reported = true;
+        }
+
+        fn set_modify_other_keys(&mut self, mode: ModifyOtherKeys) {
+            self.modify_other_keys = mode;
+        }
+
+        fn report_mode(&mut self, private: bool, mode: u16) {
+            self.reported_modes.push((private, mode));
+        }
+
+        fn report_sync_update(&mut self, active: bool) {
+            self.sync_update_reports.push(active);
+        }
+
+        fn report_status_setting(&mut self, setting: Option<StatusSetting>) {
+            self.status_settings.push(setting);
+        }
+
+        fn report_terminfo_capability(&mut self, name: &str) {
+            self.terminfo_capabilities.push(name.into());
+        }
+
+        fn report_version(&mut self) {
+            self.version_reported = true;
+        }
+
+        fn insert_graphic(&mut self, graphic: GraphicData) {
+            self.graphics.push(graphic);
+        }
+
+        fn graphics_attribute(&mut self, item: u16, action: u16) {
+            self.graphics_attributes.push((item, action));
+        }
+
+        fn kitty_graphics_command(&mut self, command: kitty::Command) {
+            self.kitty_commands.push(command);
+        }
+
+        fn prompt_mark(&mut self, mark: PromptMark) {
+            self.prompt_marks.push(mark);
+        }
+
+        fn set_working_directory(&mut self, hostname: &str, path: PathBuf) {
+            self.working_directories.push((hostname.into(), path));
+        }
+
+        fn desktop_notification(&mut self, notification: Notification) {
+            self.notifications.push(notification);
+        }
+
+        fn window_operation(&mut self, operation: WindowOperation) {
+            self.window_operations.push(operation);
+        }
+
+        fn set_status_line_type(&mut self, ty: StatusLineType) {
+            self.status_line_type = ty;
+        }
+
+        fn set_status_line_active(&mut self, active: bool) {
+            self.status_line_active = active;
+        }
+
+        fn set_line_attr(&mut self, line_attr: LineAttr) {
+            self.line_attrs.push(line_attr);
+        }
     }
 
     impl Default for MockHandler {
@@ -1337,6 +2521,31 @@ mod tests {
                 identity_reported: false,
                 color: None,
                 reset_colors: Vec::new(),
+                title: None,
+                color_requests: Vec::new(),
+                clipboard: None,
+                clipboard_request: None,
+                hyperlink: None,
+                cursor_shape: None,
+                bells: 0,
+                keyboard_modes: Vec::new(),
+                keyboard_mode_reported: false,
+                modify_other_keys: ModifyOtherKeys::Reset,
+                reported_modes: Vec::new(),
+                sync_update_reports: Vec::new(),
+                status_settings: Vec::new(),
+                terminfo_capabilities: Vec::new(),
+                version_reported: false,
+                graphics: Vec::new(),
+                graphics_attributes: Vec::new(),
+                kitty_commands: Vec::new(),
+                prompt_marks: Vec::new(),
+                working_directories: Vec::new(),
+                notifications: Vec::new(),
+                window_operations: Vec::new(),
+                status_line_type: StatusLineType::None,
+                status_line_active: false,
+                line_attrs: Vec::new(),
             }
         }
     }
@@ -1494,37 +2703,405 @@ mod tests {
     }
 
     #[test]
-    fn parse_valid_rgb_colors() {
-        assert_eq!(xparse_color(b"rgb:f/e/d"), Some(Rgb { r: 0xff, g: 0xee, b: 0xdd }));
-        assert_eq!(xparse_color(b"rgb:11/aa/ff"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
-        assert_eq!(xparse_color(b"rgb:f/ed1/cb23"), Some(Rgb { r: 0xff, g: 0xec, b: 0xca }));
-        assert_eq!(xparse_color(b"rgb:ffff/0/0"), Some(Rgb { r: 0xff, g: 0x0, b: 0x0 }));
-    }
+    fn parse_kitty_keyboard_protocol() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
 
-    #[test]
-    fn parse_valid_legacy_rgb_colors() {
-        assert_eq!(xparse_color(b"#1af"), Some(Rgb { r: 0x10, g: 0xa0, b: 0xf0 }));
-        assert_eq!(xparse_color(b"#11aaff"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
-        assert_eq!(xparse_color(b"#110aa0ff0"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
-        assert_eq!(xparse_color(b"#1100aa00ff00"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
-    }
+        // Push with and without flags.
+        for byte in b"\x1b[>5u\x1b[>u" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(
+            handler.keyboard_modes,
+            vec![
+                KeyboardModes::DISAMBIGUATE_ESC_CODES | KeyboardModes::REPORT_ALTERNATE_KEYS,
+                KeyboardModes::NO_MODE,
+            ]
+        );
+
+        // Modify the active flags.
+        for byte in b"\x1b[=3;2u\x1b[=1;3u" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.keyboard_modes.last(), Some(&KeyboardModes::REPORT_EVENT_TYPES));
 
-    #[test]
-    fn parse_invalid_rgb_colors() {
-        assert_eq!(xparse_color(b"rgb:0//"), None);
-        assert_eq!(xparse_color(b"rgb://///"), None);
-    }
+        // Pop defaults to a single entry.
+        for byte in b"\x1b[<u" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.keyboard_modes.len(), 1);
 
-    #[test]
-    fn parse_invalid_legacy_rgb_colors() {
-        assert_eq!(xparse_color(b"#"), None);
-        assert_eq!(xparse_color(b"#f"), None);
+        // Query the active flags.
+        for byte in b"\x1b[?u" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert!(handler.keyboard_mode_reported);
     }
 
     #[test]
-    fn parse_invalid_number() {
-        assert_eq!(parse_number(b"1abc"), None);
-    }
+    fn parse_modify_other_keys() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b[>4;2m" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::EnableAll);
+
+        // Other resources are ignored.
+        for byte in b"\x1b[>1;1m" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::EnableAll);
+
+        for byte in b"\x1b[>4;1m" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::EnableExceptWellDefined);
+
+        // Omitting the value resets the resource.
+        for byte in b"\x1b[>4m" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::Reset);
+
+        for byte in b"\x1b[>4;2m\x1b[>4n" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::Reset);
+
+        // Omitting all parameters resets every resource.
+        for byte in b"\x1b[>4;2m\x1b[>m" {
+            parser.advance(&mut handler, *byte);
+        }
+        assert_eq!(handler.modify_other_keys, ModifyOtherKeys::Reset);
+    }
+
+    #[test]
+    fn parse_mode_report_requests() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b[?2004$p\x1b[4$p\x1b[?9999$p\x1b[$p" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(
+            handler.reported_modes,
+            vec![(true, 2004), (false, 4), (true, 9999), (false, 0)]
+        );
+    }
+
+    #[test]
+    fn parse_sync_update_end() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        // Combined mode parameters terminate the synchronized update.
+        for byte in b"\x1b[?2026h\x07\x1b[?25;2026l" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.bells, 1);
+        assert!(parser.sync_timeout().is_none());
+
+        // Bytes following the termination are processed immediately.
+        for byte in b"\x1b[?2026h\x07\x1b[?2026l\x07" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.bells, 3);
+        assert_eq!(parser.sync_bytes_count(), 0);
+    }
+
+    #[test]
+    fn parse_sync_update_report() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b[?2026$p\x1b[?2026h\x1b[?2026$p\x1b[?2026l\x1b[?2026$p" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.sync_update_reports, vec![false, true, false]);
+        assert!(handler.reported_modes.is_empty());
+    }
+
+    #[test]
+    fn parse_unsupported_multiple_intermediates() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        // Only DECRQM accepts multiple intermediates.
+        for byte in b"\x1b[?1$h\x1b[?$c" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert!(!handler.identity_reported);
+    }
+
+    #[test]
+    fn parse_sixel_graphics() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1bP0;1q\"1;1;2;6#1!2~\x1b\\\x1bPq$-\x1b\\" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        // Empty images are dropped.
+        assert_eq!(handler.graphics.len(), 1);
+        assert_eq!((handler.graphics[0].width, handler.graphics[0].height), (2, 6));
+    }
+
+    #[test]
+    fn parse_kitty_graphics() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        let bytes: &[u8] =
+            b"\x1b_Ga=T,i=3,m=1;AAAA\x1b\\\x1b_Gm=0;BBBB\x1b\\x\x1b_Ga=d,d=A\x1b\\\x1b_Xa\x1b\\";
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        // Chunks are combined into a single command.
+        assert_eq!(handler.kitty_commands.len(), 2);
+        assert_eq!(handler.kitty_commands[0].image_id, 3);
+        assert_eq!(handler.kitty_commands[0].payload, b"AAAABBBB");
+        assert_eq!(handler.kitty_commands[1].action, kitty::Action::Delete);
+    }
+
+    #[test]
+    fn defer_kitty_graphics() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        let mut deferred = Vec::new();
+        for byte in b"\x1b_Ga=T,i=3;AAAA\x1b\\\x1b_Ga=d,d=A\x1b\\" {
+            deferred.extend(parser.advance_deferred(&mut handler, *byte));
+        }
+
+        // Only commands which load an image are deferred.
+        assert_eq!(deferred.len(), 1);
+        assert_eq!(deferred[0].image_id, 3);
+        assert_eq!(handler.kitty_commands.len(), 1);
+        assert_eq!(handler.kitty_commands[0].action, kitty::Action::Delete);
+    }
+
+    #[test]
+    fn parse_prompt_marks() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b]133;A\x07\x1b]133;B\x07\x1b]133;C\x07\x1b]133;D;2\x07\x1b]133;D\x07" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.prompt_marks, vec![
+            PromptMark::PromptStart,
+            PromptMark::CommandStart,
+            PromptMark::OutputStart,
+            PromptMark::CommandEnd(Some(2)),
+            PromptMark::CommandEnd(None),
+        ]);
+    }
+
+    #[test]
+    fn parse_working_directory() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b]7;file://host/tmp/a%20b;c\x07\x1b]7;file:///home\x07\x1b]7;/tmp\x07" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.working_directories, vec![
+            (String::from("host"), PathBuf::from("/tmp/a b;c")),
+            (String::new(), PathBuf::from("/home")),
+        ]);
+    }
+
+    #[test]
+    fn parse_notifications() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        let bytes: &[u8] = b"\x1b]9;Build done; 0 errors\x07\x1b]9;4;1;50\x07\
+                             \x1b]777;notify;Tests;All passed\x07\
+                             \x1b]99;i=1:d=0;Hello\x1b\\\x1b]99;i=1:d=0:p=body;Cop\x1b\\\
+                             \x1b]99;i=1:p=body:e=1;eQ==\x1b\\";
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        let notification =
+            |title: &str, body: &str| Notification { title: title.into(), body: body.into() };
+        assert_eq!(handler.notifications, vec![
+            notification("", "Build done; 0 errors"),
+            notification("Tests", "All passed"),
+            notification("Hello", "Copy"),
+        ]);
+    }
+
+    #[test]
+    fn parse_window_operations() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        let bytes: &[u8] = b"\x1b[2t\x1b[1t\x1b[t\x1b[5t\x1b[4;480;640t\x1b[8;;100t\
+                             \x1b[9;1t\x1b[10;2t\x1b[9;3t";
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.window_operations, vec![
+            WindowOperation::Iconify,
+            WindowOperation::Deiconify,
+            WindowOperation::Raise,
+            WindowOperation::ResizePixels { width: 640, height: 480 },
+            WindowOperation::ResizeChars { columns: 100, lines: 0 },
+            WindowOperation::Maximize(true),
+            WindowOperation::ToggleFullscreen,
+        ]);
+    }
+
+    #[test]
+    fn parse_status_line() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b[2$~\x1b[1$}" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.status_line_type, StatusLineType::HostWritable);
+        assert!(handler.status_line_active);
+
+        for byte in b"\x1b[$}\x1b[1$~" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.status_line_type, StatusLineType::Indicator);
+        assert!(!handler.status_line_active);
+    }
+
+    #[test]
+    fn parse_line_attributes() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b#3\x1b#4\x1b#6\x1b#5" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.line_attrs, vec![
+            LineAttr::DoubleTop,
+            LineAttr::DoubleBottom,
+            LineAttr::DoubleWidth,
+            LineAttr::Single,
+        ]);
+    }
+
+    #[test]
+    fn parse_status_requests() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1bP$qm\x1b\\\x1bP$q q\x1b\\\x1bP$q\"q\x1b\\\x1bP$qx\x1b\\" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.status_settings, vec![
+            Some(StatusSetting::Sgr),
+            Some(StatusSetting::CursorStyle),
+            Some(StatusSetting::ProtectedAttribute),
+            None,
+        ]);
+    }
+
+    #[test]
+    fn parse_terminfo_requests() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1bP+q544E;436f;524742;XY;5\x1b\\\x1b[>q" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.terminfo_capabilities, vec!["TN", "Co", "RGB"]);
+        assert!(handler.version_reported);
+    }
+
+    #[test]
+    fn parse_oversized_requests() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        // Oversized DECRQSS requests are answered as invalid, instead of as a truncated request.
+        let mut request = b"\x1bP$q".to_vec();
+        request.extend_from_slice(&[b' '; MAX_DCS_REQUEST_SIZE]);
+        request.extend_from_slice(b"q\x1b\\");
+
+        // Oversized XTGETTCAP requests are ignored.
+        request.extend_from_slice(b"\x1bP+q");
+        for _ in 0..=MAX_DCS_REQUEST_SIZE / 5 {
+            request.extend_from_slice(b"544E;");
+        }
+        request.extend_from_slice(b"\x1b\\");
+
+        for byte in request {
+            parser.advance(&mut handler, byte);
+        }
+
+        assert_eq!(handler.status_settings, vec![None]);
+        assert!(handler.terminfo_capabilities.is_empty());
+    }
+
+    #[test]
+    fn parse_graphics_attributes() {
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in b"\x1b[?1;1;0S\x1b[?2;4S" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.graphics_attributes, vec![(1, 1), (2, 4)]);
+    }
+
+    #[test]
+    fn parse_valid_rgb_colors() {
+        assert_eq!(xparse_color(b"rgb:f/e/d"), Some(Rgb { r: 0xff, g: 0xee, b: 0xdd }));
+        assert_eq!(xparse_color(b"rgb:11/aa/ff"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
+        assert_eq!(xparse_color(b"rgb:f/ed1/cb23"), Some(Rgb { r: 0xff, g: 0xec, b: 0xca }));
+        assert_eq!(xparse_color(b"rgb:ffff/0/0"), Some(Rgb { r: 0xff, g: 0x0, b: 0x0 }));
+    }
+
+    #[test]
+    fn parse_valid_legacy_rgb_colors() {
+        assert_eq!(xparse_color(b"#1af"), Some(Rgb { r: 0x10, g: 0xa0, b: 0xf0 }));
+        assert_eq!(xparse_color(b"#11aaff"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
+        assert_eq!(xparse_color(b"#110aa0ff0"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
+        assert_eq!(xparse_color(b"#1100aa00ff00"), Some(Rgb { r: 0x11, g: 0xaa, b: 0xff }));
+    }
+
+    #[test]
+    fn parse_invalid_rgb_colors() {
+        assert_eq!(xparse_color(b"rgb:0//"), None);
+        assert_eq!(xparse_color(b"rgb://///"), None);
+    }
+
+    #[test]
+    fn parse_invalid_legacy_rgb_colors() {
+        assert_eq!(xparse_color(b"#"), None);
+        assert_eq!(xparse_color(b"#f"), None);
+    }
+
+    #[test]
+    fn parse_invalid_number() {
+        assert_eq!(parse_number(b"1abc"), None);
+    }
 
     #[test]
     fn parse_valid_number() {
@@ -1593,4 +3170,208 @@ mod tests {
         let expected: Vec<usize> = (0..256).collect();
         assert_eq!(handler.reset_colors, expected);
     }
+
+    #[test]
+    fn parse_bell() {
+        let bytes: &[u8] = b"a\x07b\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.bells, 2);
+    }
+
+    #[test]
+    fn parse_bell_terminated_osc_without_bell() {
+        let bytes: &[u8] = b"\x1b]2;title\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.bells, 0);
+        assert_eq!(handler.title, Some(String::from("title")));
+    }
+
+    #[test]
+    fn parse_osc0_set_title() {
+        let bytes: &[u8] = b"\x1b]0;alacritty; title \x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.title, Some(String::from("alacritty; title")));
+    }
+
+    #[test]
+    fn parse_osc2_set_title() {
+        let bytes: &[u8] = b"\x1b]2;vim\x1b\\";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.title, Some(String::from("vim")));
+    }
+
+    #[test]
+    fn parse_osc4_query_color() {
+        let bytes: &[u8] = b"\x1b]4;1;?\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.color_requests, vec![(String::from("4;1"), 1, String::from("\x07"))]);
+    }
+
+    #[test]
+    fn parse_osc8_hyperlink() {
+        let bytes: &[u8] = b"\x1b]8;id=link:foo=bar;https://example.org/a;b\x1b\\";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        let hyperlink = handler.hyperlink.take().unwrap();
+        assert_eq!(hyperlink.id(), "link");
+        assert_eq!(hyperlink.uri(), "https://example.org/a;b");
+
+        // Empty URI terminates the hyperlink.
+        handler.hyperlink = Some(hyperlink);
+        for byte in b"\x1b]8;;\x1b\\" {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.hyperlink, None);
+    }
+
+    #[test]
+    fn parse_osc8_hyperlink_without_id() {
+        let bytes: &[u8] = b"\x1b]8;;https://example.org\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        let hyperlink = handler.hyperlink.unwrap();
+        assert!(hyperlink.id().ends_with("_alacritty"));
+        assert_eq!(hyperlink.uri(), "https://example.org");
+    }
+
+    #[test]
+    fn parse_osc10_dynamic_colors() {
+        let bytes: &[u8] = b"\x1b]10;#ff0000;?\x1b\\";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.color, Some(Rgb { r: 0xff, g: 0x00, b: 0x00 }));
+
+        let background = NamedColor::Background as usize;
+        let expected = vec![(String::from("11"), background, String::from("\x1b\\"))];
+        assert_eq!(handler.color_requests, expected);
+    }
+
+    #[test]
+    fn parse_osc12_set_cursor_color() {
+        let bytes: &[u8] = b"\x1b]12;rgb:00/ff/00\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.color, Some(Rgb { r: 0x00, g: 0xff, b: 0x00 }));
+    }
+
+    #[test]
+    fn parse_osc11x_reset_dynamic_colors() {
+        let bytes: &[u8] = b"\x1b]110\x07\x1b]111\x07\x1b]112\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        let expected = vec![
+            NamedColor::Foreground as usize,
+            NamedColor::Background as usize,
+            NamedColor::Cursor as usize,
+        ];
+        assert_eq!(handler.reset_colors, expected);
+    }
+
+    #[test]
+    fn parse_osc50_cursor_shape() {
+        let bytes: &[u8] = b"\x1b]50;CursorShape=1\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.cursor_shape, Some(CursorShape::Beam));
+    }
+
+    #[test]
+    fn parse_osc52_clipboard_store() {
+        let bytes: &[u8] = b"\x1b]52;c;YWxhY3JpdHR5\x07";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.clipboard, Some((b'c', b"YWxhY3JpdHR5".to_vec())));
+    }
+
+    #[test]
+    fn parse_osc52_clipboard_load() {
+        let bytes: &[u8] = b"\x1b]52;;?\x1b\\";
+
+        let mut parser = Processor::new();
+        let mut handler = MockHandler::default();
+
+        for byte in bytes {
+            parser.advance(&mut handler, *byte);
+        }
+
+        assert_eq!(handler.clipboard_request, Some((b'c', String::from("\x1b\\"))));
+    }
 }
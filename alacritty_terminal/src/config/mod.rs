@@ -1,6 +1,7 @@
 use std::cmp;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::process::ExitStatus;
 
 use serde::Deserialize;
 
@@ -44,8 +45,7 @@ pub struct PtyConfig {
     pub working_directory: Option<PathBuf>,
 
     /// Remain open after child process exits.
-    #[config(skip)]
-    pub hold: bool,
+    pub hold: Hold,
 }
 
 impl PtyConfig {
@@ -54,6 +54,38 @@ impl PtyConfig {
     }
 }
 
+/// Policy for keeping the terminal open after the child process exited.
+#[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hold {
+    /// Close the terminal once the child exits.
+    Never,
+
+    /// Always keep the terminal open.
+    Always,
+
+    /// Keep the terminal open if the child did not exit successfully.
+    OnFailure,
+}
+
+impl Default for Hold {
+    fn default() -> Self {
+        Self::Never
+    }
+}
+
+impl Hold {
+    /// Check if the terminal should remain open after the child exited.
+    ///
+    /// An unknown exit status is treated as failure.
+    pub fn should_hold(self, status: Option<ExitStatus>) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Always => true,
+            Self::OnFailure => !status.map_or(false, |status| status.success()),
+        }
+    }
+}
+
 #[derive(ConfigDeserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Selection {
     pub semantic_escape_chars: String,
@@ -72,6 +104,7 @@ impl Default for Selection {
 #[derive(ConfigDeserialize, Copy, Clone, Debug, PartialEq)]
 pub struct Cursor {
     pub style: ConfigCursorStyle,
+    pub vi_mode_style: Option<ConfigCursorStyle>,
     pub unfocused_hollow: bool,
 
     thickness: Percentage,
@@ -87,6 +120,7 @@ impl Default for Cursor {
             blink_interval: 750,
             blink_timeout: 5,
             style: Default::default(),
+            vi_mode_style: Default::default(),
         }
     }
 }
@@ -102,6 +136,11 @@ impl Cursor {
         self.style.into()
     }
 
+    #[inline]
+    pub fn vi_mode_style(self) -> Option<CursorStyle> {
+        self.vi_mode_style.map(From::from)
+    }
+
     #[inline]
     pub fn blink_interval(self) -> u64 {
         cmp::max(self.blink_interval, MIN_BLINK_INTERVAL)
@@ -234,3 +273,22 @@ impl Percentage {
         self.0
     }
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn hold_policies() {
+        let success = Some(ExitStatus::from_raw(0));
+        let failure = Some(ExitStatus::from_raw(1 << 8));
+
+        assert!(!Hold::Never.should_hold(failure));
+        assert!(Hold::Always.should_hold(success));
+        assert!(!Hold::OnFailure.should_hold(success));
+        assert!(Hold::OnFailure.should_hold(failure));
+        assert!(Hold::OnFailure.should_hold(None));
+    }
+}
@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::fmt::{self, Debug, Formatter};
+use std::process::ExitStatus;
 use std::sync::Arc;
 
+use crate::ansi::{Notification, WindowOperation};
 use crate::term::color::Rgb;
 use crate::term::ClipboardType;
 
@@ -41,12 +43,45 @@ pub enum Event {
     /// Request to write the text area size.
     TextAreaSizeRequest(Arc<dyn Fn(WindowSize) -> String + Sync + Send + 'static>),
 
+    /// Request to write the screen size.
+    ///
+    /// The attached function is a formatter which will transform the width and height of the
+    /// window's screen in pixels into the expected escape sequence format.
+    ScreenSizeRequest(Arc<dyn Fn(u32, u32) -> String + Sync + Send + 'static>),
+
+    /// Request to write the window position.
+    ///
+    /// The attached function is a formatter which will transform the window's position in pixels
+    /// into the expected escape sequence format.
+    WindowPositionRequest(Arc<dyn Fn(i32, i32) -> String + Sync + Send + 'static>),
+
+    /// Request to write the window title.
+    ///
+    /// The attached function is a formatter which will transform the title into the expected
+    /// escape sequence format.
+    TitleRequest(Arc<dyn Fn(&str) -> String + Sync + Send + 'static>),
+
+    /// Request to change the state of the window.
+    WindowOperation(WindowOperation),
+
     /// Cursor blinking state has changed.
     CursorBlinkingChange,
 
+    /// Status line type has changed.
+    StatusLineChange,
+
+    /// Terminal bell ring.
+    Bell,
+
+    /// Desktop notification request.
+    Notification(Notification),
+
     /// New terminal content available.
     Wakeup,
 
+    /// Child process exited, with its exit status if it could be retrieved.
+    ChildExit(Option<ExitStatus>),
+
     /// Shutdown request.
     Exit,
 }
@@ -57,13 +92,21 @@ impl Debug for Event {
             Event::ClipboardStore(ty, text) => write!(f, "ClipboardStore({ty:?}, {text})"),
             Event::ClipboardLoad(ty, _) => write!(f, "ClipboardLoad({ty:?})"),
             Event::TextAreaSizeRequest(_) => write!(f, "TextAreaSizeRequest"),
+            Event::ScreenSizeRequest(_) => write!(f, "ScreenSizeRequest"),
+            Event::WindowPositionRequest(_) => write!(f, "WindowPositionRequest"),
+            Event::TitleRequest(_) => write!(f, "TitleRequest"),
+            Event::WindowOperation(operation) => write!(f, "WindowOperation({operation:?})"),
             Event::ColorRequest(index, _) => write!(f, "ColorRequest({index})"),
             Event::PtyWrite(text) => write!(f, "PtyWrite({text})"),
             Event::Title(title) => write!(f, "Title({title})"),
             Event::CursorBlinkingChange => write!(f, "CursorBlinkingChange"),
+            Event::StatusLineChange => write!(f, "StatusLineChange"),
             Event::MouseCursorDirty => write!(f, "MouseCursorDirty"),
             Event::ResetTitle => write!(f, "ResetTitle"),
+            Event::Bell => write!(f, "Bell"),
+            Event::Notification(notification) => write!(f, "Notification({notification:?})"),
             Event::Wakeup => write!(f, "Wakeup"),
+            Event::ChildExit(status) => write!(f, "ChildExit({status:?})"),
             Event::Exit => write!(f, "Exit"),
         }
     }
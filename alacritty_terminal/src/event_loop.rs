@@ -14,7 +14,10 @@ use log::error;
 use mio::unix::UnixReady;
 use mio::{self, Events, PollOpt, Ready};
 use mio_extras::channel::{self, Receiver, Sender};
+use parking_lot::MutexGuard;
 
+use crate::ansi::Handler;
+use crate::config::Hold;
 use crate::event::{self, Event, EventListener, WindowSize};
 use crate::sync::FairMutex;
 use crate::term::Term;
@@ -50,7 +53,7 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     tx: Sender<Msg>,
     terminal: Arc<FairMutex<Term<U>>>,
     event_proxy: U,
-    hold: bool,
+    hold: Hold,
     ref_test: bool,
 }
 
@@ -155,7 +158,7 @@ where
         terminal: Arc<FairMutex<Term<U>>>,
         event_proxy: U,
         pty: T,
-        hold: bool,
+        hold: Hold,
         ref_test: bool,
     ) -> EventLoop<T, U> {
         let (tx, rx) = channel::channel();
@@ -218,35 +221,39 @@ where
         let mut processed = 0;
 
         // Reserve the next terminal lock for PTY reading.
-        let _terminal_lease = Some(self.terminal.lease());
+        let mut _terminal_lease = Some(self.terminal.lease());
         let mut terminal = None;
 
-        loop {
+        let result = loop {
             // Read from the PTY.
             match self.pty.reader().read(&mut buf[unprocessed..]) {
                 // This is received on Windows/macOS when no more data is readable from the PTY.
-                Ok(0) if unprocessed == 0 => break,
+                Ok(0) if unprocessed == 0 => break Ok(()),
                 Ok(got) => unprocessed += got,
                 Err(err) => match err.kind() {
                     ErrorKind::Interrupted | ErrorKind::WouldBlock => {
                         // Go back to mio if we're caught up on parsing and the PTY would block.
                         if unprocessed == 0 {
-                            break;
+                            break Ok(());
                         }
                     },
-                    _ => return Err(err),
+                    _ => break Err(err),
                 },
             }
 
             // Attempt to lock the terminal.
             let terminal = match &mut terminal {
                 Some(terminal) => terminal,
-                None => terminal.insert(match self.terminal.try_lock_unfair() {
-                    // Force block if we are at the buffer size limit.
-                    None if unprocessed >= READ_BUFFER_SIZE => self.terminal.lock_unfair(),
-                    None => continue,
-                    Some(terminal) => terminal,
-                }),
+                None => {
+                    let terminal = terminal.insert(match self.terminal.try_lock_unfair() {
+                        // Force block if we are at the buffer size limit.
+                        None if unprocessed >= READ_BUFFER_SIZE => self.terminal.lock_unfair(),
+                        None => continue,
+                        Some(terminal) => terminal,
+                    });
+                    terminal.resume_status_line();
+                    terminal
+                },
             };
 
             // Write a copy of the bytes to the ref test file.
@@ -256,7 +263,20 @@ where
 
             // Parse the incoming bytes.
             for byte in &buf[..unprocessed] {
-                state.parser.advance(&mut **terminal, *byte);
+                let mut command = match state.parser.advance_deferred(&mut **terminal, *byte) {
+                    Some(command) => command,
+                    None => continue,
+                };
+
+                // Load kitty graphics images without blocking the terminal.
+                _terminal_lease = None;
+                terminal.suspend_status_line();
+                MutexGuard::unlocked(terminal, || {
+                    command.preload();
+                    _terminal_lease = Some(self.terminal.lease());
+                });
+                terminal.resume_status_line();
+                terminal.kitty_graphics_command(command);
             }
 
             processed += unprocessed;
@@ -264,9 +284,15 @@ where
 
             // Assure we're not blocking the terminal too long unnecessarily.
             if processed >= MAX_LOCKED_READ {
-                break;
+                break Ok(());
             }
+        };
+
+        // Put the main display back in place before releasing the terminal.
+        if let Some(terminal) = &mut terminal {
+            terminal.suspend_status_line();
         }
+        result?;
 
         // Queue terminal redraw unless all processed bytes were synchronized.
         if state.parser.sync_bytes_count() < processed && processed > 0 {
@@ -345,7 +371,10 @@ where
 
                 // Handle synchronized update timeout.
                 if events.is_empty() {
-                    state.parser.stop_sync(&mut *self.terminal.lock());
+                    let mut terminal = self.terminal.lock();
+                    terminal.resume_status_line();
+                    state.parser.stop_sync(&mut *terminal);
+                    terminal.suspend_status_line();
                     self.event_proxy.send_event(Event::Wakeup);
                     continue;
                 }
@@ -359,8 +388,12 @@ where
                         },
 
                         token if token == self.pty.child_event_token() => {
-                            if let Some(tty::ChildEvent::Exited) = self.pty.next_child_event() {
-                                if self.hold {
+                            if let Some(tty::ChildEvent::Exited(status)) =
+                                self.pty.next_child_event()
+                            {
+                                self.event_proxy.send_event(Event::ChildExit(status));
+
+                                if self.hold.should_hold(status) {
                                     // With hold enabled, make sure the PTY is drained.
                                     let _ = self.pty_read(&mut state, &mut buf, pipe.as_mut());
                                 } else {
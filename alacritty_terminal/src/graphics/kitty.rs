@@ -0,0 +1,859 @@
+//! Kitty graphics protocol.
+//!
+//! Commands are transmitted as APC sequences (`APC G <control data> ; <payload> ST`), where the
+//! control data is a comma-separated list of `key=value` pairs and the payload is base64 encoded.
+//! See <https://sw.kovidgoyal.net/kitty/graphics-protocol/> for the full description.
+
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::{env, str};
+
+use flate2::read::ZlibDecoder;
+use log::debug;
+
+use crate::graphics::{GraphicData, TextureRef, MAX_GRAPHIC_HEIGHT, MAX_GRAPHIC_WIDTH};
+
+/// Maximum size of the decoded pixels of a single image.
+const MAX_PIXELS_SIZE: usize = MAX_GRAPHIC_WIDTH * MAX_GRAPHIC_HEIGHT * 4;
+
+/// Maximum size of the base64 encoded payload of a single command.
+pub const MAX_PAYLOAD_SIZE: usize = (MAX_PIXELS_SIZE + 2) / 3 * 4;
+
+/// Maximum size of all stored images, in bytes of decoded pixels (320MiB).
+const STORAGE_LIMIT: usize = 0x1400_0000;
+
+/// Temporary files are only deleted if their name contains this string.
+const TEMP_FILE_MARKER: &str = "tty-graphics-protocol";
+
+/// Action requested by a command (`a`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// Store image data (`t`).
+    Transmit,
+
+    /// Store image data and display it at the cursor position (`T`).
+    TransmitAndDisplay,
+
+    /// Display previously transmitted image data (`p`).
+    Display,
+
+    /// Delete placements or images (`d`).
+    Delete,
+
+    /// Check whether the image data could be loaded, without storing it (`q`).
+    Query,
+
+    /// Actions which are not supported, like animations.
+    Unsupported(u8),
+}
+
+/// Format of the transmitted pixels (`f`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// 24-bit RGB pixels.
+    Rgb,
+
+    /// 32-bit RGBA pixels.
+    Rgba,
+
+    /// PNG image.
+    Png,
+
+    /// Formats which are not part of the protocol.
+    Unsupported(u32),
+}
+
+/// Transmission medium of the image data (`t`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Medium {
+    /// Data is part of the payload.
+    Direct,
+
+    /// Payload is the path of a file containing the data.
+    File,
+
+    /// Payload is the path of a temporary file, which is deleted after reading it.
+    TempFile,
+
+    /// Data is stored in a shared memory object, which isn't supported.
+    SharedMemory,
+
+    /// Mediums which are not part of the protocol.
+    Unsupported(u8),
+}
+
+/// Placements affected by a delete command (`d`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DeleteTarget {
+    /// All visible placements.
+    All,
+
+    /// Placements of the image with the specified ID.
+    ImageId,
+
+    /// Placements of the newest image with the specified number.
+    ImageNumber,
+
+    /// Placements intersecting the cursor.
+    Cursor,
+
+    /// Placements intersecting the cell at `x` and `y`.
+    Cell,
+
+    /// Placements intersecting the cell at `x` and `y` with a matching z-index.
+    CellZIndex,
+
+    /// Placements intersecting the column `x`.
+    Column,
+
+    /// Placements intersecting the row `y`.
+    Row,
+
+    /// Placements with a matching z-index.
+    ZIndex,
+
+    /// Placements of images with an ID between `x` and `y`.
+    IdRange,
+
+    /// Targets which are not supported, like animation frames.
+    Unsupported(u8),
+}
+
+/// Parameters of a delete command.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Delete {
+    /// Placements which should be deleted.
+    pub target: DeleteTarget,
+
+    /// Free the data of the deleted images, requested using uppercase targets.
+    pub free: bool,
+}
+
+impl Delete {
+    fn from_byte(byte: u8) -> Self {
+        let target = match byte.to_ascii_lowercase() {
+            b'a' => DeleteTarget::All,
+            b'i' => DeleteTarget::ImageId,
+            b'n' => DeleteTarget::ImageNumber,
+            b'c' => DeleteTarget::Cursor,
+            b'p' => DeleteTarget::Cell,
+            b'q' => DeleteTarget::CellZIndex,
+            b'x' => DeleteTarget::Column,
+            b'y' => DeleteTarget::Row,
+            b'z' => DeleteTarget::ZIndex,
+            b'r' => DeleteTarget::IdRange,
+            _ => DeleteTarget::Unsupported(byte),
+        };
+
+        Self { target, free: byte.is_ascii_uppercase() }
+    }
+}
+
+/// Reasons for rejecting a command, reported back to the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Invalid or unsupported parameters.
+    Invalid(&'static str),
+
+    /// Image data could not be read.
+    BadFile(String),
+
+    /// Image data is smaller than its dimensions.
+    NoData,
+
+    /// Image exceeds the size limits.
+    TooLarge,
+
+    /// Referenced image does not exist.
+    NotFound,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(message) => write!(f, "EINVAL:{}", message),
+            Self::BadFile(message) => write!(f, "EBADF:{}", message),
+            Self::NoData => f.write_str("ENODATA:Insufficient image data"),
+            Self::TooLarge => f.write_str("EFBIG:Image is too large"),
+            Self::NotFound => f.write_str("ENOENT:No such image"),
+        }
+    }
+}
+
+/// Graphics command received from the application.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Command {
+    /// Requested action (`a`).
+    pub action: Action,
+
+    /// Suppress responses, 1 for successful commands and 2 for all commands (`q`).
+    pub quiet: u8,
+
+    /// Format of the pixels (`f`).
+    pub format: Format,
+
+    /// Transmission medium (`t`).
+    pub medium: Medium,
+
+    /// Data is compressed using zlib (`o=z`).
+    pub compressed: bool,
+
+    /// More chunks of the payload follow (`m`).
+    pub more: bool,
+
+    /// Image identifier (`i`).
+    pub image_id: u32,
+
+    /// Image number, used to let the terminal pick the image identifier (`I`).
+    pub image_number: u32,
+
+    /// Placement identifier (`p`).
+    pub placement_id: u32,
+
+    /// Width of the transmitted pixels (`s`).
+    pub data_width: u32,
+
+    /// Height of the transmitted pixels (`v`).
+    pub data_height: u32,
+
+    /// Number of bytes read from a file (`S`).
+    pub data_size: u32,
+
+    /// Offset of the data in a file (`O`).
+    pub data_offset: u32,
+
+    /// Left edge of the displayed image area, or the column of delete commands (`x`).
+    pub x: u32,
+
+    /// Top edge of the displayed image area, or the row of delete commands (`y`).
+    pub y: u32,
+
+    /// Width of the displayed image area (`w`).
+    pub width: u32,
+
+    /// Height of the displayed image area (`h`).
+    pub height: u32,
+
+    /// Horizontal offset inside the first cell in pixels (`X`).
+    pub cell_x_offset: u32,
+
+    /// Vertical offset inside the first cell in pixels (`Y`).
+    pub cell_y_offset: u32,
+
+    /// Number of columns the image is scaled to (`c`).
+    pub columns: u32,
+
+    /// Number of rows the image is scaled to (`r`).
+    pub rows: u32,
+
+    /// Stacking order relative to text and other images (`z`).
+    pub z_index: i32,
+
+    /// Move the cursor after the placement (`C=0`).
+    pub move_cursor: bool,
+
+    /// Placements affected by delete commands (`d`).
+    pub delete: Delete,
+
+    /// Base64 encoded payload.
+    pub payload: Vec<u8>,
+
+    /// Payload was dropped after exceeding the size limit.
+    truncated: bool,
+
+    /// Image loaded ahead of handling the command.
+    loaded: Option<Result<GraphicData, Error>>,
+}
+
+impl Default for Command {
+    fn default() -> Self {
+        Self {
+            action: Action::Transmit,
+            quiet: 0,
+            format: Format::Rgba,
+            medium: Medium::Direct,
+            compressed: false,
+            more: false,
+            image_id: 0,
+            image_number: 0,
+            placement_id: 0,
+            data_width: 0,
+            data_height: 0,
+            data_size: 0,
+            data_offset: 0,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            cell_x_offset: 0,
+            cell_y_offset: 0,
+            columns: 0,
+            rows: 0,
+            z_index: 0,
+            move_cursor: true,
+            delete: Delete { target: DeleteTarget::All, free: false },
+            payload: Vec::new(),
+            truncated: false,
+            loaded: None,
+        }
+    }
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Command")
+            .field("action", &self.action)
+            .field("image_id", &self.image_id)
+            .field("image_number", &self.image_number)
+            .field("placement_id", &self.placement_id)
+            .field("payload", &self.payload.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Command {
+    /// Parse the command following the `G` of the APC sequence.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let (control, payload) = match data.iter().position(|&byte| byte == b';') {
+            Some(index) => (&data[..index], &data[index + 1..]),
+            None => (data, &[][..]),
+        };
+
+        let mut command = Self { payload: payload.to_vec(), ..Self::default() };
+
+        for pair in control.split(|&byte| byte == b',').filter(|pair| !pair.is_empty()) {
+            let (key, value) = match pair {
+                [key, b'=', value @ ..] if !value.is_empty() => (*key, value),
+                _ => {
+                    debug!("Invalid kitty graphics control data: {:?}", str::from_utf8(pair));
+                    return None;
+                },
+            };
+
+            let number = || str::from_utf8(value).ok()?.parse::<u32>().ok();
+            let number = || {
+                number().or_else(|| {
+                    debug!("Invalid value for kitty graphics key {:?}", key as char);
+                    None
+                })
+            };
+
+            match key {
+                b'a' => {
+                    command.action = match value {
+                        b"t" => Action::Transmit,
+                        b"T" => Action::TransmitAndDisplay,
+                        b"p" => Action::Display,
+                        b"d" => Action::Delete,
+                        b"q" => Action::Query,
+                        _ => Action::Unsupported(value[0]),
+                    }
+                },
+                b't' => {
+                    command.medium = match value {
+                        b"d" => Medium::Direct,
+                        b"f" => Medium::File,
+                        b"t" => Medium::TempFile,
+                        b"s" => Medium::SharedMemory,
+                        _ => Medium::Unsupported(value[0]),
+                    }
+                },
+                b'f' => {
+                    command.format = match number()? {
+                        24 => Format::Rgb,
+                        32 => Format::Rgba,
+                        100 => Format::Png,
+                        format => Format::Unsupported(format),
+                    }
+                },
+                b'o' => command.compressed = value == b"z",
+                b'd' => command.delete = Delete::from_byte(value[0]),
+                b'q' => command.quiet = number()?.min(2) as u8,
+                b'm' => command.more = number()? == 1,
+                b'C' => command.move_cursor = number()? == 0,
+                b'z' => {
+                    command.z_index = match str::from_utf8(value).ok()?.parse() {
+                        Ok(z_index) => z_index,
+                        Err(_) => {
+                            debug!("Invalid value for kitty graphics key 'z'");
+                            return None;
+                        },
+                    }
+                },
+                b'i' => command.image_id = number()?,
+                b'I' => command.image_number = number()?,
+                b'p' => command.placement_id = number()?,
+                b's' => command.data_width = number()?,
+                b'v' => command.data_height = number()?,
+                b'S' => command.data_size = number()?,
+                b'O' => command.data_offset = number()?,
+                b'x' => command.x = number()?,
+                b'y' => command.y = number()?,
+                b'w' => command.width = number()?,
+                b'h' => command.height = number()?,
+                b'X' => command.cell_x_offset = number()?,
+                b'Y' => command.cell_y_offset = number()?,
+                b'c' => command.columns = number()?,
+                b'r' => command.rows = number()?,
+                _ => debug!("Ignoring unknown kitty graphics key {:?}", key as char),
+            }
+        }
+
+        Some(command)
+    }
+
+    /// Append the payload of the next chunk of a chunked transmission.
+    pub fn append_chunk(&mut self, chunk: Command) {
+        self.more = chunk.more;
+
+        if self.truncated || self.payload.len() + chunk.payload.len() > MAX_PAYLOAD_SIZE {
+            self.truncated = true;
+            self.payload = Vec::new();
+        } else {
+            self.payload.extend_from_slice(&chunk.payload);
+        }
+    }
+
+    /// Response to the command, if the client should receive one.
+    pub fn response(&self, image_id: u32, result: &Result<(), Error>) -> Option<String> {
+        // Only commands which reference an image are acknowledged.
+        if image_id == 0 && self.image_number == 0 {
+            return None;
+        }
+
+        let message = match result {
+            Ok(()) if self.quiet == 0 => String::from("OK"),
+            Err(err) if self.quiet < 2 => err.to_string(),
+            _ => return None,
+        };
+
+        let mut response = format!("\x1b_Gi={}", image_id);
+        if self.image_number != 0 {
+            response.push_str(&format!(",I={}", self.image_number));
+        }
+        if self.placement_id != 0 {
+            response.push_str(&format!(",p={}", self.placement_id));
+        }
+        response.push(';');
+        response.push_str(&message);
+        response.push_str("\x1b\\");
+
+        Some(response)
+    }
+
+    /// Whether handling this command requires loading its image.
+    pub fn loads_image(&self) -> bool {
+        matches!(self.action, Action::Transmit | Action::TransmitAndDisplay | Action::Query)
+    }
+
+    /// Load the image ahead of handling the command.
+    ///
+    /// Since this might read files and decode large images, it should be called without holding
+    /// the terminal lock.
+    pub fn preload(&mut self) {
+        self.loaded = Some(self.decode());
+        self.payload = Vec::new();
+    }
+
+    /// Take the image transmitted by this command, decoding it unless it was preloaded.
+    pub fn load(&mut self) -> Result<GraphicData, Error> {
+        match self.loaded.take() {
+            Some(result) => result,
+            None => self.decode(),
+        }
+    }
+
+    /// Decode the image transmitted by this command.
+    fn decode(&self) -> Result<GraphicData, Error> {
+        if self.truncated {
+            return Err(Error::TooLarge);
+        }
+
+        let payload =
+            base64::decode(&self.payload).map_err(|_| Error::Invalid("Invalid base64 payload"))?;
+
+        let data = match self.medium {
+            Medium::Direct => payload,
+            Medium::File | Medium::TempFile => self.read_file(&payload)?,
+            Medium::SharedMemory => return Err(Error::Invalid("Shared memory is not supported")),
+            Medium::Unsupported(_) => return Err(Error::Invalid("Unknown transmission medium")),
+        };
+
+        let data = if self.compressed { inflate(&data)? } else { data };
+
+        match self.format {
+            Format::Rgb => self.decode_raw(&data, 3),
+            Format::Rgba => self.decode_raw(&data, 4),
+            Format::Png => decode_png(&data),
+            Format::Unsupported(_) => Err(Error::Invalid("Unknown image format")),
+        }
+    }
+
+    /// Read image data from the file at `path`.
+    fn read_file(&self, path: &[u8]) -> Result<Vec<u8>, Error> {
+        let path = str::from_utf8(path).map_err(|_| Error::Invalid("Invalid file path"))?;
+        let path = Path::new(path);
+
+        if self.medium != Medium::TempFile {
+            return read_regular_file(path, self.data_offset, self.data_size);
+        }
+
+        // Refuse to delete files which weren't created for the graphics protocol.
+        let path = temp_file_path(path)?;
+        let result = read_regular_file(&path, self.data_offset, self.data_size);
+
+        if let Err(err) = fs::remove_file(&path) {
+            debug!("Unable to remove temporary image file {:?}: {}", path, err);
+        }
+
+        result
+    }
+
+    /// Convert RGB or RGBA pixels with the dimensions specified by the command.
+    fn decode_raw(&self, data: &[u8], bytes_per_pixel: usize) -> Result<GraphicData, Error> {
+        let width = self.data_width as usize;
+        let height = self.data_height as usize;
+        if width == 0 || height == 0 {
+            return Err(Error::Invalid("Missing image dimensions"));
+        } else if width > MAX_GRAPHIC_WIDTH || height > MAX_GRAPHIC_HEIGHT {
+            return Err(Error::TooLarge);
+        }
+
+        let data = data.get(..width * height * bytes_per_pixel).ok_or(Error::NoData)?;
+        let pixels = match bytes_per_pixel {
+            3 => data.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX]).collect(),
+            _ => data.to_vec(),
+        };
+
+        Ok(GraphicData { width, height, pixels })
+    }
+}
+
+/// Read at most `size` bytes from a regular file, starting at `offset`.
+fn read_regular_file(path: &Path, offset: u32, size: u32) -> Result<Vec<u8>, Error> {
+    let bad_file = |err: std::io::Error| Error::BadFile(err.to_string());
+
+    // Avoid blocking the terminal on devices and pipes.
+    let mut file = File::open(path).map_err(bad_file)?;
+    let metadata = file.metadata().map_err(bad_file)?;
+    if !metadata.is_file() {
+        return Err(Error::BadFile(String::from("Not a regular file")));
+    }
+
+    // Reject oversized files before reading any of their data.
+    let available = metadata.len().saturating_sub(u64::from(offset));
+    let limit = if size == 0 { available } else { available.min(u64::from(size)) };
+    if limit > MAX_PIXELS_SIZE as u64 {
+        return Err(Error::TooLarge);
+    }
+
+    file.seek(SeekFrom::Start(u64::from(offset))).map_err(bad_file)?;
+
+    let mut data = Vec::with_capacity(limit as usize);
+    file.take(limit).read_to_end(&mut data).map_err(bad_file)?;
+
+    Ok(data)
+}
+
+/// Resolve the path of a temporary file, making sure it is inside a temporary directory.
+fn temp_file_path(path: &Path) -> Result<PathBuf, Error> {
+    let invalid = || Error::Invalid("Temporary file is not in a temporary directory");
+    let bad_file = |err: std::io::Error| Error::BadFile(err.to_string());
+
+    // Symlinks and parent directories could be used to escape the temporary directory.
+    if path.components().any(|component| component == Component::ParentDir) {
+        return Err(invalid());
+    }
+    if fs::symlink_metadata(path).map_err(bad_file)?.file_type().is_symlink() {
+        return Err(invalid());
+    }
+
+    let path = fs::canonicalize(path).map_err(bad_file)?;
+    let in_temp_dir = [env::temp_dir(), PathBuf::from("/dev/shm")]
+        .iter()
+        .filter_map(|dir| fs::canonicalize(dir).ok())
+        .any(|dir| path.starts_with(dir));
+
+    if !in_temp_dir || !path.to_string_lossy().contains(TEMP_FILE_MARKER) {
+        return Err(invalid());
+    }
+
+    Ok(path)
+}
+
+/// Decompress zlib compressed image data.
+fn inflate(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut inflated = Vec::new();
+    ZlibDecoder::new(data)
+        .take(MAX_PIXELS_SIZE as u64 + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|_| Error::Invalid("Invalid compressed data"))?;
+
+    if inflated.len() > MAX_PIXELS_SIZE {
+        return Err(Error::TooLarge);
+    }
+
+    Ok(inflated)
+}
+
+/// Decode a PNG image to RGBA pixels.
+fn decode_png(data: &[u8]) -> Result<GraphicData, Error> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+    let mut reader = decoder.read_info().map_err(|_| Error::Invalid("Invalid PNG image"))?;
+    let (width, height) = (reader.info().width as usize, reader.info().height as usize);
+    if width > MAX_GRAPHIC_WIDTH || height > MAX_GRAPHIC_HEIGHT {
+        return Err(Error::TooLarge);
+    }
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).map_err(|_| Error::Invalid("Invalid PNG image"))?;
+    let buffer = &buffer[..frame.buffer_size()];
+
+    let pixels = match frame.color_type {
+        png::ColorType::Rgba => buffer.to_vec(),
+        png::ColorType::Rgb => {
+            buffer.chunks_exact(3).flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX]).collect()
+        },
+        png::ColorType::GrayscaleAlpha => {
+            buffer.chunks_exact(2).flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]]).collect()
+        },
+        png::ColorType::Grayscale => buffer.iter().flat_map(|&g| [g, g, g, u8::MAX]).collect(),
+        png::ColorType::Indexed => return Err(Error::Invalid("Unsupported PNG color type")),
+    };
+
+    Ok(GraphicData { width, height, pixels })
+}
+
+/// Image stored by the terminal.
+#[derive(Debug)]
+pub struct Image {
+    /// Image identifier.
+    pub id: u32,
+
+    /// Image number specified by the client, zero if none was used.
+    pub number: u32,
+
+    /// Texture of the image.
+    pub texture: Arc<TextureRef>,
+}
+
+/// Images transmitted by the client, which can be displayed by later commands.
+///
+/// Once the storage limit is exceeded, the oldest images are removed. Their placements remain
+/// visible, since every placement keeps its own reference to the texture.
+#[derive(Debug, Default)]
+pub struct ImageStorage {
+    /// Stored images, ordered from oldest to newest.
+    images: Vec<Image>,
+
+    /// Last identifier picked for images transmitted with only an image number.
+    last_id: u32,
+}
+
+impl ImageStorage {
+    /// Store an image, replacing existing images with the same ID.
+    pub fn insert(&mut self, image: Image) {
+        self.remove(image.id);
+        self.images.push(image);
+
+        let size = |image: &Image| image.texture.width * image.texture.height * 4;
+        let mut total: usize = self.images.iter().map(size).sum();
+        while total > STORAGE_LIMIT && self.images.len() > 1 {
+            total -= size(&self.images.remove(0));
+        }
+    }
+
+    /// Image with the specified ID.
+    pub fn get(&self, id: u32) -> Option<&Image> {
+        self.images.iter().find(|image| image.id == id)
+    }
+
+    /// Newest image with the specified number.
+    pub fn get_by_number(&self, number: u32) -> Option<&Image> {
+        self.images.iter().rev().find(|image| image.number == number)
+    }
+
+    /// Remove the image with the specified ID.
+    pub fn remove(&mut self, id: u32) {
+        self.images.retain(|image| image.id != id);
+    }
+
+    /// Keep only the images matching `predicate`.
+    pub fn retain<F: FnMut(&Image) -> bool>(&mut self, predicate: F) {
+        self.images.retain(predicate);
+    }
+
+    /// Remove all images.
+    pub fn clear(&mut self) {
+        self.images.clear();
+    }
+
+    /// Pick an identifier which isn't used by any stored image.
+    pub fn next_id(&mut self) -> u32 {
+        loop {
+            self.last_id = self.last_id.wrapping_add(1).max(1);
+            if self.get(self.last_id).is_none() {
+                return self.last_id;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    fn command(data: &str) -> Command {
+        Command::parse(data.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn parse_control_data() {
+        let command = command("a=T,f=24,s=2,v=1,i=7,p=3,c=4,r=2,z=-5,C=1,q=1;AAAA");
+        assert_eq!(command.action, Action::TransmitAndDisplay);
+        assert_eq!(command.format, Format::Rgb);
+        assert_eq!((command.data_width, command.data_height), (2, 1));
+        assert_eq!((command.image_id, command.placement_id), (7, 3));
+        assert_eq!((command.columns, command.rows), (4, 2));
+        assert_eq!(command.z_index, -5);
+        assert!(!command.move_cursor);
+        assert_eq!(command.quiet, 1);
+        assert_eq!(command.payload, b"AAAA");
+
+        let command = self::command("a=d,d=I,i=3");
+        assert_eq!(command.delete, Delete { target: DeleteTarget::ImageId, free: true });
+
+        assert!(Command::parse(b"a=t,i=x").is_none());
+        assert!(Command::parse(b"a=t,i").is_none());
+    }
+
+    #[test]
+    fn load_raw_pixels() {
+        let mut rgb = command(&format!("f=24,s=2,v=1;{}", base64::encode([1, 2, 3, 4, 5, 6])));
+        let graphic = rgb.load().unwrap();
+        assert_eq!((graphic.width, graphic.height), (2, 1));
+        assert_eq!(graphic.pixels, vec![1, 2, 3, 255, 4, 5, 6, 255]);
+
+        let mut missing = command(&format!("f=32,s=2,v=1;{}", base64::encode([0; 4])));
+        assert_eq!(missing.load(), Err(Error::NoData));
+
+        let mut too_large = command(&format!("f=32,s=5000,v=1;{}", base64::encode([0; 4])));
+        assert_eq!(too_large.load(), Err(Error::TooLarge));
+    }
+
+    #[test]
+    fn load_compressed_chunks() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[9; 16]).unwrap();
+        let payload = base64::encode(encoder.finish().unwrap());
+        let (first, second) = payload.split_at(4);
+
+        let mut command = command(&format!("f=32,s=2,v=2,o=z,m=1;{}", first));
+        assert!(command.more);
+        command.append_chunk(self::command(&format!("m=0;{}", second)));
+        assert!(!command.more);
+
+        let graphic = command.load().unwrap();
+        assert_eq!(graphic.pixels, vec![9; 16]);
+    }
+
+    #[test]
+    fn load_png() {
+        let mut data = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, 2, 1);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[10, 20]).unwrap();
+        }
+
+        let mut command = command(&format!("f=100;{}", base64::encode(data)));
+        let graphic = command.load().unwrap();
+        assert_eq!((graphic.width, graphic.height), (2, 1));
+        assert_eq!(graphic.pixels, vec![10, 10, 10, 255, 20, 20, 20, 255]);
+    }
+
+    #[test]
+    fn load_temp_file() {
+        let path = env::temp_dir().join(format!("{}-alacritty-test", TEMP_FILE_MARKER));
+        fs::write(&path, [1, 2, 3, 4]).unwrap();
+
+        let payload = base64::encode(path.to_str().unwrap());
+        let mut command = command(&format!("f=32,t=t,s=1,v=1;{}", payload));
+        command.preload();
+        assert!(command.payload.is_empty());
+        assert!(!path.exists());
+        assert_eq!(command.load().unwrap().pixels, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn reject_temp_file_outside_temp_dir() {
+        let temp_dir = env::temp_dir();
+        let path = temp_dir.join(format!("{}-alacritty-escape", TEMP_FILE_MARKER));
+        fs::write(&path, [1, 2, 3, 4]).unwrap();
+
+        // Parent directory components are rejected, even if they resolve to a temporary file.
+        let dir_name = temp_dir.file_name().unwrap().to_str().unwrap();
+        let escaped = temp_dir.join("..").join(dir_name).join(path.file_name().unwrap());
+        let payload = base64::encode(escaped.to_str().unwrap());
+        let mut command = self::command(&format!("f=32,t=t,s=1,v=1;{}", payload));
+        assert!(matches!(command.load(), Err(Error::Invalid(_))));
+        assert!(path.exists());
+
+        // Symlinks are rejected, since they could point outside of the temporary directory.
+        #[cfg(unix)]
+        {
+            let link = temp_dir.join(format!("{}-alacritty-link", TEMP_FILE_MARKER));
+            let _ = fs::remove_file(&link);
+            std::os::unix::fs::symlink(&path, &link).unwrap();
+
+            let payload = base64::encode(link.to_str().unwrap());
+            let mut command = self::command(&format!("f=32,t=t,s=1,v=1;{}", payload));
+            assert!(matches!(command.load(), Err(Error::Invalid(_))));
+            assert!(path.exists());
+
+            fs::remove_file(link).unwrap();
+        }
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn reject_oversized_file() {
+        let path = env::temp_dir().join("alacritty-kitty-oversized-test");
+        File::create(&path).unwrap().set_len(MAX_PIXELS_SIZE as u64 + 1).unwrap();
+
+        let payload = base64::encode(path.to_str().unwrap());
+        let mut command = command(&format!("f=32,t=f,s=1,v=1;{}", payload));
+        assert_eq!(command.load(), Err(Error::TooLarge));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn responses() {
+        let command = command("a=t,i=3,p=4");
+        assert_eq!(command.response(3, &Ok(())).unwrap(), "\x1b_Gi=3,p=4;OK\x1b\\");
+
+        let command = self::command("a=t,I=5,q=1");
+        assert_eq!(command.response(9, &Ok(())), None);
+        assert_eq!(
+            command.response(9, &Err(Error::NotFound)).unwrap(),
+            "\x1b_Gi=9,I=5;ENOENT:No such image\x1b\\"
+        );
+
+        let command = self::command("a=t");
+        assert_eq!(command.response(0, &Err(Error::NoData)), None);
+    }
+}
@@ -0,0 +1,281 @@
+//! Storage for images displayed inside the terminal grid.
+//!
+//! Decoded images are queued as [`GraphicData`] until the renderer uploads them as textures.
+//! Every grid cell covered by an image stores a [`GraphicCell`], which keeps the texture alive
+//! while the cell is visible or in the scrollback history. Once the last cell referencing an image
+//! is overwritten or rotated out of the history, its texture is queued for removal.
+//!
+//! Since the placement is stored in the grid cells, images scroll together with the text.
+
+use std::mem;
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+
+pub mod kitty;
+pub mod sixel;
+
+/// Maximum width of a single image in pixels.
+pub const MAX_GRAPHIC_WIDTH: usize = 4096;
+
+/// Maximum height of a single image in pixels.
+pub const MAX_GRAPHIC_HEIGHT: usize = 4096;
+
+/// Unique identifier for every image.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GraphicId(pub u64);
+
+/// Decoded image pixels, waiting to be uploaded to the GPU.
+#[derive(Clone, PartialEq, Eq)]
+pub struct GraphicData {
+    /// Width in pixels.
+    pub width: usize,
+
+    /// Height in pixels.
+    pub height: usize,
+
+    /// Pixels in RGBA format, with 4 bytes per pixel.
+    pub pixels: Vec<u8>,
+}
+
+impl std::fmt::Debug for GraphicData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphicData")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("pixels", &self.pixels.len())
+            .finish()
+    }
+}
+
+/// Reference to a texture uploaded by the renderer.
+///
+/// The texture is queued for removal once the last reference is dropped.
+#[derive(Debug)]
+pub struct TextureRef {
+    /// Graphic identifier.
+    pub id: GraphicId,
+
+    /// Width of the image in pixels.
+    pub width: usize,
+
+    /// Height of the image in pixels.
+    pub height: usize,
+
+    /// Queue of textures which should be removed by the renderer.
+    remove_queue: Weak<Mutex<Vec<GraphicId>>>,
+}
+
+impl PartialEq for TextureRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for TextureRef {}
+
+impl Drop for TextureRef {
+    fn drop(&mut self) {
+        if let Some(remove_queue) = self.remove_queue.upgrade() {
+            remove_queue.lock().push(self.id);
+        }
+    }
+}
+
+/// Identifier of a placement created through the kitty graphics protocol.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KittyPlacementId {
+    /// Image identifier.
+    pub image_id: u32,
+
+    /// Placement identifier, zero if none was specified.
+    pub placement_id: u32,
+}
+
+/// Area of an image in pixels.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// Image displayed in the grid.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GraphicPlacement {
+    /// Texture of the image.
+    pub texture: Arc<TextureRef>,
+
+    /// Area of the texture which is displayed.
+    pub source: PixelRect,
+
+    /// Position and size of the image relative to the top-left corner of its first cell.
+    ///
+    /// This uses the cell size at the time the image was placed, images are scaled with the cells
+    /// if their size changes.
+    pub destination: PixelRect,
+
+    /// Cell width in pixels at the time the image was placed.
+    pub cell_width: usize,
+
+    /// Cell height in pixels at the time the image was placed.
+    pub cell_height: usize,
+
+    /// Images with a negative z-index are drawn below the text.
+    pub z_index: i32,
+
+    /// Identifier for placements of the kitty graphics protocol.
+    ///
+    /// Unlike sixel images, these are not removed when text is written on top of them.
+    pub kitty_id: Option<KittyPlacementId>,
+}
+
+/// Part of an image displayed in a single grid cell.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphicCell {
+    /// Image placement covering this cell.
+    pub placement: Arc<GraphicPlacement>,
+
+    /// Horizontal offset of the cell inside the placement, in cells.
+    pub column: usize,
+
+    /// Vertical offset of the cell inside the placement, in cells.
+    pub line: usize,
+}
+
+/// Pending changes to the textures stored by the renderer.
+#[derive(Debug, Default)]
+pub struct UpdateQueues {
+    /// Images which need to be uploaded.
+    pub pending: Vec<(GraphicId, GraphicData)>,
+
+    /// Textures which can be deleted.
+    pub remove_queue: Vec<GraphicId>,
+}
+
+/// Image state of the terminal.
+#[derive(Debug)]
+pub struct Graphics {
+    /// Last generated identifier.
+    last_id: u64,
+
+    /// Images waiting to be uploaded.
+    pending: Vec<(GraphicId, GraphicData)>,
+
+    /// Textures which aren't referenced by any cell anymore.
+    remove_queue: Arc<Mutex<Vec<GraphicId>>>,
+
+    /// Cell width in pixels.
+    pub cell_width: usize,
+
+    /// Cell height in pixels.
+    pub cell_height: usize,
+
+    /// Images transmitted through the kitty graphics protocol.
+    pub kitty_images: kitty::ImageStorage,
+
+    /// Placements of the kitty graphics protocol, which are alive while any cell references them.
+    kitty_placements: Vec<Weak<GraphicPlacement>>,
+}
+
+impl Default for Graphics {
+    fn default() -> Self {
+        Self {
+            last_id: 0,
+            pending: Vec::new(),
+            remove_queue: Arc::new(Mutex::new(Vec::new())),
+            cell_width: 1,
+            cell_height: 1,
+            kitty_images: Default::default(),
+            kitty_placements: Vec::new(),
+        }
+    }
+}
+
+impl Graphics {
+    /// Queue an image for upload, returning the reference which should be stored in the cells.
+    pub fn insert(&mut self, graphic: GraphicData) -> Arc<TextureRef> {
+        self.last_id += 1;
+        let id = GraphicId(self.last_id);
+
+        let texture = Arc::new(TextureRef {
+            id,
+            width: graphic.width,
+            height: graphic.height,
+            remove_queue: Arc::downgrade(&self.remove_queue),
+        });
+
+        self.pending.push((id, graphic));
+
+        texture
+    }
+
+    /// Keep track of a new kitty graphics placement.
+    pub fn track_kitty_placement(&mut self, placement: &Arc<GraphicPlacement>) {
+        self.kitty_placements.retain(|placement| placement.strong_count() > 0);
+        self.kitty_placements.push(Arc::downgrade(placement));
+    }
+
+    /// Kitty graphics placements which are still stored in the grid.
+    pub fn kitty_placements(&self) -> impl Iterator<Item = Arc<GraphicPlacement>> + '_ {
+        self.kitty_placements.iter().filter_map(Weak::upgrade)
+    }
+
+    /// Take all pending texture updates.
+    pub fn take_queues(&mut self) -> Option<UpdateQueues> {
+        let remove_queue = mem::take(&mut *self.remove_queue.lock());
+
+        if self.pending.is_empty() && remove_queue.is_empty() {
+            return None;
+        }
+
+        Some(UpdateQueues { pending: mem::take(&mut self.pending), remove_queue })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn textures_are_removed_when_unused() {
+        let mut graphics = Graphics::default();
+        let data = GraphicData { width: 1, height: 1, pixels: vec![0; 4] };
+
+        let texture = graphics.insert(data.clone());
+        let id = texture.id;
+        let queues = graphics.take_queues().unwrap();
+        assert_eq!(queues.pending, vec![(id, data)]);
+        assert!(queues.remove_queue.is_empty());
+        assert!(graphics.take_queues().is_none());
+
+        drop(texture);
+        let queues = graphics.take_queues().unwrap();
+        assert!(queues.pending.is_empty());
+        assert_eq!(queues.remove_queue, vec![id]);
+    }
+
+    #[test]
+    fn kitty_placements_are_tracked_while_used() {
+        let mut graphics = Graphics::default();
+        let data = GraphicData { width: 1, height: 1, pixels: vec![0; 4] };
+
+        let area = PixelRect { x: 0, y: 0, width: 1, height: 1 };
+        let placement = Arc::new(GraphicPlacement {
+            texture: graphics.insert(data),
+            source: area,
+            destination: area,
+            cell_width: 1,
+            cell_height: 1,
+            z_index: 0,
+            kitty_id: Some(KittyPlacementId { image_id: 1, placement_id: 0 }),
+        });
+
+        graphics.track_kitty_placement(&placement);
+        assert_eq!(graphics.kitty_placements().count(), 1);
+
+        drop(placement);
+        assert_eq!(graphics.kitty_placements().count(), 0);
+    }
+}
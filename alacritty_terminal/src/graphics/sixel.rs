@@ -0,0 +1,451 @@
+//! Decoder for the Sixel image format.
+//!
+//! Sixel images are transmitted as a DCS sequence (`DCS P1 ; P2 ; P3 q <data> ST`), where every
+//! data byte encodes a column of six vertical pixels. See the VT330/VT340 programmer reference for
+//! the full description of the format.
+
+use log::debug;
+use vte::Params;
+
+use crate::graphics::{GraphicData, MAX_GRAPHIC_HEIGHT, MAX_GRAPHIC_WIDTH};
+use crate::term::color::Rgb;
+
+/// Number of color registers available to every image.
+pub const MAX_COLOR_REGISTERS: usize = 1024;
+
+/// Number of pixels encoded in a single sixel.
+const SIXEL_HEIGHT: usize = 6;
+
+/// Color of pixels which haven't been drawn by the image.
+const TRANSPARENT: [u8; 4] = [0, 0, 0, 0];
+
+/// Default color palette of the VT340.
+const VT340_PALETTE: [Rgb; 16] = [
+    Rgb { r: 0, g: 0, b: 0 },
+    Rgb { r: 51, g: 51, b: 204 },
+    Rgb { r: 204, g: 33, b: 33 },
+    Rgb { r: 51, g: 204, b: 51 },
+    Rgb { r: 204, g: 51, b: 204 },
+    Rgb { r: 51, g: 204, b: 204 },
+    Rgb { r: 204, g: 204, b: 51 },
+    Rgb { r: 120, g: 120, b: 120 },
+    Rgb { r: 69, g: 69, b: 69 },
+    Rgb { r: 87, g: 87, b: 153 },
+    Rgb { r: 153, g: 69, b: 69 },
+    Rgb { r: 87, g: 153, b: 87 },
+    Rgb { r: 153, g: 87, b: 153 },
+    Rgb { r: 87, g: 153, b: 153 },
+    Rgb { r: 153, g: 153, b: 87 },
+    Rgb { r: 204, g: 204, b: 204 },
+];
+
+/// Control function which is still collecting its parameters.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+    /// Graphics repeat introducer (`!`).
+    Repeat,
+
+    /// Color introducer (`#`).
+    Color,
+
+    /// Raster attributes (`"`).
+    RasterAttributes,
+}
+
+/// Incremental Sixel decoder.
+#[derive(Debug)]
+pub struct Parser {
+    /// Command waiting for its parameters to be completed.
+    command: Option<Command>,
+
+    /// Parameters of the pending command.
+    params: Vec<u16>,
+
+    /// Color registers of the image.
+    palette: Vec<Rgb>,
+
+    /// Color of the next sixels.
+    color: [u8; 4],
+
+    /// Number of times the next sixel is repeated.
+    repeat: usize,
+
+    /// Horizontal position of the next sixel.
+    x: usize,
+
+    /// Top of the current sixel row.
+    y: usize,
+
+    /// Width of the image.
+    width: usize,
+
+    /// Height of the image.
+    height: usize,
+
+    /// Width of the pixel buffer, which can exceed the image width.
+    buffer_width: usize,
+
+    /// Height of the pixel buffer, which can exceed the image height.
+    buffer_height: usize,
+
+    /// Color of pixels not set by the image.
+    background: [u8; 4],
+
+    /// Pixels in RGBA format.
+    pixels: Vec<u8>,
+}
+
+impl Parser {
+    /// Create a decoder using the parameters of the DCS sequence.
+    ///
+    /// The pixel aspect ratio (`P1`) and grid size (`P3`) are ignored, since every sixel is drawn
+    /// with square pixels.
+    pub fn new(params: &Params) -> Self {
+        let mut palette = Vec::with_capacity(MAX_COLOR_REGISTERS);
+        palette.extend_from_slice(&VT340_PALETTE);
+        palette.resize(MAX_COLOR_REGISTERS, Rgb::default());
+
+        // Unset pixels are transparent if `P2` is 1, otherwise they use color register 0.
+        let background = match params.iter().nth(1) {
+            Some([1, ..]) => TRANSPARENT,
+            _ => rgba(palette[0]),
+        };
+
+        Self {
+            background,
+            palette,
+            command: None,
+            params: Vec::new(),
+            color: rgba(VT340_PALETTE[0]),
+            repeat: 1,
+            x: 0,
+            y: 0,
+            width: 0,
+            height: 0,
+            buffer_width: 0,
+            buffer_height: 0,
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Process a byte of the image data.
+    pub fn put(&mut self, byte: u8) {
+        // Collect parameters of the pending command.
+        if self.command.is_some() {
+            match byte {
+                b'0'..=b'9' => {
+                    if self.params.is_empty() {
+                        self.params.push(0);
+                    }
+
+                    let param = self.params.last_mut().unwrap();
+                    *param = param.saturating_mul(10).saturating_add(u16::from(byte - b'0'));
+                    return;
+                },
+                b';' => {
+                    if self.params.is_empty() {
+                        self.params.push(0);
+                    }
+                    self.params.push(0);
+                    return;
+                },
+                _ => self.finish_command(),
+            }
+        }
+
+        match byte {
+            b'?'..=b'~' => self.draw_sixel(byte - b'?'),
+            b'!' => self.command = Some(Command::Repeat),
+            b'#' => self.command = Some(Command::Color),
+            b'"' => self.command = Some(Command::RasterAttributes),
+            b'$' => self.x = 0,
+            b'-' => {
+                self.x = 0;
+                self.y += SIXEL_HEIGHT;
+            },
+            _ => (),
+        }
+    }
+
+    /// Complete the image.
+    ///
+    /// Returns `None` if the image does not contain any pixels.
+    pub fn finish(mut self) -> Option<GraphicData> {
+        self.finish_command();
+
+        if self.width == 0 || self.height == 0 {
+            return None;
+        }
+
+        // Crop the pixel buffer to the size of the image.
+        let pixels = if self.width == self.buffer_width && self.height == self.buffer_height {
+            self.pixels
+        } else {
+            let mut pixels = Vec::with_capacity(self.width * self.height * 4);
+            for row in self.pixels.chunks_exact(self.buffer_width * 4).take(self.height) {
+                pixels.extend_from_slice(&row[..self.width * 4]);
+            }
+            pixels
+        };
+
+        Some(GraphicData { width: self.width, height: self.height, pixels })
+    }
+
+    /// Execute the pending command.
+    fn finish_command(&mut self) {
+        let command = match self.command.take() {
+            Some(command) => command,
+            None => return,
+        };
+        let params = std::mem::take(&mut self.params);
+        let param = |index: usize| params.get(index).copied().unwrap_or(0);
+
+        match command {
+            Command::Repeat => self.repeat = usize::from(param(0)).max(1),
+            Command::Color => {
+                let register = usize::from(param(0)) % MAX_COLOR_REGISTERS;
+
+                if params.len() >= 5 {
+                    let color = match param(1) {
+                        1 => hls_to_rgb(param(2), param(3), param(4)),
+                        2 => Rgb {
+                            r: percent_to_byte(param(2)),
+                            g: percent_to_byte(param(3)),
+                            b: percent_to_byte(param(4)),
+                        },
+                        space => {
+                            debug!("Unsupported sixel color space: {}", space);
+                            return;
+                        },
+                    };
+                    self.palette[register] = color;
+                }
+
+                self.color = rgba(self.palette[register]);
+            },
+            Command::RasterAttributes => {
+                let width = usize::from(param(2));
+                let height = usize::from(param(3));
+                self.grow(width, height);
+            },
+        }
+    }
+
+    /// Draw the pixels of a sixel at the current position.
+    fn draw_sixel(&mut self, sixel: u8) {
+        let repeat = std::mem::replace(&mut self.repeat, 1);
+        let start = self.x;
+        self.x = self.x.saturating_add(repeat);
+
+        // Ignore empty sixels, to avoid growing the image.
+        if sixel == 0 {
+            return;
+        }
+
+        self.grow(self.x, self.y + SIXEL_HEIGHT);
+
+        let end = self.x.min(self.width);
+        for bit in 0..SIXEL_HEIGHT {
+            let y = self.y + bit;
+            if sixel & (1 << bit) == 0 || y >= self.height {
+                continue;
+            }
+
+            for x in start..end {
+                let index = (y * self.buffer_width + x) * 4;
+                self.pixels[index..index + 4].copy_from_slice(&self.color);
+            }
+        }
+    }
+
+    /// Grow the image to contain at least the requested size.
+    ///
+    /// The pixel buffer grows geometrically, so images extended one sixel at a time aren't copied
+    /// for every sixel.
+    fn grow(&mut self, width: usize, height: usize) {
+        self.width = width.min(MAX_GRAPHIC_WIDTH).max(self.width);
+        self.height = height.min(MAX_GRAPHIC_HEIGHT).max(self.height);
+
+        if self.width <= self.buffer_width && self.height <= self.buffer_height {
+            return;
+        }
+
+        let buffer_width = grow_capacity(self.buffer_width, self.width, MAX_GRAPHIC_WIDTH);
+        let buffer_height = grow_capacity(self.buffer_height, self.height, MAX_GRAPHIC_HEIGHT);
+
+        let row_len = self.buffer_width * 4;
+        let mut pixels = Vec::with_capacity(buffer_width * buffer_height * 4);
+        for y in 0..buffer_height {
+            if y < self.buffer_height {
+                let row_start = y * row_len;
+                pixels.extend_from_slice(&self.pixels[row_start..row_start + row_len]);
+            }
+
+            let filled = if y < self.buffer_height { self.buffer_width } else { 0 };
+            for _ in filled..buffer_width {
+                pixels.extend_from_slice(&self.background);
+            }
+        }
+
+        self.buffer_width = buffer_width;
+        self.buffer_height = buffer_height;
+        self.pixels = pixels;
+    }
+}
+
+/// Size of a buffer dimension which fits `required` elements.
+///
+/// The current size is at least doubled, to amortize the cost of growing the buffer.
+#[inline]
+fn grow_capacity(current: usize, required: usize, max: usize) -> usize {
+    if required <= current {
+        current
+    } else {
+        required.max(current * 2).min(max)
+    }
+}
+
+/// Convert a color to opaque RGBA bytes.
+#[inline]
+fn rgba(color: Rgb) -> [u8; 4] {
+    [color.r, color.g, color.b, u8::MAX]
+}
+
+/// Convert a color component from 0-100 to 0-255.
+#[inline]
+fn percent_to_byte(percent: u16) -> u8 {
+    (u32::from(percent.min(100)) * 255 / 100) as u8
+}
+
+/// Convert a color in the HLS color space to RGB.
+///
+/// Sixel hues are rotated compared to the usual HLS definition, with blue at 0 degrees.
+fn hls_to_rgb(hue: u16, lightness: u16, saturation: u16) -> Rgb {
+    let lightness = f32::from(lightness.min(100)) / 100.;
+    let saturation = f32::from(saturation.min(100)) / 100.;
+    let hue = f32::from((hue % 360 + 240) % 360);
+
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let x = chroma * (1. - ((hue / 60.) % 2. - 1.).abs());
+    let m = lightness - chroma / 2.;
+
+    let (r, g, b) = match hue as u16 / 60 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+
+    let byte = |component: f32| ((component + m) * 255.).round() as u8;
+    Rgb { r: byte(r), g: byte(g), b: byte(b) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(params: &[u16], data: &[u8]) -> Option<GraphicData> {
+        let mut parser = parser(params);
+        for &byte in data {
+            parser.put(byte);
+        }
+        parser.finish()
+    }
+
+    fn parser(params: &[u16]) -> Parser {
+        let mut vte_params = vte::Parser::new();
+        let mut collector = ParamsCollector(None);
+        vte_params.advance(&mut collector, b'\x1b');
+        vte_params.advance(&mut collector, b'P');
+        for (i, param) in params.iter().enumerate() {
+            if i != 0 {
+                vte_params.advance(&mut collector, b';');
+            }
+            for byte in param.to_string().bytes() {
+                vte_params.advance(&mut collector, byte);
+            }
+        }
+        vte_params.advance(&mut collector, b'q');
+
+        collector.0.unwrap()
+    }
+
+    struct ParamsCollector(Option<Parser>);
+
+    impl vte::Perform for ParamsCollector {
+        fn hook(&mut self, params: &Params, _: &[u8], _: bool, _: char) {
+            self.0 = Some(Parser::new(params));
+        }
+    }
+
+    fn pixel(graphic: &GraphicData, x: usize, y: usize) -> &[u8] {
+        let index = (y * graphic.width + x) * 4;
+        &graphic.pixels[index..index + 4]
+    }
+
+    #[test]
+    fn decode_single_sixel() {
+        // Draw the first and last pixel of a sixel, using a custom RGB color.
+        let graphic = decode(&[0, 1], b"#1;2;100;0;0#1`").unwrap();
+
+        assert_eq!((graphic.width, graphic.height), (1, 6));
+        assert_eq!(pixel(&graphic, 0, 0), [255, 0, 0, 255]);
+        assert_eq!(pixel(&graphic, 0, 1), TRANSPARENT);
+        assert_eq!(pixel(&graphic, 0, 5), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decode_repeat_and_newline() {
+        let graphic = decode(&[0, 1], b"!3~-#2?~").unwrap();
+
+        assert_eq!((graphic.width, graphic.height), (3, 12));
+        assert_eq!(pixel(&graphic, 2, 5), rgba(VT340_PALETTE[0]));
+        assert_eq!(pixel(&graphic, 0, 6), TRANSPARENT);
+        assert_eq!(pixel(&graphic, 1, 11), rgba(VT340_PALETTE[2]));
+        assert_eq!(pixel(&graphic, 2, 11), TRANSPARENT);
+    }
+
+    #[test]
+    fn decode_raster_attributes_background() {
+        let graphic = decode(&[0, 0], b"\"1;1;4;2#15@").unwrap();
+
+        assert_eq!((graphic.width, graphic.height), (4, 6));
+        assert_eq!(pixel(&graphic, 0, 0), rgba(VT340_PALETTE[15]));
+        assert_eq!(pixel(&graphic, 3, 5), rgba(VT340_PALETTE[0]));
+    }
+
+    #[test]
+    fn grow_buffer_geometrically() {
+        let mut data = vec![b'~'; 1000];
+        data.extend_from_slice(b"-~");
+
+        let mut parser = parser(&[0, 1]);
+        for byte in data {
+            parser.put(byte);
+        }
+        assert_eq!((parser.buffer_width, parser.buffer_height), (1024, 12));
+
+        // The image is cropped to the drawn sixels.
+        let graphic = parser.finish().unwrap();
+        assert_eq!((graphic.width, graphic.height), (1000, 12));
+        assert_eq!(graphic.pixels.len(), 1000 * 12 * 4);
+        assert_eq!(pixel(&graphic, 999, 5), rgba(VT340_PALETTE[0]));
+        assert_eq!(pixel(&graphic, 0, 6), rgba(VT340_PALETTE[0]));
+        assert_eq!(pixel(&graphic, 1, 6), TRANSPARENT);
+    }
+
+    #[test]
+    fn decode_empty_image() {
+        assert_eq!(decode(&[], b"#1;2;0;0;0$-"), None);
+    }
+
+    #[test]
+    fn hls_colors() {
+        assert_eq!(hls_to_rgb(0, 50, 100), Rgb { r: 0, g: 0, b: 255 });
+        assert_eq!(hls_to_rgb(120, 50, 100), Rgb { r: 255, g: 0, b: 0 });
+        assert_eq!(hls_to_rgb(240, 50, 100), Rgb { r: 0, g: 255, b: 0 });
+        assert_eq!(hls_to_rgb(0, 100, 0), Rgb { r: 255, g: 255, b: 255 });
+    }
+}
@@ -1,6 +1,7 @@
 //! A specialized 2D grid implementation optimized for use in a terminal.
 
 use std::cmp::{max, min};
+use std::mem;
 use std::ops::{Bound, Deref, Index, IndexMut, Range, RangeBounds};
 
 use serde::{Deserialize, Serialize};
@@ -15,7 +16,7 @@ mod storage;
 #[cfg(test)]
 mod tests;
 
-pub use self::row::Row;
+pub use self::row::{LineAttr, Row, RowAttrs, ShellMarks};
 use self::storage::Storage;
 
 pub trait GridCell: Sized {
@@ -185,6 +186,54 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         }
     }
 
+    /// Scroll the cells within a column range of the region down.
+    ///
+    /// This is used for scrolling with horizontal margins, where only part of every line moves.
+    pub fn scroll_down_columns(
+        &mut self,
+        region: &Range<Line>,
+        columns: &Range<Column>,
+        positions: usize,
+    ) {
+        let positions = min(positions, (region.end - region.start).0 as usize) as i32;
+
+        for line in ((region.start.0 + positions)..region.end.0).rev().map(Line::from) {
+            for column in (columns.start.0..columns.end.0).map(Column) {
+                self.raw[line][column] = mem::take(&mut self.raw[line - positions][column]);
+            }
+        }
+
+        for line in (region.start.0..(region.start.0 + positions)).map(Line::from) {
+            for column in (columns.start.0..columns.end.0).map(Column) {
+                self.raw[line][column].reset(&self.cursor.template);
+            }
+        }
+    }
+
+    /// Scroll the cells within a column range of the region up.
+    ///
+    /// Unlike [`Grid::scroll_up`], this never moves any lines into the scrollback history.
+    pub fn scroll_up_columns(
+        &mut self,
+        region: &Range<Line>,
+        columns: &Range<Column>,
+        positions: usize,
+    ) {
+        let positions = min(positions, (region.end - region.start).0 as usize) as i32;
+
+        for line in (region.start.0..(region.end.0 - positions)).map(Line::from) {
+            for column in (columns.start.0..columns.end.0).map(Column) {
+                self.raw[line][column] = mem::take(&mut self.raw[line + positions][column]);
+            }
+        }
+
+        for line in ((region.end.0 - positions)..region.end.0).map(Line::from) {
+            for column in (columns.start.0..columns.end.0).map(Column) {
+                self.raw[line][column].reset(&self.cursor.template);
+            }
+        }
+    }
+
     #[inline]
     pub fn scroll_down<D>(&mut self, region: &Range<Line>, positions: usize)
     where
@@ -194,7 +243,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // When rotating the entire region, just reset everything.
         if region.end - region.start <= positions {
             for i in (region.start.0..region.end.0).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset_row(i, &self.cursor.template);
             }
 
             return;
@@ -223,7 +272,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
             // Ensure all new lines are fully cleared.
             for i in (0..positions).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset_row(i, &self.cursor.template);
             }
 
             // Swap the fixed lines at the top back into position.
@@ -239,7 +288,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
             let range = region.start.0..(region.start + positions).0;
             for line in range.rev().map(Line::from) {
-                self.raw[line].reset(&self.cursor.template);
+                self.raw.reset_row(line, &self.cursor.template);
             }
         }
     }
@@ -255,7 +304,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // When rotating the entire region with fixed lines at the top, just reset everything.
         if region.end - region.start <= positions && region.start != 0 {
             for i in (region.start.0..region.end.0).map(Line::from) {
-                self.raw[i].reset(&self.cursor.template);
+                self.raw.reset_row(i, &self.cursor.template);
             }
 
             return;
@@ -287,7 +336,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         // Ensure all new lines are fully cleared.
         let screen_lines = self.screen_lines();
         for i in ((screen_lines - positions)..screen_lines).map(Line::from) {
-            self.raw[i].reset(&self.cursor.template);
+            self.raw.reset_row(i, &self.cursor.template);
         }
 
         // Swap the fixed lines at the bottom back into position.
@@ -318,7 +367,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
 
         // Reset rotated lines.
         for line in (0..(self.lines - positions)).map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset_row(line, &self.cursor.template);
         }
     }
 
@@ -329,15 +378,23 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         D: PartialEq,
     {
         self.clear_history();
+        self.reset_screen();
+    }
 
+    /// Reset the grid state, without clearing the scrollback history.
+    pub fn reset_screen<D>(&mut self)
+    where
+        T: ResetDiscriminant<D>,
+        D: PartialEq,
+    {
         self.saved_cursor = Cursor::default();
         self.cursor = Cursor::default();
         self.display_offset = 0;
 
         // Reset all visible lines.
-        let range = self.topmost_line().0..(self.screen_lines() as i32);
+        let range = 0..(self.screen_lines() as i32);
         for line in range.map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset_row(line, &self.cursor.template);
         }
     }
 }
@@ -365,7 +422,7 @@ impl<T> Grid<T> {
         debug_assert!(end <= self.screen_lines() as i32);
 
         for line in (start.0..end.0).map(Line::from) {
-            self.raw[line].reset(&self.cursor.template);
+            self.raw.reset_row(line, &self.cursor.template);
         }
     }
 
@@ -428,6 +485,18 @@ impl<T> Grid<T> {
         let point = self.cursor.point;
         &mut self[point.line][point.column]
     }
+
+    /// Attributes of a row, like its shell integration marks.
+    #[inline]
+    pub fn row_attrs(&self, line: Line) -> RowAttrs {
+        self.raw.attrs(line)
+    }
+
+    /// Mutable attributes of a row.
+    #[inline]
+    pub fn row_attrs_mut(&mut self, line: Line) -> &mut RowAttrs {
+        self.raw.attrs_mut(line)
+    }
 }
 
 impl<T: PartialEq> PartialEq for Grid<T> {
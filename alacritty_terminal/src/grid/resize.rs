@@ -1,13 +1,14 @@
 //! Grid resize and reflow.
 
 use std::cmp::{max, min, Ordering};
+use std::collections::BTreeMap;
 use std::mem;
 
 use crate::index::{Boundary, Column, Line};
 use crate::term::cell::{Flags, ResetDiscriminant};
 
 use crate::grid::row::Row;
-use crate::grid::{Dimensions, Grid, GridCell};
+use crate::grid::{Dimensions, Grid, GridCell, RowAttrs};
 
 impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
     /// Resize the grid's width and/or height.
@@ -116,13 +117,17 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             self.cursor.point.column += 1;
         }
 
-        let mut rows = self.raw.take_all();
+        let (mut rows, mut attrs) = self.raw.take_all();
+        let mut reversed_attrs = BTreeMap::new();
 
         for (i, mut row) in rows.drain(..).enumerate().rev() {
+            let row_attrs = attrs.remove(&i);
+
             // Check if reflowing should be performed.
             let last_row = match reversed.last_mut() {
                 Some(last_row) if should_reflow(last_row) => last_row,
                 _ => {
+                    reversed_attrs.extend(row_attrs.map(|attrs| (reversed.len(), attrs)));
                     reversed.push(row);
                     continue;
                 },
@@ -182,6 +187,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 let line_delta = self.cursor.point.line - target.line;
 
                 if line_delta != 0 && row.is_clear() {
+                    merge_row_attrs(&mut reversed_attrs, reversed.len() - 1, row_attrs);
                     continue;
                 }
 
@@ -198,6 +204,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 }
 
                 // Don't push line into the new buffer.
+                merge_row_attrs(&mut reversed_attrs, reversed.len() - 1, row_attrs);
                 continue;
             }
 
@@ -206,6 +213,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
                 cell.flags_mut().insert(Flags::WRAPLINE);
             }
 
+            reversed_attrs.extend(row_attrs.map(|attrs| (reversed.len(), attrs)));
             reversed.push(row);
         }
 
@@ -223,6 +231,7 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             let available = min(cursor_buffer_line, reversed.len() - self.lines);
             let overflow = cursor_line_delta.saturating_sub(available);
             reversed.truncate(reversed.len() + overflow - cursor_line_delta);
+            reversed_attrs.split_off(&reversed.len());
             self.cursor.point.line = max(self.cursor.point.line - overflow, Line(0));
         }
 
@@ -235,7 +244,9 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
             new_raw.push(row);
         }
 
-        self.raw.replace_inner(new_raw);
+        let len = new_raw.len();
+        let new_attrs = reversed_attrs.into_iter().map(|(i, attrs)| (len - i - 1, attrs)).collect();
+        self.raw.replace_inner(new_raw, new_attrs);
 
         // Clamp display offset in case lines above it got merged.
         self.display_offset = min(self.display_offset, self.history_size());
@@ -254,8 +265,12 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         let mut new_raw = Vec::with_capacity(self.raw.len());
         let mut buffered: Option<Vec<T>> = None;
 
-        let mut rows = self.raw.take_all();
+        let (mut rows, mut attrs) = self.raw.take_all();
+        let mut new_attrs = BTreeMap::new();
         for (i, mut row) in rows.drain(..).enumerate().rev() {
+            // Keep attributes with the first line of the reflown row.
+            new_attrs.extend(attrs.remove(&i).map(|attrs| (new_raw.len(), attrs)));
+
             // Append lines left over from the previous row.
             if let Some(buffered) = buffered.take() {
                 // Add a column for every cell added before the cursor, if it goes beyond the new
@@ -364,9 +379,15 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         }
 
         // Reverse iterator and use it as the new grid storage.
+        let len = new_raw.len();
         let mut reversed: Vec<Row<T>> = new_raw.drain(..).rev().collect();
         reversed.truncate(self.max_scroll_limit + self.lines);
-        self.raw.replace_inner(reversed);
+        let reversed_attrs = new_attrs
+            .into_iter()
+            .map(|(i, attrs)| (len - i - 1, attrs))
+            .filter(|(i, _)| *i < reversed.len())
+            .collect();
+        self.raw.replace_inner(reversed, reversed_attrs);
 
         // Clamp display offset in case some lines went off.
         self.display_offset = min(self.display_offset, self.history_size());
@@ -387,3 +408,17 @@ impl<T: GridCell + Default + PartialEq + Clone> Grid<T> {
         self.saved_cursor.point.column = min(self.saved_cursor.point.column, Column(columns - 1));
     }
 }
+
+/// Merge the attributes of a row into the row above it, after its content was reflown into it.
+fn merge_row_attrs(attrs: &mut BTreeMap<usize, RowAttrs>, index: usize, merged: Option<RowAttrs>) {
+    let merged = match merged {
+        Some(merged) => merged,
+        None => return,
+    };
+
+    let marks = &mut attrs.entry(index).or_insert(merged).shell_marks;
+    marks.prompt |= merged.shell_marks.prompt;
+    marks.output |= merged.shell_marks.output;
+    marks.command_end |= merged.shell_marks.command_end;
+    marks.exit_code = marks.exit_code.or(merged.shell_marks.exit_code);
+}
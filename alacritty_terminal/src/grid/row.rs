@@ -10,6 +10,56 @@ use crate::grid::GridCell;
 use crate::index::Column;
 use crate::term::cell::ResetDiscriminant;
 
+/// Semantic prompt zone boundaries inside a row, reported by the shell through OSC 133.
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct ShellMarks {
+    /// A prompt starts in this row.
+    pub prompt: bool,
+
+    /// The output of a command starts in this row.
+    pub output: bool,
+
+    /// A command finished in this row.
+    pub command_end: bool,
+
+    /// Exit status of the command whose prompt starts in this row.
+    pub exit_code: Option<i32>,
+}
+
+/// Character size of a row, set through DECSWL, DECDWL and DECDHL.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineAttr {
+    /// Single-width and single-height characters.
+    Single,
+
+    /// Double-width characters.
+    DoubleWidth,
+
+    /// Top half of double-width and double-height characters.
+    DoubleTop,
+
+    /// Bottom half of double-width and double-height characters.
+    DoubleBottom,
+}
+
+impl Default for LineAttr {
+    fn default() -> Self {
+        LineAttr::Single
+    }
+}
+
+/// Attributes of a row, which are stored outside of it since most rows don't have any.
+#[derive(Serialize, Deserialize, Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct RowAttrs {
+    /// Shell integration marks of the row.
+    #[serde(skip)]
+    pub shell_marks: ShellMarks,
+
+    /// Character size of the row.
+    #[serde(default)]
+    pub line_attr: LineAttr,
+}
+
 /// A row in the grid.
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
 pub struct Row<T> {
@@ -1,12 +1,14 @@
 use std::cmp::{max, PartialEq};
+use std::collections::BTreeMap;
 use std::mem;
 use std::mem::MaybeUninit;
 use std::ops::{Index, IndexMut};
 
 use serde::{Deserialize, Serialize};
 
-use super::Row;
+use super::{GridCell, LineAttr, Row, RowAttrs};
 use crate::index::Line;
+use crate::term::cell::ResetDiscriminant;
 
 /// Maximum number of buffered lines outside of the grid for performance optimization.
 const MAX_CACHE_SIZE: usize = 1_000;
@@ -48,6 +50,13 @@ pub struct Storage<T> {
     /// As long as `len` is bigger than `inner`, it is also possible to grow the scrollback buffer
     /// without any additional insertions.
     len: usize,
+
+    /// Attributes of rows, keyed by their index in the raw buffer.
+    ///
+    /// Since only few rows have any attributes, they're kept in a sparse table to avoid growing
+    /// [`Row`], which would slow down swapping rows while scrolling.
+    #[serde(default)]
+    attrs: BTreeMap<usize, RowAttrs>,
 }
 
 impl<T: PartialEq> PartialEq for Storage<T> {
@@ -56,7 +65,17 @@ impl<T: PartialEq> PartialEq for Storage<T> {
         assert_eq!(self.zero, 0);
         assert_eq!(other.zero, 0);
 
-        self.inner == other.inner && self.len == other.len
+        // Shell marks aren't serialized, so only line attributes are compared.
+        let line_attrs = |storage: &'_ Self| {
+            storage
+                .attrs
+                .iter()
+                .filter(|(_, attrs)| attrs.line_attr != LineAttr::Single)
+                .map(|(index, attrs)| (*index, attrs.line_attr))
+                .collect::<Vec<_>>()
+        };
+
+        self.inner == other.inner && self.len == other.len && line_attrs(self) == line_attrs(other)
     }
 }
 
@@ -70,7 +89,7 @@ impl<T> Storage<T> {
         let mut inner = Vec::with_capacity(visible_lines);
         inner.resize_with(visible_lines, || Row::new(columns));
 
-        Storage { inner, zero: 0, visible_lines, len: visible_lines }
+        Storage { inner, zero: 0, visible_lines, len: visible_lines, attrs: BTreeMap::new() }
     }
 
     /// Increase the number of lines in the buffer.
@@ -104,6 +123,7 @@ impl<T> Storage<T> {
     #[inline]
     pub fn shrink_lines(&mut self, shrinkage: usize) {
         self.len -= shrinkage;
+        self.remove_stale_attrs();
 
         // Free memory.
         if self.inner.len() > self.len + MAX_CACHE_SIZE {
@@ -117,6 +137,7 @@ impl<T> Storage<T> {
         self.rezero();
 
         self.inner.truncate(self.len);
+        self.remove_stale_attrs();
     }
 
     /// Dynamically grow the storage buffer at runtime.
@@ -171,6 +192,43 @@ impl<T> Storage<T> {
                 *b_ptr.offset(i) = tmp;
             }
         }
+
+        // Row attributes are rare, so avoid touching the table unless necessary.
+        if !self.attrs.is_empty() {
+            let a_attrs = self.attrs.remove(&a);
+            let b_attrs = self.attrs.remove(&b);
+            self.attrs.extend(a_attrs.map(|attrs| (b, attrs)));
+            self.attrs.extend(b_attrs.map(|attrs| (a, attrs)));
+        }
+    }
+
+    /// Attributes of a row.
+    #[inline]
+    pub fn attrs(&self, line: Line) -> RowAttrs {
+        let index = self.compute_index(line);
+        self.attrs.get(&index).copied().unwrap_or_default()
+    }
+
+    /// Mutable attributes of a row.
+    #[inline]
+    pub fn attrs_mut(&mut self, line: Line) -> &mut RowAttrs {
+        let index = self.compute_index(line);
+        self.attrs.entry(index).or_default()
+    }
+
+    /// Reset a row and remove all of its attributes.
+    #[inline]
+    pub fn reset_row<D>(&mut self, line: Line, template: &T)
+    where
+        T: ResetDiscriminant<D> + GridCell + Clone + Default,
+        D: PartialEq,
+    {
+        let index = self.compute_index(line);
+        self.inner[index].reset(template);
+
+        if !self.attrs.is_empty() {
+            self.attrs.remove(&index);
+        }
     }
 
     /// Rotate the grid, moving all lines up/down in history.
@@ -193,16 +251,21 @@ impl<T> Storage<T> {
     }
 
     /// Update the raw storage buffer.
+    ///
+    /// The attributes are keyed by the index of their row in `vec`.
     #[inline]
-    pub fn replace_inner(&mut self, vec: Vec<Row<T>>) {
+    pub fn replace_inner(&mut self, vec: Vec<Row<T>>, attrs: BTreeMap<usize, RowAttrs>) {
         self.len = vec.len();
         self.inner = vec;
+        self.attrs = attrs;
         self.zero = 0;
     }
 
     /// Remove all rows from storage.
+    ///
+    /// The returned attributes are keyed by the index of their row in the returned buffer.
     #[inline]
-    pub fn take_all(&mut self) -> Vec<Row<T>> {
+    pub fn take_all(&mut self) -> (Vec<Row<T>>, BTreeMap<usize, RowAttrs>) {
         self.truncate();
 
         let mut buffer = Vec::new();
@@ -210,7 +273,7 @@ impl<T> Storage<T> {
         mem::swap(&mut buffer, &mut self.inner);
         self.len = 0;
 
-        buffer
+        (buffer, mem::take(&mut self.attrs))
     }
 
     /// Compute actual index in underlying storage given the requested index.
@@ -243,8 +306,22 @@ impl<T> Storage<T> {
         }
 
         self.inner.rotate_left(self.zero);
+
+        let (zero, inner_len) = (self.zero, self.inner.len());
+        self.attrs = mem::take(&mut self.attrs)
+            .into_iter()
+            .map(|(index, attrs)| ((index + inner_len - zero) % inner_len, attrs))
+            .collect();
+
         self.zero = 0;
     }
+
+    /// Remove attributes of rows which are no longer part of the storage.
+    #[inline]
+    fn remove_stale_attrs(&mut self) {
+        let (zero, len, inner_len) = (self.zero, self.len, self.inner.len());
+        self.attrs.retain(|index, _| (index + inner_len - zero) % inner_len < len);
+    }
 }
 
 impl<T> Index<Line> for Storage<T> {
@@ -267,6 +344,8 @@ impl<T> IndexMut<Line> for Storage<T> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use crate::grid::row::Row;
     use crate::grid::storage::{Storage, MAX_CACHE_SIZE};
     use crate::grid::GridCell;
@@ -356,6 +435,7 @@ mod tests {
             zero: 0,
             visible_lines: 3,
             len: 3,
+            attrs: BTreeMap::new(),
         };
 
         // Grow buffer.
@@ -367,6 +447,7 @@ mod tests {
             zero: 0,
             visible_lines: 4,
             len: 4,
+            attrs: BTreeMap::new(),
         };
         expected.inner.append(&mut vec![filled_row('\0'); MAX_CACHE_SIZE]);
 
@@ -397,6 +478,7 @@ mod tests {
             zero: 1,
             visible_lines: 3,
             len: 3,
+            attrs: BTreeMap::new(),
         };
 
         // Grow buffer.
@@ -408,6 +490,7 @@ mod tests {
             zero: 0,
             visible_lines: 4,
             len: 4,
+            attrs: BTreeMap::new(),
         };
         expected.inner.append(&mut vec![filled_row('\0'); MAX_CACHE_SIZE]);
 
@@ -435,6 +518,7 @@ mod tests {
             zero: 1,
             visible_lines: 3,
             len: 3,
+            attrs: BTreeMap::new(),
         };
 
         // Shrink buffer.
@@ -446,6 +530,7 @@ mod tests {
             zero: 1,
             visible_lines: 2,
             len: 2,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -471,6 +556,7 @@ mod tests {
             zero: 0,
             visible_lines: 3,
             len: 3,
+            attrs: BTreeMap::new(),
         };
 
         // Shrink buffer.
@@ -482,6 +568,7 @@ mod tests {
             zero: 0,
             visible_lines: 2,
             len: 2,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -520,6 +607,7 @@ mod tests {
             zero: 2,
             visible_lines: 6,
             len: 6,
+            attrs: BTreeMap::new(),
         };
 
         // Shrink buffer.
@@ -538,6 +626,7 @@ mod tests {
             zero: 2,
             visible_lines: 2,
             len: 2,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -572,6 +661,7 @@ mod tests {
             zero: 2,
             visible_lines: 1,
             len: 2,
+            attrs: BTreeMap::new(),
         };
 
         // Truncate buffer.
@@ -583,6 +673,7 @@ mod tests {
             zero: 0,
             visible_lines: 1,
             len: 2,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -607,6 +698,7 @@ mod tests {
             zero: 2,
             visible_lines: 1,
             len: 2,
+            attrs: BTreeMap::new(),
         };
 
         // Truncate buffer.
@@ -618,6 +710,7 @@ mod tests {
             zero: 0,
             visible_lines: 1,
             len: 2,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.visible_lines, expected.visible_lines);
         assert_eq!(storage.inner, expected.inner);
@@ -664,6 +757,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 6,
+            attrs: BTreeMap::new(),
         };
 
         // Shrink buffer.
@@ -682,6 +776,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 3,
+            attrs: BTreeMap::new(),
         };
         assert_eq!(storage.inner, shrinking_expected.inner);
         assert_eq!(storage.zero, shrinking_expected.zero);
@@ -703,6 +798,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 4,
+            attrs: BTreeMap::new(),
         };
 
         assert_eq!(storage.inner, growing_expected.inner);
@@ -725,6 +821,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 6,
+            attrs: BTreeMap::new(),
         };
 
         // Initialize additional lines.
@@ -742,7 +839,13 @@ mod tests {
         ];
         let expected_init_size = std::cmp::max(init_size, MAX_CACHE_SIZE);
         expected_inner.append(&mut vec![filled_row('\0'); expected_init_size]);
-        let expected_storage = Storage { inner: expected_inner, zero: 0, visible_lines: 0, len: 9 };
+        let expected_storage = Storage {
+            inner: expected_inner,
+            zero: 0,
+            visible_lines: 0,
+            len: 9,
+            attrs: BTreeMap::new(),
+        };
 
         assert_eq!(storage.len, expected_storage.len);
         assert_eq!(storage.zero, expected_storage.zero);
@@ -756,6 +859,7 @@ mod tests {
             zero: 2,
             visible_lines: 0,
             len: 3,
+            attrs: BTreeMap::new(),
         };
 
         storage.rotate(2);
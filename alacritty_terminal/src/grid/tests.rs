@@ -86,6 +86,31 @@ fn scroll_down() {
     assert_eq!(grid[Line(9)].occ, 1);
 }
 
+// Scrolling within columns leaves the cells outside of them untouched.
+#[test]
+fn scroll_columns() {
+    let mut grid = Grid::<usize>::new(4, 3, 0);
+    for i in 0..4 {
+        for j in 0..3 {
+            grid[Line(i as i32)][Column(j)] = i * 10 + j;
+        }
+    }
+
+    grid.scroll_up_columns(&(Line(0)..Line(4)), &(Column(1)..Column(2)), 1);
+
+    assert_eq!(grid[Line(0)][..], [0, 11, 2]);
+    assert_eq!(grid[Line(1)][..], [10, 21, 12]);
+    assert_eq!(grid[Line(2)][..], [20, 31, 22]);
+    assert_eq!(grid[Line(3)][..], [30, 0, 32]);
+
+    grid.scroll_down_columns(&(Line(1)..Line(4)), &(Column(0)..Column(2)), 2);
+
+    assert_eq!(grid[Line(0)][..], [0, 11, 2]);
+    assert_eq!(grid[Line(1)][..], [0, 0, 12]);
+    assert_eq!(grid[Line(2)][..], [0, 0, 22]);
+    assert_eq!(grid[Line(3)][..], [10, 21, 32]);
+}
+
 #[test]
 fn scroll_down_with_history() {
     let mut grid = Grid::<usize>::new(10, 1, 1);
@@ -348,6 +373,65 @@ fn shrink_reflow_disabled() {
     assert_eq!(grid[Line(0)][Column(1)], cell('2'));
 }
 
+#[test]
+fn row_attrs_scroll() {
+    let mut grid = Grid::<usize>::new(3, 1, 2);
+    grid.row_attrs_mut(Line(1)).shell_marks.prompt = true;
+    grid.row_attrs_mut(Line(2)).line_attr = LineAttr::DoubleWidth;
+
+    // Attributes move with their rows into the history.
+    grid.scroll_up::<usize>(&(Line(0)..Line(3)), 2);
+
+    assert!(grid.row_attrs(Line(-1)).shell_marks.prompt);
+    assert_eq!(grid.row_attrs(Line(0)).line_attr, LineAttr::DoubleWidth);
+    assert_eq!(grid.row_attrs(Line(1)), RowAttrs::default());
+    assert_eq!(grid.row_attrs(Line(2)), RowAttrs::default());
+
+    // Attributes of rows in a scrolling region are swapped and reset with them.
+    grid.scroll_down::<usize>(&(Line(0)..Line(2)), 1);
+
+    assert_eq!(grid.row_attrs(Line(0)), RowAttrs::default());
+    assert_eq!(grid.row_attrs(Line(1)).line_attr, LineAttr::DoubleWidth);
+
+    // Attributes are removed with the history.
+    grid.clear_history();
+    grid.scroll_up::<usize>(&(Line(0)..Line(3)), 2);
+
+    assert_eq!(grid.row_attrs(Line(-2)), RowAttrs::default());
+    assert_eq!(grid.row_attrs(Line(-1)).line_attr, LineAttr::DoubleWidth);
+    for line in (0..3).map(Line::from) {
+        assert_eq!(grid.row_attrs(line), RowAttrs::default());
+    }
+}
+
+#[test]
+fn row_attrs_reflow() {
+    let mut grid = Grid::<Cell>::new(3, 4, 1);
+    grid[Line(0)][Column(0)] = cell('$');
+    grid[Line(0)][Column(1)] = cell(' ');
+    grid[Line(0)][Column(2)] = cell('l');
+    grid[Line(0)][Column(3)] = cell('s');
+    grid[Line(1)][Column(0)] = cell('$');
+    grid.row_attrs_mut(Line(0)).shell_marks.prompt = true;
+    grid.row_attrs_mut(Line(1)).shell_marks.prompt = true;
+    grid.row_attrs_mut(Line(1)).shell_marks.exit_code = Some(1);
+    grid.cursor.point = Point::new(Line(1), Column(1));
+
+    // Attributes stay with the first line of a wrapped row.
+    grid.resize(true, 3, 2);
+
+    assert!(grid.row_attrs(Line(-1)).shell_marks.prompt);
+    assert_eq!(grid.row_attrs(Line(0)), RowAttrs::default());
+    assert_eq!(grid.row_attrs(Line(1)).shell_marks.exit_code, Some(1));
+
+    // Attributes are restored once the row is joined again.
+    grid.resize(true, 3, 4);
+
+    assert!(grid.row_attrs(Line(0)).shell_marks.prompt);
+    assert_eq!(grid.row_attrs(Line(1)).shell_marks.exit_code, Some(1));
+    assert_eq!(grid.row_attrs(Line(2)), RowAttrs::default());
+}
+
 // https://github.com/rust-lang/rust-clippy/pull/6375
 #[allow(clippy::all)]
 fn cell(c: char) -> Cell {
@@ -8,6 +8,7 @@ pub mod ansi;
 pub mod config;
 pub mod event;
 pub mod event_loop;
+pub mod graphics;
 pub mod grid;
 pub mod index;
 pub mod selection;
@@ -15,6 +16,7 @@ pub mod sync;
 pub mod term;
 pub mod thread;
 pub mod tty;
+pub mod vi_mode;
 
 pub use crate::grid::Grid;
 pub use crate::term::Term;
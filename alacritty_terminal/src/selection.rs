@@ -289,10 +289,39 @@ impl Selection {
         match self.ty {
             SelectionType::Simple => self.range_simple(start, end, columns),
             SelectionType::Block => self.range_block(start, end),
-            _ => None,
+            SelectionType::Semantic => Some(Self::range_semantic(term, start.point, end.point)),
+            SelectionType::Lines => Some(Self::range_lines(term, start.point, end.point)),
         }
     }
 
+    fn range_semantic<T>(term: &Term<T>, mut start: Point, mut end: Point) -> SelectionRange {
+        if start == end {
+            if let Some(matching) = term.bracket_search(start) {
+                if (matching.line == start.line && matching.column < start.column)
+                    || (matching.line < start.line)
+                {
+                    start = matching;
+                } else {
+                    end = matching;
+                }
+
+                return SelectionRange { start, end, is_block: false };
+            }
+        }
+
+        let start = term.semantic_search_left(start);
+        let end = term.semantic_search_right(end);
+
+        SelectionRange { start, end, is_block: false }
+    }
+
+    fn range_lines<T>(term: &Term<T>, start: Point, end: Point) -> SelectionRange {
+        let start = term.line_search_left(start);
+        let end = term.line_search_right(end);
+
+        SelectionRange { start, end, is_block: false }
+    }
+
     fn range_simple(
         &self,
         mut start: Anchor,
@@ -487,6 +516,36 @@ mod tests {
         });
     }
 
+    #[test]
+    fn line_selection() {
+        let size = (10, 5);
+        let mut selection =
+            Selection::new(SelectionType::Lines, Point::new(Line(9), Column(1)), Side::Left);
+        selection.update(Point::new(Line(4), Column(1)), Side::Right);
+        selection = selection.rotate(&size, &(Line(0)..Line(size.0 as i32)), 4).unwrap();
+
+        assert_eq!(selection.to_range(&term(size.0, size.1)).unwrap(), SelectionRange {
+            start: Point::new(Line(0), Column(0)),
+            end: Point::new(Line(5), Column(4)),
+            is_block: false,
+        });
+    }
+
+    #[test]
+    fn semantic_selection() {
+        let size = (10, 5);
+        let mut selection =
+            Selection::new(SelectionType::Semantic, Point::new(Line(9), Column(3)), Side::Left);
+        selection.update(Point::new(Line(4), Column(1)), Side::Right);
+        selection = selection.rotate(&size, &(Line(0)..Line(size.0 as i32)), 4).unwrap();
+
+        assert_eq!(selection.to_range(&term(size.0, size.1)).unwrap(), SelectionRange {
+            start: Point::new(Line(0), Column(1)),
+            end: Point::new(Line(5), Column(3)),
+            is_block: false,
+        });
+    }
+
     #[test]
     fn simple_selection() {
         let size = (10, 5);
@@ -5,6 +5,7 @@ use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 
 use crate::ansi::{Color, NamedColor};
+use crate::graphics::GraphicCell;
 use crate::grid::{self, GridCell};
 use crate::index::Column;
 
@@ -111,6 +112,9 @@ pub struct CellExtra {
     underline_color: Option<Color>,
 
     hyperlink: Option<Hyperlink>,
+
+    #[serde(skip)]
+    graphic: Option<GraphicCell>,
 }
 
 /// Content and attributes of a single cell in the terminal grid.
@@ -164,10 +168,9 @@ impl Cell {
     pub fn set_underline_color(&mut self, color: Option<Color>) {
         // If we reset color and we don't have zerowidth we should drop extra storage.
         if color.is_none()
-            && self
-                .extra
-                .as_ref()
-                .map_or(true, |extra| extra.zerowidth.is_empty() && extra.hyperlink.is_none())
+            && self.extra.as_ref().map_or(true, |extra| {
+                extra.zerowidth.is_empty() && extra.hyperlink.is_none() && extra.graphic.is_none()
+            })
         {
             self.extra = None;
         } else {
@@ -186,7 +189,9 @@ impl Cell {
     pub fn set_hyperlink(&mut self, hyperlink: Option<Hyperlink>) {
         let should_drop = hyperlink.is_none()
             && self.extra.as_ref().map_or(true, |extra| {
-                extra.zerowidth.is_empty() && extra.underline_color.is_none()
+                extra.zerowidth.is_empty()
+                    && extra.underline_color.is_none()
+                    && extra.graphic.is_none()
             });
 
         if should_drop {
@@ -202,6 +207,29 @@ impl Cell {
     pub fn hyperlink(&self) -> Option<Hyperlink> {
         self.extra.as_ref()?.hyperlink.clone()
     }
+
+    /// Set the part of an image displayed in this cell.
+    pub fn set_graphic(&mut self, graphic: Option<GraphicCell>) {
+        let should_drop = graphic.is_none()
+            && self.extra.as_ref().map_or(true, |extra| {
+                extra.zerowidth.is_empty()
+                    && extra.underline_color.is_none()
+                    && extra.hyperlink.is_none()
+            });
+
+        if should_drop {
+            self.extra = None;
+        } else {
+            let extra = self.extra.get_or_insert(Default::default());
+            Arc::make_mut(extra).graphic = graphic;
+        }
+    }
+
+    /// Part of an image displayed in this cell.
+    #[inline]
+    pub fn graphic(&self) -> Option<&GraphicCell> {
+        self.extra.as_ref()?.graphic.as_ref()
+    }
 }
 
 impl GridCell for Cell {
@@ -218,7 +246,10 @@ impl GridCell for Cell {
                     | Flags::WIDE_CHAR_SPACER
                     | Flags::LEADING_WIDE_CHAR_SPACER,
             )
-            && self.extra.as_ref().map(|extra| extra.zerowidth.is_empty()) != Some(false)
+            && self
+                .extra
+                .as_ref()
+                .map_or(true, |extra| extra.zerowidth.is_empty() && extra.graphic.is_none())
     }
 
     #[inline]
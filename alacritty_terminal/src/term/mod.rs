@@ -1,6 +1,8 @@
 //! Exports the `Term` type which is a high-level API for the Grid.
 
-use std::ops::{Index, IndexMut, Range};
+use std::collections::HashSet;
+use std::ops::{Index, IndexMut, Range, RangeInclusive};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::{cmp, mem, ptr, slice, str};
 
@@ -9,18 +11,29 @@ use log::{debug, trace};
 use unicode_width::UnicodeWidthChar;
 
 use crate::ansi::{
-    self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, Handler, NamedColor, StandardCharset,
+    self, Attr, CharsetIndex, Color, CursorShape, CursorStyle, Handler, KeyboardModes,
+    KeyboardModesApplyBehavior, ModeState, ModifyOtherKeys, NamedColor, Notification, PromptMark,
+    StandardCharset, StatusLineType, StatusSetting, WindowOperation,
 };
 use crate::config::Config;
 use crate::event::{Event, EventListener};
-use crate::grid::{Dimensions, Grid, GridIterator, Scroll};
-use crate::index::{self, Boundary, Column, Direction, Line, Point};
+use crate::graphics::kitty::{self, Action, DeleteTarget};
+use crate::graphics::sixel::MAX_COLOR_REGISTERS;
+use crate::graphics::{
+    GraphicCell, GraphicData, GraphicPlacement, Graphics, KittyPlacementId, PixelRect, TextureRef,
+    UpdateQueues, MAX_GRAPHIC_HEIGHT, MAX_GRAPHIC_WIDTH,
+};
+use crate::grid::{Dimensions, Grid, GridIterator, LineAttr, Scroll};
+use crate::index::{self, Boundary, Column, Direction, Line, Point, Side};
 use crate::selection::{Selection, SelectionRange, SelectionType};
 use crate::term::cell::{Cell, Flags, Hyperlink, LineLength};
 use crate::term::color::{Colors, Rgb};
+use crate::vi_mode::{ViModeCursor, ViMotion};
 
 pub mod cell;
 pub mod color;
+pub mod search;
+mod terminfo;
 
 /// Minimum number of columns.
 ///
@@ -33,32 +46,53 @@ pub const MIN_SCREEN_LINES: usize = 1;
 /// Max size of the window title stack.
 const TITLE_STACK_MAX_DEPTH: usize = 4096;
 
+/// Max size of the keyboard modes stack.
+const KEYBOARD_MODE_STACK_MAX_DEPTH: usize = TITLE_STACK_MAX_DEPTH;
+
 /// Default tab interval, corresponding to terminfo `it` value.
 const INITIAL_TABSTOPS: usize = 8;
 
 bitflags! {
     pub struct TermMode: u32 {
-        const NONE                = 0;
-        const SHOW_CURSOR         = 0b0000_0000_0000_0000_0001;
-        const APP_CURSOR          = 0b0000_0000_0000_0000_0010;
-        const APP_KEYPAD          = 0b0000_0000_0000_0000_0100;
-        const MOUSE_REPORT_CLICK  = 0b0000_0000_0000_0000_1000;
-        const BRACKETED_PASTE     = 0b0000_0000_0000_0001_0000;
-        const SGR_MOUSE           = 0b0000_0000_0000_0010_0000;
-        const MOUSE_MOTION        = 0b0000_0000_0000_0100_0000;
-        const LINE_WRAP           = 0b0000_0000_0000_1000_0000;
-        const LINE_FEED_NEW_LINE  = 0b0000_0000_0001_0000_0000;
-        const ORIGIN              = 0b0000_0000_0010_0000_0000;
-        const INSERT              = 0b0000_0000_0100_0000_0000;
-        const FOCUS_IN_OUT        = 0b0000_0000_1000_0000_0000;
-        const ALT_SCREEN          = 0b0000_0001_0000_0000_0000;
-        const MOUSE_DRAG          = 0b0000_0010_0000_0000_0000;
-        const MOUSE_MODE          = 0b0000_0010_0000_0100_1000;
-        const UTF8_MOUSE          = 0b0000_0100_0000_0000_0000;
-        const ALTERNATE_SCROLL    = 0b0000_1000_0000_0000_0000;
-        const VI                  = 0b0001_0000_0000_0000_0000;
-        const URGENCY_HINTS       = 0b0010_0000_0000_0000_0000;
-        const ANY                 = u32::MAX;
+        const NONE                    = 0;
+        const SHOW_CURSOR             = 0b0000_0000_0000_0000_0000_0000_0001;
+        const APP_CURSOR              = 0b0000_0000_0000_0000_0000_0000_0010;
+        const APP_KEYPAD              = 0b0000_0000_0000_0000_0000_0000_0100;
+        const MOUSE_REPORT_CLICK      = 0b0000_0000_0000_0000_0000_0000_1000;
+        const BRACKETED_PASTE         = 0b0000_0000_0000_0000_0000_0001_0000;
+        const SGR_MOUSE               = 0b0000_0000_0000_0000_0000_0010_0000;
+        const MOUSE_MOTION            = 0b0000_0000_0000_0000_0000_0100_0000;
+        const LINE_WRAP               = 0b0000_0000_0000_0000_0000_1000_0000;
+        const LINE_FEED_NEW_LINE      = 0b0000_0000_0000_0000_0001_0000_0000;
+        const ORIGIN                  = 0b0000_0000_0000_0000_0010_0000_0000;
+        const INSERT                  = 0b0000_0000_0000_0000_0100_0000_0000;
+        const FOCUS_IN_OUT            = 0b0000_0000_0000_0000_1000_0000_0000;
+        const ALT_SCREEN              = 0b0000_0000_0000_0001_0000_0000_0000;
+        const MOUSE_DRAG              = 0b0000_0000_0000_0010_0000_0000_0000;
+        const MOUSE_MODE              = 0b0000_0000_0000_0010_0000_0100_1000;
+        const UTF8_MOUSE              = 0b0000_0000_0000_0100_0000_0000_0000;
+        const ALTERNATE_SCROLL        = 0b0000_0000_0000_1000_0000_0000_0000;
+        const VI                      = 0b0000_0000_0001_0000_0000_0000_0000;
+        const URGENCY_HINTS           = 0b0000_0000_0010_0000_0000_0000_0000;
+        const DISAMBIGUATE_ESC_CODES  = 0b0000_0000_0100_0000_0000_0000_0000;
+        const REPORT_EVENT_TYPES      = 0b0000_0000_1000_0000_0000_0000_0000;
+        const REPORT_ALTERNATE_KEYS   = 0b0000_0001_0000_0000_0000_0000_0000;
+        const REPORT_ALL_KEYS_AS_ESC  = 0b0000_0010_0000_0000_0000_0000_0000;
+        const REPORT_ASSOCIATED_TEXT  = 0b0000_0100_0000_0000_0000_0000_0000;
+        const LEFT_RIGHT_MARGIN       = 0b0000_1000_0000_0000_0000_0000_0000;
+        const REVERSE_VIDEO           = 0b0001_0000_0000_0000_0000_0000_0000;
+        const URXVT_MOUSE             = 0b0010_0000_0000_0000_0000_0000_0000;
+        const SGR_PIXELS_MOUSE        = 0b0100_0000_0000_0000_0000_0000_0000;
+        const MOUSE_ENCODING          = Self::SGR_MOUSE.bits
+                                    | Self::UTF8_MOUSE.bits
+                                    | Self::URXVT_MOUSE.bits
+                                    | Self::SGR_PIXELS_MOUSE.bits;
+        const KITTY_KEYBOARD_PROTOCOL = Self::DISAMBIGUATE_ESC_CODES.bits
+                                    | Self::REPORT_EVENT_TYPES.bits
+                                    | Self::REPORT_ALTERNATE_KEYS.bits
+                                    | Self::REPORT_ALL_KEYS_AS_ESC.bits
+                                    | Self::REPORT_ASSOCIATED_TEXT.bits;
+        const ANY                     = u32::MAX;
     }
 }
 
@@ -71,6 +105,30 @@ impl Default for TermMode {
     }
 }
 
+impl From<KeyboardModes> for TermMode {
+    fn from(value: KeyboardModes) -> Self {
+        let mut mode = Self::empty();
+        mode.set(
+            TermMode::DISAMBIGUATE_ESC_CODES,
+            value.contains(KeyboardModes::DISAMBIGUATE_ESC_CODES),
+        );
+        mode.set(TermMode::REPORT_EVENT_TYPES, value.contains(KeyboardModes::REPORT_EVENT_TYPES));
+        mode.set(
+            TermMode::REPORT_ALTERNATE_KEYS,
+            value.contains(KeyboardModes::REPORT_ALTERNATE_KEYS),
+        );
+        mode.set(
+            TermMode::REPORT_ALL_KEYS_AS_ESC,
+            value.contains(KeyboardModes::REPORT_ALL_KEYS_AS_ESC),
+        );
+        mode.set(
+            TermMode::REPORT_ASSOCIATED_TEXT,
+            value.contains(KeyboardModes::REPORT_ASSOCIATED_TEXT),
+        );
+        mode
+    }
+}
+
 /// Convert a terminal point to a viewport relative point.
 #[inline]
 pub fn point_to_viewport(display_offset: usize, point: Point) -> Option<Point<usize>> {
@@ -161,6 +219,9 @@ struct TermDamageState {
     /// Old terminal cursor point.
     last_cursor: Point,
 
+    /// Old vi cursor point.
+    last_vi_cursor_point: Option<Point<usize>>,
+
     /// Old selection range.
     last_selection: Option<SelectionRange>,
 }
@@ -174,6 +235,7 @@ impl TermDamageState {
             is_fully_damaged: true,
             lines,
             last_cursor: Default::default(),
+            last_vi_cursor_point: Default::default(),
             last_selection: Default::default(),
         }
     }
@@ -182,6 +244,7 @@ impl TermDamageState {
     fn resize(&mut self, num_cols: usize, num_lines: usize) {
         // Reset point, so old cursor won't end up outside of the viewport.
         self.last_cursor = Default::default();
+        self.last_vi_cursor_point = None;
         self.last_selection = None;
         self.is_fully_damaged = true;
 
@@ -234,6 +297,15 @@ impl TermDamageState {
     }
 }
 
+/// State of the main display, while output is routed to the host-writable status line.
+#[derive(Debug, Clone)]
+struct MainDisplayState {
+    scroll_region: Range<Line>,
+    horizontal_margins: Range<Column>,
+    selection: Option<Selection>,
+    vi_mode_cursor: ViModeCursor,
+}
+
 pub struct Term<T> {
     /// Terminal focus controlling the cursor shape.
     pub is_focused: bool,
@@ -261,11 +333,19 @@ pub struct Term<T> {
     /// Mode flags.
     mode: TermMode,
 
+    /// Cursor for keyboard selection.
+    pub vi_mode_cursor: ViModeCursor,
+
     /// Scroll region.
     ///
     /// Range going from top to bottom of the terminal, indexed from the top of the viewport.
     scroll_region: Range<Line>,
 
+    /// Horizontal margins.
+    ///
+    /// Range going from the left to the right margin, only set while DECLRMM is enabled.
+    horizontal_margins: Range<Column>,
+
     semantic_escape_chars: String,
 
     /// Modified terminal colors.
@@ -277,6 +357,9 @@ pub struct Term<T> {
     /// Default style for resetting the cursor.
     default_cursor_style: CursorStyle,
 
+    /// Style of the vi mode cursor.
+    vi_mode_cursor_style: Option<CursorStyle>,
+
     /// Proxy for sending events to the event loop.
     event_proxy: T,
 
@@ -287,6 +370,36 @@ pub struct Term<T> {
     /// term is set.
     title_stack: Vec<Option<String>>,
 
+    /// Stack of kitty keyboard protocol modes, the last entry being the active one.
+    keyboard_mode_stack: Vec<KeyboardModes>,
+
+    /// Keyboard mode stack of the currently inactive screen buffer.
+    inactive_keyboard_mode_stack: Vec<KeyboardModes>,
+
+    /// Encoding of modified keys requested through xterm's modifyOtherKeys.
+    modify_other_keys: ModifyOtherKeys,
+
+    /// Images displayed in the grid.
+    graphics: Graphics,
+
+    /// Working directory reported by the shell.
+    working_directory: Option<PathBuf>,
+
+    /// Host-writable status line below the main display.
+    status_line: Grid<Cell>,
+
+    /// Type of status line selected by the application.
+    status_line_type: StatusLineType,
+
+    /// Whether output is written to the host-writable status line.
+    status_line_active: bool,
+
+    /// Main display state, while the status line takes its place.
+    main_display: Option<MainDisplayState>,
+
+    /// Information about damaged cells on the host-writable status line.
+    status_line_damage: TermDamageState,
+
     /// Information about damaged cells.
     damage: TermDamageState,
 }
@@ -301,6 +414,13 @@ impl<T> Term<T> {
         self.grid.scroll_display(scroll);
         self.event_proxy.send_event(Event::MouseCursorDirty);
 
+        // Clamp vi mode cursor to the viewport.
+        let viewport_start = -(self.grid.display_offset() as i32);
+        let viewport_end = viewport_start + self.bottommost_line().0;
+        let vi_cursor_line = &mut self.vi_mode_cursor.point.line.0;
+        *vi_cursor_line = cmp::min(viewport_end, cmp::max(viewport_start, *vi_cursor_line));
+        self.vi_mode_recompute_selection();
+
         // Damage everything if display offset changed.
         if old_display_offset != self.grid().display_offset() {
             self.mark_fully_damaged();
@@ -328,15 +448,28 @@ impl<T> Term<T> {
             active_charset: Default::default(),
             tabs,
             mode: Default::default(),
+            vi_mode_cursor: Default::default(),
             scroll_region,
+            horizontal_margins: Column(0)..Column(num_cols),
             colors: color::Colors::default(),
             semantic_escape_chars: config.selection.semantic_escape_chars.to_owned(),
             cursor_style: None,
             default_cursor_style: config.cursor.style(),
+            vi_mode_cursor_style: config.cursor.vi_mode_style(),
             event_proxy,
             is_focused: true,
             title: None,
             title_stack: Vec::new(),
+            keyboard_mode_stack: Vec::new(),
+            inactive_keyboard_mode_stack: Vec::new(),
+            modify_other_keys: Default::default(),
+            graphics: Default::default(),
+            working_directory: None,
+            status_line: Grid::new(1, num_cols, 0),
+            status_line_type: StatusLineType::None,
+            status_line_active: false,
+            main_display: None,
+            status_line_damage: TermDamageState::new(num_cols, 1),
             selection: None,
             damage,
         }
@@ -353,9 +486,16 @@ impl<T> Term<T> {
         // Update tracking of cursor, selection, and vi mode cursor.
 
         let display_offset = self.grid().display_offset();
+        let vi_cursor_point = if self.mode.contains(TermMode::VI) {
+            point_to_viewport(display_offset, self.vi_mode_cursor.point)
+        } else {
+            None
+        };
 
         let previous_cursor = mem::replace(&mut self.damage.last_cursor, self.grid.cursor.point);
         let previous_selection = mem::replace(&mut self.damage.last_selection, selection);
+        let previous_vi_cursor_point =
+            mem::replace(&mut self.damage.last_vi_cursor_point, vi_cursor_point);
 
         // Early return if the entire terminal is damaged.
         if self.damage.is_fully_damaged {
@@ -373,6 +513,17 @@ impl<T> Term<T> {
         // Always damage current cursor.
         self.damage_cursor();
 
+        // Vi mode doesn't update the terminal content, thus only last vi cursor position and the
+        // new one should be damaged.
+        if let Some(previous_vi_cursor_point) = previous_vi_cursor_point {
+            self.damage.damage_point(previous_vi_cursor_point);
+        }
+
+        // Damage vi cursor if it's present.
+        if let Some(vi_cursor_point) = self.damage.last_vi_cursor_point {
+            self.damage.damage_point(vi_cursor_point);
+        }
+
         if self.damage.last_selection != previous_selection {
             for selection in self.damage.last_selection.into_iter().chain(previous_selection) {
                 self.damage.damage_selection(selection, display_offset, self.columns());
@@ -385,6 +536,7 @@ impl<T> Term<T> {
     /// Resets the terminal damage information.
     pub fn reset_damage(&mut self) {
         self.damage.reset(self.columns());
+        self.status_line_damage.reset(self.columns());
     }
 
     #[inline]
@@ -404,6 +556,7 @@ impl<T> Term<T> {
     {
         self.semantic_escape_chars = config.selection.semantic_escape_chars.to_owned();
         self.default_cursor_style = config.cursor.style();
+        self.vi_mode_cursor_style = config.cursor.vi_mode_style();
 
         let title_event = match &self.title {
             Some(title) => Event::Title(title.clone()),
@@ -548,6 +701,66 @@ impl<T> Term<T> {
         &mut self.grid
     }
 
+    /// Reset the terminal state for a new child process.
+    ///
+    /// With `keep_history`, the content of the primary screen is moved into the scrollback history
+    /// instead of being discarded.
+    pub fn reset_for_new_child(&mut self, keep_history: bool)
+    where
+        T: EventListener,
+    {
+        if keep_history {
+            // Leave the alternate screen, since its content is never part of the history.
+            if self.mode.contains(TermMode::ALT_SCREEN) {
+                mem::swap(&mut self.grid, &mut self.inactive_grid);
+                self.mode.remove(TermMode::ALT_SCREEN);
+            }
+
+            // Push every line up to the cursor into the history.
+            let region = Line(0)..Line(self.screen_lines() as i32);
+            let lines = self.grid.cursor.point.line.0 as usize + 1;
+            self.grid.scroll_up(&region, lines);
+        }
+
+        self.reset(keep_history);
+    }
+
+    /// Reset the terminal state, optionally keeping the scrollback history.
+    fn reset(&mut self, keep_history: bool)
+    where
+        T: EventListener,
+    {
+        if self.mode.contains(TermMode::ALT_SCREEN) {
+            mem::swap(&mut self.grid, &mut self.inactive_grid);
+        }
+        self.active_charset = Default::default();
+        self.cursor_style = None;
+        if keep_history {
+            self.grid.reset_screen();
+        } else {
+            self.grid.reset();
+        }
+        self.inactive_grid.reset();
+        self.scroll_region = Line(0)..Line(self.screen_lines() as i32);
+        self.horizontal_margins = Column(0)..Column(self.columns());
+        self.tabs = TabStops::new(self.columns());
+        self.title_stack = Vec::new();
+        self.title = None;
+        self.selection = None;
+        self.keyboard_mode_stack = Vec::new();
+        self.inactive_keyboard_mode_stack = Vec::new();
+        self.modify_other_keys = ModifyOtherKeys::Reset;
+        self.graphics.kitty_images.clear();
+        self.set_status_line_type(StatusLineType::None);
+
+        // Preserve vi mode across resets.
+        self.mode &= TermMode::VI;
+        self.mode.insert(TermMode::default());
+
+        self.event_proxy.send_event(Event::CursorBlinkingChange);
+        self.mark_fully_damaged();
+    }
+
     /// Resize terminal to new dimensions.
     pub fn resize<S: Dimensions>(&mut self, size: S) {
         let old_cols = self.columns();
@@ -569,6 +782,8 @@ impl<T> Term<T> {
         let min_delta = cmp::min(0, num_lines as i32 - self.grid.cursor.point.line.0 - 1);
         delta = cmp::min(cmp::max(delta, min_delta), history_size as i32);
 
+        self.vi_mode_cursor.point.line += delta;
+
         let is_alt = self.mode.contains(TermMode::ALT_SCREEN);
         self.grid.resize(!is_alt, num_lines, num_cols);
         self.inactive_grid.resize(is_alt, num_lines, num_cols);
@@ -589,8 +804,20 @@ impl<T> Term<T> {
         self.grid.resize(!is_alt, num_lines, num_cols);
         self.inactive_grid.resize(is_alt, num_lines, num_cols);
 
-        // Reset scrolling region.
+        // Clamp vi cursor to viewport.
+        let vi_point = self.vi_mode_cursor.point;
+        let viewport_top = Line(-(self.grid.display_offset() as i32));
+        let viewport_bottom = viewport_top + self.bottommost_line();
+        self.vi_mode_cursor.point.line =
+            cmp::max(cmp::min(vi_point.line, viewport_bottom), viewport_top);
+        self.vi_mode_cursor.point.column = cmp::min(vi_point.column, self.last_column());
+
+        self.status_line.resize(false, 1, num_cols);
+        self.status_line_damage.resize(num_cols, 1);
+
+        // Reset scrolling region and margins.
         self.scroll_region = Line(0)..Line(self.screen_lines() as i32);
+        self.horizontal_margins = Column(0)..Column(num_cols);
 
         // Resize damage information.
         self.damage.resize(num_cols, num_lines);
@@ -602,6 +829,210 @@ impl<T> Term<T> {
         &self.mode
     }
 
+    /// Active xterm modifyOtherKeys encoding.
+    #[inline]
+    pub fn modify_other_keys(&self) -> ModifyOtherKeys {
+        self.modify_other_keys
+    }
+
+    /// Update the size of a cell in pixels, which is used to place images in the grid.
+    #[inline]
+    pub fn set_cell_size(&mut self, cell_width: usize, cell_height: usize) {
+        self.graphics.cell_width = cmp::max(cell_width, 1);
+        self.graphics.cell_height = cmp::max(cell_height, 1);
+    }
+
+    /// Take the images and textures the renderer needs to update.
+    #[inline]
+    pub fn graphics_take_queues(&mut self) -> Option<UpdateQueues> {
+        self.graphics.take_queues()
+    }
+
+    /// Find the closest line with a prompt in `direction`, excluding `origin`.
+    pub fn prompt_line(&self, origin: Line, direction: Direction) -> Option<Line> {
+        let has_prompt = |line: &Line| self.grid.row_attrs(*line).shell_marks.prompt;
+        match direction {
+            Direction::Left => {
+                (self.topmost_line().0..origin.0).rev().map(Line).find(has_prompt)
+            },
+            Direction::Right => {
+                (origin.0 + 1..=self.bottommost_line().0).map(Line).find(has_prompt)
+            },
+        }
+    }
+
+    /// Lines of the command output containing `line`.
+    ///
+    /// The output starts at the line marked by the shell and ends before the next prompt or the
+    /// end of the command. Output of running commands ends at the cursor.
+    pub fn command_output(&self, line: Line) -> Option<RangeInclusive<Line>> {
+        // Find the start of the output.
+        let mut start = line;
+        loop {
+            let marks = self.grid.row_attrs(start).shell_marks;
+            if marks.output {
+                break;
+            } else if marks.prompt || marks.command_end || start <= self.topmost_line() {
+                return None;
+            }
+            start -= 1;
+        }
+
+        // Find the end of the output.
+        let last_line = cmp::min(self.grid.cursor.point.line, self.bottommost_line());
+        let mut end = start;
+        while end < last_line {
+            let marks = self.grid.row_attrs(end + 1i32).shell_marks;
+            if marks.prompt || marks.output || marks.command_end {
+                break;
+            }
+            end += 1;
+        }
+
+        (line <= end).then(|| start..=end)
+    }
+
+    /// Lines of the output of the last command.
+    pub fn last_command_output(&self) -> Option<RangeInclusive<Line>> {
+        let bottommost_line = self.bottommost_line();
+        let start = (self.topmost_line().0..=bottommost_line.0)
+            .rev()
+            .map(Line)
+            .find(|line| self.grid.row_attrs(*line).shell_marks.output)?;
+        self.command_output(start)
+    }
+
+    /// Working directory of the shell, if it was reported using OSC 7.
+    #[inline]
+    pub fn working_directory(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    /// Type of status line requested by the application.
+    #[inline]
+    pub fn status_line_type(&self) -> StatusLineType {
+        self.status_line_type
+    }
+
+    /// Content of the host-writable status line.
+    #[inline]
+    pub fn status_line(&self) -> &Grid<Cell> {
+        &self.status_line
+    }
+
+    /// Check if the host-writable status line changed since the last damage reset.
+    #[inline]
+    pub fn status_line_damaged(&self) -> bool {
+        self.status_line_damage.is_fully_damaged || self.status_line_damage.lines[0].is_damaged()
+    }
+
+    /// Route output to the host-writable status line, if it is active.
+    ///
+    /// The status line takes the place of the active grid and its damage, so all handlers write
+    /// to it. This must be undone with [`Term::suspend_status_line`] before the terminal is
+    /// accessed by anything other than the parser.
+    pub fn resume_status_line(&mut self) {
+        if !self.status_line_active || self.main_display.is_some() {
+            return;
+        }
+
+        mem::swap(&mut self.grid, &mut self.status_line);
+        mem::swap(&mut self.damage, &mut self.status_line_damage);
+
+        let horizontal_margins = Column(0)..Column(self.columns());
+        self.main_display = Some(MainDisplayState {
+            scroll_region: mem::replace(&mut self.scroll_region, Line(0)..Line(1)),
+            horizontal_margins: mem::replace(&mut self.horizontal_margins, horizontal_margins),
+            selection: self.selection.take(),
+            vi_mode_cursor: self.vi_mode_cursor,
+        });
+    }
+
+    /// Put the main display back in place of the host-writable status line.
+    pub fn suspend_status_line(&mut self) {
+        let main_display = match self.main_display.take() {
+            Some(main_display) => main_display,
+            None => return,
+        };
+
+        self.scroll_region = main_display.scroll_region;
+        self.horizontal_margins = main_display.horizontal_margins;
+        self.selection = main_display.selection;
+        self.vi_mode_cursor = main_display.vi_mode_cursor;
+
+        mem::swap(&mut self.damage, &mut self.status_line_damage);
+        mem::swap(&mut self.grid, &mut self.status_line);
+    }
+
+    /// Check if output is currently written to the host-writable status line.
+    #[inline]
+    fn status_line_routed(&self) -> bool {
+        self.main_display.is_some()
+    }
+
+    /// Run a handler function on the main display, even while output is routed to the status line.
+    fn on_main_display<F: FnOnce(&mut Self)>(&mut self, f: F) {
+        self.suspend_status_line();
+        f(self);
+        self.resume_status_line();
+    }
+
+    /// Check if text is wrapped at the end of the line.
+    ///
+    /// Text on the status line is never wrapped.
+    #[inline]
+    fn line_wrap(&self) -> bool {
+        self.mode.contains(TermMode::LINE_WRAP) && !self.status_line_routed()
+    }
+
+    /// Current setting of a terminal mode, as reported by DECRPM.
+    fn mode_state(&self, mode: &ansi::Mode) -> ModeState {
+        let flag = match mode {
+            // Switching to 132 columns is not supported.
+            ansi::Mode::ColumnMode => return ModeState::PermanentlyReset,
+            ansi::Mode::BlinkingCursor => {
+                let blinking = self.cursor_style.unwrap_or(self.default_cursor_style).blinking;
+                return blinking.into();
+            },
+            // Synchronized updates are buffered and reported by the parser.
+            ansi::Mode::SyncUpdate => return ModeState::Reset,
+            // The saved cursor is not a persistent setting.
+            ansi::Mode::SaveRestoreCursor => return ModeState::Reset,
+            ansi::Mode::HighlightMouseTracking => return ModeState::PermanentlyReset,
+            ansi::Mode::CursorKeys => TermMode::APP_CURSOR,
+            ansi::Mode::Insert => TermMode::INSERT,
+            ansi::Mode::Origin => TermMode::ORIGIN,
+            ansi::Mode::LineWrap => TermMode::LINE_WRAP,
+            ansi::Mode::LineFeedNewLine => TermMode::LINE_FEED_NEW_LINE,
+            ansi::Mode::ShowCursor => TermMode::SHOW_CURSOR,
+            ansi::Mode::LeftRightMargin => TermMode::LEFT_RIGHT_MARGIN,
+            ansi::Mode::ReportMouseClicks => TermMode::MOUSE_REPORT_CLICK,
+            ansi::Mode::ReportCellMouseMotion => TermMode::MOUSE_DRAG,
+            ansi::Mode::ReportAllMouseMotion => TermMode::MOUSE_MOTION,
+            ansi::Mode::ReportFocusInOut => TermMode::FOCUS_IN_OUT,
+            ansi::Mode::Utf8Mouse => TermMode::UTF8_MOUSE,
+            ansi::Mode::SgrMouse => TermMode::SGR_MOUSE,
+            ansi::Mode::UrxvtMouse => TermMode::URXVT_MOUSE,
+            ansi::Mode::SgrPixelsMouse => TermMode::SGR_PIXELS_MOUSE,
+            ansi::Mode::AlternateScroll => TermMode::ALTERNATE_SCROLL,
+            ansi::Mode::UrgencyHints => TermMode::URGENCY_HINTS,
+            ansi::Mode::ReverseVideo => TermMode::REVERSE_VIDEO,
+            ansi::Mode::SwapScreen
+            | ansi::Mode::SwapScreenAndClear
+            | ansi::Mode::SwapScreenAndSetRestoreCursor => TermMode::ALT_SCREEN,
+            ansi::Mode::BracketedPaste => TermMode::BRACKETED_PASTE,
+        };
+
+        self.mode.contains(flag).into()
+    }
+
+    /// Sync the keyboard mode flags with the top of the keyboard mode stack.
+    fn update_keyboard_mode(&mut self) {
+        let mode = self.keyboard_mode_stack.last().copied().unwrap_or(KeyboardModes::NO_MODE);
+        self.mode &= !TermMode::KITTY_KEYBOARD_PROTOCOL;
+        self.mode.insert(mode.into());
+    }
+
     /// Swap primary and alternate screen buffer.
     pub fn swap_alt(&mut self) {
         if !self.mode.contains(TermMode::ALT_SCREEN) {
@@ -615,6 +1046,24 @@ impl<T> Term<T> {
             self.inactive_grid.reset_region(..);
         }
 
+        self.swap_screen_buffers();
+    }
+
+    /// Swap primary and alternate screen buffer, sharing the cursor between them.
+    ///
+    /// This is used by the legacy alternate screen modes, which neither save the cursor nor clear
+    /// the alternate screen when entering it.
+    fn swap_alt_keep_cursor(&mut self) {
+        self.inactive_grid.cursor = self.grid.cursor.clone();
+        self.swap_screen_buffers();
+    }
+
+    /// Switch the active screen buffer.
+    fn swap_screen_buffers(&mut self) {
+        // Each screen buffer has its own keyboard mode stack.
+        mem::swap(&mut self.keyboard_mode_stack, &mut self.inactive_keyboard_mode_stack);
+        self.update_keyboard_mode();
+
         mem::swap(&mut self.grid, &mut self.inactive_grid);
         self.mode ^= TermMode::ALT_SCREEN;
         self.selection = None;
@@ -634,10 +1083,24 @@ impl<T> Term<T> {
 
         let region = origin..self.scroll_region.end;
 
+        // Only scroll the cells between the margins.
+        if self.has_horizontal_margins() {
+            self.selection = None;
+            self.grid.scroll_down_columns(&region, &self.horizontal_margins, lines);
+            self.mark_fully_damaged();
+            return;
+        }
+
         // Scroll selection.
         self.selection =
             self.selection.take().and_then(|s| s.rotate(self, &region, -(lines as i32)));
 
+        // Scroll vi mode cursor.
+        let line = &mut self.vi_mode_cursor.point.line;
+        if region.start <= *line && region.end > *line {
+            *line = cmp::min(*line + lines, region.end - 1);
+        }
+
         // Scroll between origin and bottom
         self.grid.scroll_down(&region, lines);
         self.mark_fully_damaged();
@@ -655,11 +1118,27 @@ impl<T> Term<T> {
 
         let region = origin..self.scroll_region.end;
 
+        // Only scroll the cells between the margins, without touching the history.
+        if self.has_horizontal_margins() {
+            self.selection = None;
+            self.grid.scroll_up_columns(&region, &self.horizontal_margins, lines);
+            self.mark_fully_damaged();
+            return;
+        }
+
         // Scroll selection.
         self.selection = self.selection.take().and_then(|s| s.rotate(self, &region, lines as i32));
 
         self.grid.scroll_up(&region, lines);
 
+        // Scroll vi mode cursor.
+        let viewport_top = Line(-(self.grid.display_offset() as i32));
+        let top = if region.start == 0 { viewport_top } else { region.start };
+        let line = &mut self.vi_mode_cursor.point.line;
+        if (top <= *line) && region.end > *line {
+            *line = cmp::max(*line - lines, top);
+        }
+
         self.mark_fully_damaged();
     }
 
@@ -668,8 +1147,9 @@ impl<T> Term<T> {
         T: EventListener,
     {
         // Setting 132 column font makes no sense, but run the other side effects.
-        // Clear scrolling region.
+        // Clear scrolling region and margins.
         self.set_scrolling_region(1, None);
+        self.horizontal_margins = Column(0)..Column(self.columns());
 
         // Clear grid.
         self.grid.reset_region(..);
@@ -684,6 +1164,76 @@ impl<T> Term<T> {
         self.event_proxy.send_event(Event::Exit);
     }
 
+    /// Toggle the vi mode.
+    #[inline]
+    pub fn toggle_vi_mode(&mut self)
+    where
+        T: EventListener,
+    {
+        self.mode ^= TermMode::VI;
+
+        if self.mode.contains(TermMode::VI) {
+            let display_offset = self.grid.display_offset() as i32;
+            if self.grid.cursor.point.line > self.bottommost_line() - display_offset {
+                // Move cursor to top-left if terminal cursor is not visible.
+                let point = Point::new(Line(-display_offset), Column(0));
+                self.vi_mode_cursor = ViModeCursor::new(point);
+            } else {
+                // Reset vi mode cursor position to match primary cursor.
+                self.vi_mode_cursor = ViModeCursor::new(self.grid.cursor.point);
+            }
+        }
+
+        // Update UI about cursor blinking state changes.
+        self.event_proxy.send_event(Event::CursorBlinkingChange);
+    }
+
+    /// Move vi mode cursor.
+    #[inline]
+    pub fn vi_motion(&mut self, motion: ViMotion)
+    where
+        T: EventListener,
+    {
+        // Require vi mode to be active.
+        if !self.mode.contains(TermMode::VI) {
+            return;
+        }
+
+        // Move cursor.
+        self.vi_mode_cursor = self.vi_mode_cursor.motion(self, motion);
+        self.vi_mode_recompute_selection();
+    }
+
+    /// Move vi cursor to a point in the grid.
+    #[inline]
+    pub fn vi_goto_point(&mut self, point: Point)
+    where
+        T: EventListener,
+    {
+        // Move viewport to make point visible.
+        self.scroll_to_point(point);
+
+        // Move vi cursor to the point.
+        self.vi_mode_cursor.point = point;
+
+        self.vi_mode_recompute_selection();
+    }
+
+    /// Update the active selection to match the vi mode cursor position.
+    #[inline]
+    fn vi_mode_recompute_selection(&mut self) {
+        // Require vi mode to be active.
+        if !self.mode.contains(TermMode::VI) {
+            return;
+        }
+
+        // Update only if non-empty selection is present.
+        if let Some(selection) = self.selection.as_mut().filter(|s| !s.is_empty()) {
+            selection.update(self.vi_mode_cursor.point, Side::Left);
+            selection.include_all();
+        }
+    }
+
     /// Scroll display to point if it is outside of viewport.
     pub fn scroll_to_point(&mut self, point: Point)
     where
@@ -739,20 +1289,109 @@ impl<T> Term<T> {
     /// While vi mode is active, this will automatically return the vi mode cursor style.
     #[inline]
     pub fn cursor_style(&self) -> CursorStyle {
-        self.cursor_style.unwrap_or(self.default_cursor_style)
+        let cursor_style = self.cursor_style.unwrap_or(self.default_cursor_style);
+
+        if self.mode.contains(TermMode::VI) {
+            self.vi_mode_cursor_style.unwrap_or(cursor_style)
+        } else {
+            cursor_style
+        }
     }
 
     pub fn colors(&self) -> &Colors {
         &self.colors
     }
 
+    /// SGR parameters of the current character attributes.
+    fn sgr_parameters(&self) -> String {
+        let template = &self.grid.cursor.template;
+        let mut parameters = vec![String::from("0")];
+
+        let flags = [
+            (Flags::BOLD, "1"),
+            (Flags::DIM, "2"),
+            (Flags::ITALIC, "3"),
+            (Flags::UNDERLINE, "4"),
+            (Flags::DOUBLE_UNDERLINE, "4:2"),
+            (Flags::UNDERCURL, "4:3"),
+            (Flags::DOTTED_UNDERLINE, "4:4"),
+            (Flags::DASHED_UNDERLINE, "4:5"),
+            (Flags::INVERSE, "7"),
+            (Flags::HIDDEN, "8"),
+            (Flags::STRIKEOUT, "9"),
+        ];
+        for (flag, parameter) in flags {
+            if template.flags.contains(flag) {
+                parameters.push(parameter.into());
+            }
+        }
+
+        parameters.extend(sgr_color(template.fg, 30));
+        parameters.extend(sgr_color(template.bg, 40));
+        parameters.extend(template.underline_color().and_then(|color| sgr_color(color, 50)));
+
+        parameters.join(";")
+    }
+
+    /// Check if left or right margins are restricting the scrolling region.
+    #[inline]
+    fn has_horizontal_margins(&self) -> bool {
+        self.horizontal_margins != (Column(0)..Column(self.columns()))
+    }
+
+    /// Check if the cursor is between the left and right margins.
+    #[inline]
+    fn cursor_in_margins(&self) -> bool {
+        self.horizontal_margins.contains(&self.grid.cursor.point.column)
+    }
+
+    /// Exclusive end of the cursor line, stopping at the right margin unless already beyond it.
+    #[inline]
+    fn line_end(&self) -> usize {
+        let line_columns = self.line_columns(self.grid.cursor.point.line);
+        if self.grid.cursor.point.column < self.horizontal_margins.end {
+            cmp::min(self.horizontal_margins.end.0, line_columns)
+        } else {
+            line_columns
+        }
+    }
+
+    /// Number of columns available in a line, which is halved for double-width lines.
+    #[inline]
+    fn line_columns(&self, line: Line) -> usize {
+        match self.grid.row_attrs(line).line_attr {
+            LineAttr::Single => self.columns(),
+            _ => cmp::max(self.columns() / 2, 1),
+        }
+    }
+
+    /// Move the cursor back into the available columns of its line.
+    #[inline]
+    fn clamp_cursor_column(&mut self) {
+        let last_column = Column(self.line_columns(self.grid.cursor.point.line) - 1);
+        if self.grid.cursor.point.column > last_column {
+            self.grid.cursor.point.column = last_column;
+            self.grid.cursor.input_needs_wrap = false;
+        }
+    }
+
+    /// Start of the cursor line, stopping at the left margin unless already before it.
+    #[inline]
+    fn line_start(&self) -> Column {
+        if self.grid.cursor.point.column >= self.horizontal_margins.start {
+            self.horizontal_margins.start
+        } else {
+            Column(0)
+        }
+    }
+
     /// Insert a linebreak at the current cursor position.
     #[inline]
     fn wrapline(&mut self)
     where
         T: EventListener,
     {
-        if !self.mode.contains(TermMode::LINE_WRAP) {
+        if !self.line_wrap() {
             return;
         }
 
@@ -760,6 +1399,13 @@ impl<T> Term<T> {
 
         self.grid.cursor_cell().flags.insert(Flags::WRAPLINE);
 
+        // Wrap to the left margin when wrapping at the right margin.
+        let column = if self.grid.cursor.point.column < self.horizontal_margins.end {
+            self.horizontal_margins.start
+        } else {
+            Column(0)
+        };
+
         if self.grid.cursor.point.line + 1 >= self.scroll_region.end {
             self.linefeed();
         } else {
@@ -767,8 +1413,9 @@ impl<T> Term<T> {
             self.grid.cursor.point.line += 1;
         }
 
-        self.grid.cursor.point.column = Column(0);
+        self.grid.cursor.point.column = column;
         self.grid.cursor.input_needs_wrap = false;
+        self.clamp_cursor_column();
         self.damage_cursor();
     }
 
@@ -803,11 +1450,349 @@ impl<T> Term<T> {
             cursor_cell = self.grid.cursor_cell();
         }
 
+        // Images of the kitty graphics protocol are not replaced by text.
+        let graphic =
+            cursor_cell.graphic().filter(|graphic| graphic.placement.kitty_id.is_some()).cloned();
+
         cursor_cell.c = c;
         cursor_cell.fg = fg;
         cursor_cell.bg = bg;
         cursor_cell.flags = flags;
         cursor_cell.extra = extra;
+
+        if graphic.is_some() {
+            cursor_cell.set_graphic(graphic);
+        }
+    }
+
+    /// Store an image placement in the cells starting at the cursor position.
+    ///
+    /// If `scroll` is `true`, the terminal scrolls to fit the image and the cursor is left on its
+    /// last line. Otherwise the image is cut off at the bottom of the screen.
+    fn place_graphic(
+        &mut self,
+        placement: Arc<GraphicPlacement>,
+        columns: usize,
+        lines: usize,
+        scroll: bool,
+    ) where
+        T: EventListener,
+    {
+        trace!("Placing {}x{} image at {:?}", columns, lines, self.grid.cursor.point);
+
+        // Images are cut off at the right edge of the terminal.
+        let start = self.grid.cursor.point;
+        let columns = cmp::min(columns, self.columns() - start.column.0);
+
+        // Sixel images replace the text below them.
+        let replace_text = placement.kitty_id.is_none();
+
+        let bg = self.grid.cursor.template.bg;
+        for line in 0..lines {
+            let point_line = if scroll {
+                if line > 0 {
+                    self.linefeed();
+                }
+                self.grid.cursor.point.line
+            } else if start.line + line <= self.bottommost_line() {
+                start.line + line
+            } else {
+                break;
+            };
+
+            let row = &mut self.grid[point_line];
+            for column in 0..columns {
+                let cell = &mut row[start.column + column];
+                if replace_text {
+                    *cell = Cell::from(bg);
+                }
+                cell.set_graphic(Some(GraphicCell { placement: placement.clone(), column, line }));
+            }
+        }
+
+        self.selection = None;
+        self.mark_fully_damaged();
+    }
+
+    /// Handle the transmission of a kitty graphics image, returning its ID.
+    fn kitty_transmit(&mut self, command: &mut kitty::Command) -> Result<u32, kitty::Error>
+    where
+        T: EventListener,
+    {
+        if command.image_id != 0 && command.image_number != 0 {
+            return Err(kitty::Error::Invalid("Image ID and number are mutually exclusive"));
+        }
+
+        let graphic = command.load()?;
+        let texture = self.graphics.insert(graphic);
+
+        // Images without ID or number can only be displayed once.
+        let image_id = match (command.image_id, command.image_number) {
+            (0, 0) => 0,
+            (0, _) => self.graphics.kitty_images.next_id(),
+            (id, _) => id,
+        };
+
+        if image_id != 0 {
+            self.graphics.kitty_images.insert(kitty::Image {
+                id: image_id,
+                number: command.image_number,
+                texture: texture.clone(),
+            });
+        }
+
+        if command.action == Action::TransmitAndDisplay {
+            self.kitty_place(command, image_id, texture)?;
+        }
+
+        Ok(image_id)
+    }
+
+    /// Display a previously transmitted kitty graphics image, returning its ID.
+    fn kitty_display(&mut self, command: &kitty::Command) -> Result<u32, kitty::Error>
+    where
+        T: EventListener,
+    {
+        let image = match (command.image_id, command.image_number) {
+            (0, 0) => return Err(kitty::Error::Invalid("Missing image ID")),
+            (0, number) => self.graphics.kitty_images.get_by_number(number),
+            (id, _) => self.graphics.kitty_images.get(id),
+        };
+
+        let (image_id, texture) = match image {
+            Some(image) => (image.id, image.texture.clone()),
+            None => return Err(kitty::Error::NotFound),
+        };
+
+        self.kitty_place(command, image_id, texture)?;
+
+        Ok(image_id)
+    }
+
+    /// Place a kitty graphics image at the cursor position.
+    fn kitty_place(
+        &mut self,
+        command: &kitty::Command,
+        image_id: u32,
+        texture: Arc<TextureRef>,
+    ) -> Result<(), kitty::Error>
+    where
+        T: EventListener,
+    {
+        // Clamp the displayed area to the image.
+        let x = cmp::min(command.x as usize, texture.width);
+        let y = cmp::min(command.y as usize, texture.height);
+        let source_width = match command.width as usize {
+            0 => texture.width - x,
+            width => cmp::min(width, texture.width - x),
+        };
+        let source_height = match command.height as usize {
+            0 => texture.height - y,
+            height => cmp::min(height, texture.height - y),
+        };
+        if source_width == 0 || source_height == 0 {
+            return Err(kitty::Error::Invalid("Displayed image area is empty"));
+        }
+        let source = PixelRect { x, y, width: source_width, height: source_height };
+
+        // Scale the image to the requested number of cells, keeping the aspect ratio if only one
+        // of the dimensions was specified.
+        let cell_width = self.graphics.cell_width;
+        let cell_height = self.graphics.cell_height;
+        let (width, height) = match (command.columns as usize, command.rows as usize) {
+            (0, 0) => (source_width, source_height),
+            (columns, 0) => {
+                let width = columns * cell_width;
+                (width, cmp::max(source_height * width / source_width, 1))
+            },
+            (0, rows) => {
+                let height = rows * cell_height;
+                (cmp::max(source_width * height / source_height, 1), height)
+            },
+            (columns, rows) => (columns * cell_width, rows * cell_height),
+        };
+        if width > MAX_GRAPHIC_WIDTH * 4 || height > MAX_GRAPHIC_HEIGHT * 4 {
+            return Err(kitty::Error::TooLarge);
+        }
+
+        let destination = PixelRect {
+            x: cmp::min(command.cell_x_offset as usize, cell_width - 1),
+            y: cmp::min(command.cell_y_offset as usize, cell_height - 1),
+            width,
+            height,
+        };
+        let columns = (destination.x + width + cell_width - 1) / cell_width;
+        let lines = (destination.y + height + cell_height - 1) / cell_height;
+
+        // Placements with an ID replace the previous placement with the same ID.
+        let kitty_id = KittyPlacementId { image_id, placement_id: command.placement_id };
+        if command.placement_id != 0 {
+            self.kitty_delete_placements(|placement| placement.kitty_id == Some(kitty_id));
+        }
+
+        let placement = Arc::new(GraphicPlacement {
+            texture,
+            source,
+            destination,
+            cell_width,
+            cell_height,
+            z_index: command.z_index,
+            kitty_id: Some(kitty_id),
+        });
+        self.graphics.track_kitty_placement(&placement);
+
+        let start = self.grid.cursor.point;
+        self.place_graphic(placement, columns, lines, command.move_cursor);
+
+        // Move the cursor to the cell after the bottom right corner of the image.
+        if command.move_cursor {
+            let column = start.column + columns;
+            if column > self.last_column() {
+                self.grid.cursor.point.column = self.last_column();
+                self.grid.cursor.input_needs_wrap = true;
+            } else {
+                self.grid.cursor.point.column = column;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a kitty graphics delete command.
+    fn kitty_delete(&mut self, command: &kitty::Command) {
+        let cursor = self.grid.cursor.point;
+        let column = Column(command.x.saturating_sub(1) as usize);
+        let line = Line(command.y.saturating_sub(1) as i32);
+        let z_index = command.z_index;
+
+        let mut image_ids = match command.delete.target {
+            DeleteTarget::All => self.kitty_delete_visible_placements(|_, _| true),
+            DeleteTarget::ImageId | DeleteTarget::ImageNumber => {
+                let image_id = if command.delete.target == DeleteTarget::ImageId {
+                    command.image_id
+                } else {
+                    match self.graphics.kitty_images.get_by_number(command.image_number) {
+                        Some(image) => image.id,
+                        None => return,
+                    }
+                };
+
+                let placement_id = command.placement_id;
+                self.kitty_delete_placements(|placement| {
+                    placement.kitty_id.map_or(false, |id| {
+                        id.image_id == image_id
+                            && (placement_id == 0 || id.placement_id == placement_id)
+                    })
+                });
+
+                vec![image_id]
+            },
+            DeleteTarget::Cursor => {
+                self.kitty_delete_visible_placements(|point, _| point == cursor)
+            },
+            DeleteTarget::Cell => {
+                self.kitty_delete_visible_placements(|point, _| point == Point::new(line, column))
+            },
+            DeleteTarget::CellZIndex => self.kitty_delete_visible_placements(|point, placement| {
+                point == Point::new(line, column) && placement.z_index == z_index
+            }),
+            DeleteTarget::Column => {
+                self.kitty_delete_visible_placements(|point, _| point.column == column)
+            },
+            DeleteTarget::Row => {
+                self.kitty_delete_visible_placements(|point, _| point.line == line)
+            },
+            DeleteTarget::ZIndex => {
+                self.kitty_delete_visible_placements(|_, placement| placement.z_index == z_index)
+            },
+            DeleteTarget::IdRange => {
+                let ids = command.x..=command.y;
+                if command.delete.free {
+                    self.graphics.kitty_images.retain(|image| !ids.contains(&image.id));
+                }
+
+                self.kitty_delete_placements(|placement| {
+                    placement.kitty_id.map_or(false, |id| ids.contains(&id.image_id))
+                })
+            },
+            DeleteTarget::Unsupported(target) => {
+                debug!("Unsupported kitty graphics delete target {:?}", target as char);
+                return;
+            },
+        };
+
+        // Uppercase targets also remove the stored image data.
+        if command.delete.free {
+            image_ids.sort_unstable();
+            image_ids.dedup();
+            for image_id in image_ids {
+                self.graphics.kitty_images.remove(image_id);
+            }
+        }
+    }
+
+    /// Delete kitty graphics placements matching `predicate`, returning their image IDs.
+    fn kitty_delete_placements<F>(&mut self, predicate: F) -> Vec<u32>
+    where
+        F: Fn(&GraphicPlacement) -> bool,
+    {
+        let placements =
+            self.graphics.kitty_placements().filter(|placement| predicate(placement)).collect();
+        self.kitty_remove_placements(placements)
+    }
+
+    /// Delete kitty graphics placements covering a visible cell matching `predicate`, returning
+    /// their image IDs.
+    fn kitty_delete_visible_placements<F>(&mut self, predicate: F) -> Vec<u32>
+    where
+        F: Fn(Point, &GraphicPlacement) -> bool,
+    {
+        let mut placements: Vec<Arc<GraphicPlacement>> = Vec::new();
+        let mut matched = HashSet::new();
+        for line in (0..self.screen_lines() as i32).map(Line) {
+            for column in (0..self.columns()).map(Column) {
+                let point = Point::new(line, column);
+                let placement = match self.grid[point].graphic() {
+                    Some(graphic) if graphic.placement.kitty_id.is_some() => &graphic.placement,
+                    _ => continue,
+                };
+
+                if predicate(point, placement) && matched.insert(Arc::as_ptr(placement)) {
+                    placements.push(placement.clone());
+                }
+            }
+        }
+
+        self.kitty_remove_placements(placements)
+    }
+
+    /// Remove placements from all cells they cover, returning their image IDs.
+    fn kitty_remove_placements(&mut self, placements: Vec<Arc<GraphicPlacement>>) -> Vec<u32> {
+        if placements.is_empty() {
+            return Vec::new();
+        }
+
+        // Most placements are close to the bottom of the grid, so the scan starts there and stops
+        // once the only remaining references to the placements are the ones held here.
+        let removed: HashSet<_> = placements.iter().map(Arc::as_ptr).collect();
+        for line in (self.topmost_line().0..=self.bottommost_line().0).rev().map(Line::from) {
+            if placements.iter().all(|placement| Arc::strong_count(placement) == 1) {
+                break;
+            }
+
+            for cell in self.grid[line][..].iter_mut() {
+                let matches = cell
+                    .graphic()
+                    .map_or(false, |graphic| removed.contains(&Arc::as_ptr(&graphic.placement)));
+                if matches {
+                    cell.set_graphic(None);
+                }
+            }
+        }
+
+        self.mark_fully_damaged();
+
+        placements.iter().filter_map(|placement| Some(placement.kitty_id?.image_id)).collect()
     }
 
     #[inline]
@@ -870,7 +1855,7 @@ impl<T: EventListener> Handler for Term<T> {
         }
 
         // If in insert mode, first shift cells to the right.
-        let columns = self.columns();
+        let mut columns = self.line_end();
         if self.mode.contains(TermMode::INSERT) && self.grid.cursor.point.column + width < columns {
             let line = self.grid.cursor.point.line;
             let col = self.grid.cursor.point.column;
@@ -885,12 +1870,13 @@ impl<T: EventListener> Handler for Term<T> {
             self.write_at_cursor(c);
         } else {
             if self.grid.cursor.point.column + 1 >= columns {
-                if self.mode.contains(TermMode::LINE_WRAP) {
+                if self.line_wrap() {
                     // Insert placeholder before wide char if glyph does not fit in this row.
                     self.grid.cursor.template.flags.insert(Flags::LEADING_WIDE_CHAR_SPACER);
                     self.write_at_cursor(' ');
                     self.grid.cursor.template.flags.remove(Flags::LEADING_WIDE_CHAR_SPACER);
                     self.wrapline();
+                    columns = self.line_end();
                 } else {
                     // Prevent out of bounds crash when linewrapping is disabled.
                     self.grid.cursor.input_needs_wrap = true;
@@ -932,20 +1918,37 @@ impl<T: EventListener> Handler for Term<T> {
         self.mark_fully_damaged();
     }
 
+    #[inline]
+    fn set_line_attr(&mut self, line_attr: LineAttr) {
+        // Lines can't be changed while writing to the status line.
+        if self.status_line_routed() {
+            return;
+        }
+
+        trace!("Setting line attribute: {:?}", line_attr);
+        let line = self.grid.cursor.point.line;
+        self.grid.row_attrs_mut(line).line_attr = line_attr;
+
+        self.clamp_cursor_column();
+        self.damage.damage_line(line.0 as usize, 0, self.columns() - 1);
+    }
+
     #[inline]
     fn goto(&mut self, line: Line, col: Column) {
         trace!("Going to: line={}, col={}", line, col);
-        let (y_offset, max_y) = if self.mode.contains(TermMode::ORIGIN) {
-            (self.scroll_region.start, self.scroll_region.end - 1)
+        let (y_offset, max_y, x_offset, max_x) = if self.mode.contains(TermMode::ORIGIN) {
+            let margins = &self.horizontal_margins;
+            (self.scroll_region.start, self.scroll_region.end - 1, margins.start, margins.end - 1)
         } else {
-            (Line(0), self.bottommost_line())
+            (Line(0), self.bottommost_line(), Column(0), self.last_column())
         };
 
         self.damage_cursor();
         self.grid.cursor.point.line = cmp::max(cmp::min(line + y_offset, max_y), Line(0));
-        self.grid.cursor.point.column = cmp::min(col, self.last_column());
-        self.damage_cursor();
+        self.grid.cursor.point.column = cmp::min(col + x_offset, max_x);
         self.grid.cursor.input_needs_wrap = false;
+        self.clamp_cursor_column();
+        self.damage_cursor();
     }
 
     #[inline]
@@ -962,15 +1965,21 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn insert_blank(&mut self, count: usize) {
+        // Characters outside of the margins can't be shifted.
+        if !self.cursor_in_margins() {
+            return;
+        }
+
+        let columns = self.line_end();
         let cursor = &self.grid.cursor;
         let bg = cursor.template.bg;
 
         // Ensure inserting within terminal bounds
-        let count = cmp::min(count, self.columns() - cursor.point.column.0);
+        let count = cmp::min(count, columns - cursor.point.column.0);
 
         let source = cursor.point.column;
         let destination = cursor.point.column.0 + count;
-        let num_cells = self.columns() - destination;
+        let num_cells = columns - destination;
 
         let line = cursor.point.line;
         self.damage.damage_line(line.0 as usize, 0, self.columns() - 1);
@@ -1003,7 +2012,8 @@ impl<T: EventListener> Handler for Term<T> {
     #[inline]
     fn move_forward(&mut self, cols: Column) {
         trace!("Moving forward: {}", cols);
-        let last_column = cmp::min(self.grid.cursor.point.column + cols, self.last_column());
+        let last_column =
+            cmp::min(self.grid.cursor.point.column + cols, Column(self.line_end() - 1));
 
         let cursor_line = self.grid.cursor.point.line.0 as usize;
         self.damage.damage_line(cursor_line, self.grid.cursor.point.column.0, last_column.0);
@@ -1015,7 +2025,8 @@ impl<T: EventListener> Handler for Term<T> {
     #[inline]
     fn move_backward(&mut self, cols: Column) {
         trace!("Moving backward: {}", cols);
-        let column = self.grid.cursor.point.column.saturating_sub(cols.0);
+        let line_start = self.line_start();
+        let column = cmp::max(self.grid.cursor.point.column.saturating_sub(cols.0), line_start.0);
 
         let cursor_line = self.grid.cursor.point.line.0 as usize;
         self.damage.damage_line(cursor_line, column, self.grid.cursor.point.column.0);
@@ -1029,7 +2040,7 @@ impl<T: EventListener> Handler for Term<T> {
         match intermediate {
             None => {
                 trace!("Reporting primary device attributes");
-                let text = String::from("\x1b[?6c");
+                let text = String::from("\x1b[?6;4c");
                 self.event_proxy.send_event(Event::PtyWrite(text));
             },
             Some('>') => {
@@ -1090,7 +2101,7 @@ impl<T: EventListener> Handler for Term<T> {
             }
 
             loop {
-                if (self.grid.cursor.point.column + 1) == self.columns() {
+                if (self.grid.cursor.point.column + 1) == self.line_end() {
                     break;
                 }
 
@@ -1108,7 +2119,9 @@ impl<T: EventListener> Handler for Term<T> {
     fn backspace(&mut self) {
         trace!("Backspace");
 
-        if self.grid.cursor.point.column > Column(0) {
+        if self.grid.cursor.point.column > Column(0)
+            && self.grid.cursor.point.column != self.horizontal_margins.start
+        {
             let line = self.grid.cursor.point.line.0 as usize;
             let column = self.grid.cursor.point.column.0;
             self.grid.cursor.point.column -= 1;
@@ -1121,23 +2134,38 @@ impl<T: EventListener> Handler for Term<T> {
     #[inline]
     fn carriage_return(&mut self) {
         trace!("Carriage return");
-        let new_col = 0;
+        let new_col = self.line_start().0;
         let line = self.grid.cursor.point.line.0 as usize;
         self.damage_line(line, new_col, self.grid.cursor.point.column.0);
         self.grid.cursor.point.column = Column(new_col);
         self.grid.cursor.input_needs_wrap = false;
     }
 
+    #[inline]
+    fn bell(&mut self) {
+        trace!("Bell");
+        self.event_proxy.send_event(Event::Bell);
+    }
+
     /// Linefeed.
     #[inline]
     fn linefeed(&mut self) {
+        // Lines can't be changed while writing to the status line.
+        if self.status_line_routed() {
+            return;
+        }
+
         trace!("Linefeed");
         let next = self.grid.cursor.point.line + 1;
         if next == self.scroll_region.end {
-            self.scroll_up(1);
+            // Content can't scroll while the cursor is outside of the margins.
+            if self.cursor_in_margins() {
+                self.scroll_up(1);
+            }
         } else if next < self.screen_lines() {
             self.damage_cursor();
             self.grid.cursor.point.line += 1;
+            self.clamp_cursor_column();
             self.damage_cursor();
         }
     }
@@ -1171,6 +2199,11 @@ impl<T: EventListener> Handler for Term<T> {
     /// on it.
     #[inline]
     fn newline(&mut self) {
+        // Lines can't be changed while writing to the status line.
+        if self.status_line_routed() {
+            return;
+        }
+
         self.linefeed();
 
         if self.mode.contains(TermMode::LINE_FEED_NEW_LINE) {
@@ -1201,7 +2234,7 @@ impl<T: EventListener> Handler for Term<T> {
         trace!("Inserting blank {} lines", lines);
 
         let origin = self.grid.cursor.point.line;
-        if self.scroll_region.contains(&origin) {
+        if self.scroll_region.contains(&origin) && self.cursor_in_margins() {
             self.scroll_down_relative(origin, lines);
         }
     }
@@ -1213,7 +2246,7 @@ impl<T: EventListener> Handler for Term<T> {
 
         trace!("Deleting {} lines", lines);
 
-        if lines > 0 && self.scroll_region.contains(&origin) {
+        if lines > 0 && self.scroll_region.contains(&origin) && self.cursor_in_margins() {
             self.scroll_up_relative(origin, lines);
         }
     }
@@ -1239,12 +2272,17 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn delete_chars(&mut self, count: usize) {
-        let columns = self.columns();
+        // Characters outside of the margins can't be shifted.
+        if !self.cursor_in_margins() {
+            return;
+        }
+
+        let columns = self.line_end();
         let cursor = &self.grid.cursor;
         let bg = cursor.template.bg;
 
         // Ensure deleting within terminal bounds.
-        let count = cmp::min(count, columns);
+        let count = cmp::min(count, columns - cursor.point.column.0);
 
         let start = cursor.point.column.0;
         let end = cmp::min(start + count, columns - 1);
@@ -1261,7 +2299,7 @@ impl<T: EventListener> Handler for Term<T> {
         // Clear last `count` cells in the row. If deleting 1 char, need to delete
         // 1 cell.
         let end = columns - count;
-        for cell in &mut row[end..] {
+        for cell in &mut row[end..columns] {
             *cell = bg.into();
         }
     }
@@ -1464,6 +2502,11 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::ClearMode::Saved if self.history_size() > 0 => {
                 self.grid.clear_history();
 
+                if self.mode.contains(TermMode::VI) {
+                    self.vi_mode_cursor.point.line =
+                        cmp::max(self.vi_mode_cursor.point.line, Line(0));
+                }
+
                 self.selection = self.selection.take().filter(|s| !s.intersects_range(..Line(0)));
             },
             // We have no history to clear.
@@ -1489,36 +2532,27 @@ impl<T: EventListener> Handler for Term<T> {
     /// Reset all important fields in the term struct.
     #[inline]
     fn reset_state(&mut self) {
-        if self.mode.contains(TermMode::ALT_SCREEN) {
-            mem::swap(&mut self.grid, &mut self.inactive_grid);
-        }
-        self.active_charset = Default::default();
-        self.cursor_style = None;
-        self.grid.reset();
-        self.inactive_grid.reset();
-        self.scroll_region = Line(0)..Line(self.screen_lines() as i32);
-        self.tabs = TabStops::new(self.columns());
-        self.title_stack = Vec::new();
-        self.title = None;
-        self.selection = None;
-
-        // Preserve vi mode across resets.
-        self.mode &= TermMode::VI;
-        self.mode.insert(TermMode::default());
-
-        self.event_proxy.send_event(Event::CursorBlinkingChange);
-        self.mark_fully_damaged();
+        self.on_main_display(|term| term.reset(false));
     }
 
     #[inline]
     fn reverse_index(&mut self) {
+        // Lines can't be changed while writing to the status line.
+        if self.status_line_routed() {
+            return;
+        }
+
         trace!("Reversing index");
         // If cursor is at the top.
         if self.grid.cursor.point.line == self.scroll_region.start {
-            self.scroll_down(1);
+            // Content can't scroll while the cursor is outside of the margins.
+            if self.cursor_in_margins() {
+                self.scroll_down(1);
+            }
         } else {
             self.damage_cursor();
             self.grid.cursor.point.line = cmp::max(self.grid.cursor.point.line - 1, Line(0));
+            self.clamp_cursor_column();
             self.damage_cursor();
         }
     }
@@ -1529,6 +2563,53 @@ impl<T: EventListener> Handler for Term<T> {
         self.grid.cursor.template.set_hyperlink(hyperlink);
     }
 
+    #[inline]
+    fn prompt_mark(&mut self, mark: PromptMark) {
+        trace!("Setting prompt mark: {:?}", mark);
+
+        let line = self.grid.cursor.point.line;
+        match mark {
+            PromptMark::PromptStart => self.grid.row_attrs_mut(line).shell_marks.prompt = true,
+            PromptMark::OutputStart => self.grid.row_attrs_mut(line).shell_marks.output = true,
+            PromptMark::CommandEnd(exit_code) => {
+                self.grid.row_attrs_mut(line).shell_marks.command_end = true;
+
+                // Store the exit status with the prompt of the command.
+                let prompt_line = if self.grid.row_attrs(line).shell_marks.prompt {
+                    Some(line)
+                } else {
+                    self.prompt_line(line, Direction::Left)
+                };
+                if let (Some(exit_code), Some(prompt_line)) = (exit_code, prompt_line) {
+                    self.grid.row_attrs_mut(prompt_line).shell_marks.exit_code = Some(exit_code);
+                    self.mark_fully_damaged();
+                }
+            },
+            PromptMark::CommandStart => (),
+        }
+    }
+
+    #[inline]
+    fn set_working_directory(&mut self, hostname: &str, path: PathBuf) {
+        // Directories on other machines can't be used to spawn local processes.
+        if !is_local_hostname(hostname) {
+            debug!("Ignoring working directory {:?} on remote host {}", path, hostname);
+            return;
+        }
+
+        trace!("Setting working directory: {:?}", path);
+        self.working_directory = Some(path);
+    }
+
+    #[inline]
+    fn desktop_notification(&mut self, notification: Notification) {
+        trace!("Requesting desktop notification: {:?}", notification);
+
+        if !notification.title.is_empty() || !notification.body.is_empty() {
+            self.event_proxy.send_event(Event::Notification(notification));
+        }
+    }
+
     /// Set a terminal attribute.
     #[inline]
     fn terminal_attribute(&mut self, attr: Attr) {
@@ -1585,17 +2666,36 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn set_mode(&mut self, mode: ansi::Mode) {
+        // Modes are applied to the main display, since they might switch its screen buffer.
+        if self.status_line_routed() {
+            return self.on_main_display(|term| term.set_mode(mode));
+        }
+
         trace!("Setting mode: {:?}", mode);
         match mode {
+            // Synchronized updates are handled by the parser.
+            ansi::Mode::SyncUpdate => (),
             ansi::Mode::UrgencyHints => self.mode.insert(TermMode::URGENCY_HINTS),
             ansi::Mode::SwapScreenAndSetRestoreCursor => {
                 if !self.mode.contains(TermMode::ALT_SCREEN) {
                     self.swap_alt();
                 }
             },
+            ansi::Mode::SwapScreen | ansi::Mode::SwapScreenAndClear => {
+                if !self.mode.contains(TermMode::ALT_SCREEN) {
+                    self.swap_alt_keep_cursor();
+                }
+            },
+            ansi::Mode::SaveRestoreCursor => self.save_cursor_position(),
+            ansi::Mode::ReverseVideo => {
+                self.mode.insert(TermMode::REVERSE_VIDEO);
+                self.mark_fully_damaged();
+            },
             ansi::Mode::ShowCursor => self.mode.insert(TermMode::SHOW_CURSOR),
+            ansi::Mode::LeftRightMargin => self.mode.insert(TermMode::LEFT_RIGHT_MARGIN),
             ansi::Mode::CursorKeys => self.mode.insert(TermMode::APP_CURSOR),
             // Mouse protocols are mutually exclusive.
+            ansi::Mode::HighlightMouseTracking => (),
             ansi::Mode::ReportMouseClicks => {
                 self.mode.remove(TermMode::MOUSE_MODE);
                 self.mode.insert(TermMode::MOUSE_REPORT_CLICK);
@@ -1615,13 +2715,21 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::Mode::BracketedPaste => self.mode.insert(TermMode::BRACKETED_PASTE),
             // Mouse encodings are mutually exclusive.
             ansi::Mode::SgrMouse => {
-                self.mode.remove(TermMode::UTF8_MOUSE);
+                self.mode.remove(TermMode::MOUSE_ENCODING);
                 self.mode.insert(TermMode::SGR_MOUSE);
             },
             ansi::Mode::Utf8Mouse => {
-                self.mode.remove(TermMode::SGR_MOUSE);
+                self.mode.remove(TermMode::MOUSE_ENCODING);
                 self.mode.insert(TermMode::UTF8_MOUSE);
             },
+            ansi::Mode::UrxvtMouse => {
+                self.mode.remove(TermMode::MOUSE_ENCODING);
+                self.mode.insert(TermMode::URXVT_MOUSE);
+            },
+            ansi::Mode::SgrPixelsMouse => {
+                self.mode.remove(TermMode::MOUSE_ENCODING);
+                self.mode.insert(TermMode::SGR_PIXELS_MOUSE);
+            },
             ansi::Mode::AlternateScroll => self.mode.insert(TermMode::ALTERNATE_SCROLL),
             ansi::Mode::LineWrap => self.mode.insert(TermMode::LINE_WRAP),
             ansi::Mode::LineFeedNewLine => self.mode.insert(TermMode::LINE_FEED_NEW_LINE),
@@ -1638,16 +2746,44 @@ impl<T: EventListener> Handler for Term<T> {
 
     #[inline]
     fn unset_mode(&mut self, mode: ansi::Mode) {
+        // Modes are applied to the main display, since they might switch its screen buffer.
+        if self.status_line_routed() {
+            return self.on_main_display(|term| term.unset_mode(mode));
+        }
+
         trace!("Unsetting mode: {:?}", mode);
         match mode {
+            // Synchronized updates are handled by the parser.
+            ansi::Mode::SyncUpdate => (),
             ansi::Mode::UrgencyHints => self.mode.remove(TermMode::URGENCY_HINTS),
             ansi::Mode::SwapScreenAndSetRestoreCursor => {
                 if self.mode.contains(TermMode::ALT_SCREEN) {
                     self.swap_alt();
                 }
             },
+            ansi::Mode::SwapScreen => {
+                if self.mode.contains(TermMode::ALT_SCREEN) {
+                    self.swap_alt_keep_cursor();
+                }
+            },
+            ansi::Mode::SwapScreenAndClear => {
+                if self.mode.contains(TermMode::ALT_SCREEN) {
+                    self.grid.reset_region(..);
+                    self.swap_alt_keep_cursor();
+                }
+            },
+            ansi::Mode::SaveRestoreCursor => self.restore_cursor_position(),
+            ansi::Mode::ReverseVideo => {
+                self.mode.remove(TermMode::REVERSE_VIDEO);
+                self.mark_fully_damaged();
+            },
             ansi::Mode::ShowCursor => self.mode.remove(TermMode::SHOW_CURSOR),
+            ansi::Mode::LeftRightMargin => {
+                self.mode.remove(TermMode::LEFT_RIGHT_MARGIN);
+                self.horizontal_margins = Column(0)..Column(self.columns());
+            },
             ansi::Mode::CursorKeys => self.mode.remove(TermMode::APP_CURSOR),
+            ansi::Mode::HighlightMouseTracking => (),
             ansi::Mode::ReportMouseClicks => {
                 self.mode.remove(TermMode::MOUSE_REPORT_CLICK);
                 self.event_proxy.send_event(Event::MouseCursorDirty);
@@ -1664,6 +2800,8 @@ impl<T: EventListener> Handler for Term<T> {
             ansi::Mode::BracketedPaste => self.mode.remove(TermMode::BRACKETED_PASTE),
             ansi::Mode::SgrMouse => self.mode.remove(TermMode::SGR_MOUSE),
             ansi::Mode::Utf8Mouse => self.mode.remove(TermMode::UTF8_MOUSE),
+            ansi::Mode::UrxvtMouse => self.mode.remove(TermMode::URXVT_MOUSE),
+            ansi::Mode::SgrPixelsMouse => self.mode.remove(TermMode::SGR_PIXELS_MOUSE),
             ansi::Mode::AlternateScroll => self.mode.remove(TermMode::ALTERNATE_SCROLL),
             ansi::Mode::LineWrap => self.mode.remove(TermMode::LINE_WRAP),
             ansi::Mode::LineFeedNewLine => self.mode.remove(TermMode::LINE_FEED_NEW_LINE),
@@ -1681,6 +2819,113 @@ impl<T: EventListener> Handler for Term<T> {
         }
     }
 
+    #[inline]
+    fn report_mode(&mut self, private: bool, mode: u16) {
+        trace!("Reporting mode {} (private: {})", mode, private);
+
+        let intermediate = if private { Some(&b'?') } else { None };
+        let state = match ansi::Mode::from_primitive(intermediate, mode) {
+            Some(mode) => self.mode_state(&mode),
+            None => ModeState::NotRecognized,
+        };
+
+        let prefix = if private { "?" } else { "" };
+        let text = format!("\x1b[{}{};{}$y", prefix, mode, state as u8);
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn report_sync_update(&mut self, active: bool) {
+        trace!("Reporting synchronized update (active: {})", active);
+
+        let mode = ansi::Mode::SyncUpdate as u16;
+        let text = format!("\x1b[?{};{}$y", mode, ModeState::from(active) as u8);
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn report_status_setting(&mut self, setting: Option<StatusSetting>) {
+        trace!("Reporting status setting: {:?}", setting);
+
+        let value = match setting {
+            Some(StatusSetting::Sgr) => format!("{}m", self.sgr_parameters()),
+            Some(StatusSetting::ScrollingRegion) => {
+                format!("{};{}r", self.scroll_region.start + 1, self.scroll_region.end)
+            },
+            Some(StatusSetting::HorizontalMargins) => {
+                format!("{};{}s", self.horizontal_margins.start + 1, self.horizontal_margins.end)
+            },
+            Some(StatusSetting::CursorStyle) => {
+                let style = self.cursor_style.unwrap_or(self.default_cursor_style);
+                let shape = match style.shape {
+                    CursorShape::Underline => 3,
+                    CursorShape::Beam => 5,
+                    CursorShape::Block | CursorShape::HollowBlock | CursorShape::Hidden => 1,
+                };
+                format!("{} q", shape + !style.blinking as u8)
+            },
+            // Character protection is not supported.
+            Some(StatusSetting::ProtectedAttribute) => String::from("0\"q"),
+            None => {
+                self.event_proxy.send_event(Event::PtyWrite(String::from("\x1bP0$r\x1b\\")));
+                return;
+            },
+        };
+
+        let text = format!("\x1bP1$r{}\x1b\\", value);
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn report_terminfo_capability(&mut self, name: &str) {
+        trace!("Reporting terminfo capability: {}", name);
+
+        let hex = |text: &str| text.bytes().map(|byte| format!("{:02X}", byte)).collect::<String>();
+        let text = match terminfo::capability(name) {
+            Some(capability) => match capability.value() {
+                Some(value) => format!("\x1bP1+r{}={}\x1b\\", hex(name), hex(&value)),
+                None => format!("\x1bP1+r{}\x1b\\", hex(name)),
+            },
+            None => format!("\x1bP0+r{}\x1b\\", hex(name)),
+        };
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn set_status_line_type(&mut self, ty: StatusLineType) {
+        trace!("Setting status line type: {:?}", ty);
+
+        if ty != StatusLineType::HostWritable {
+            self.set_status_line_active(false);
+        }
+
+        if ty != self.status_line_type {
+            self.status_line_type = ty;
+            self.status_line.reset();
+            self.status_line_damage.is_fully_damaged = true;
+            self.event_proxy.send_event(Event::StatusLineChange);
+        }
+    }
+
+    #[inline]
+    fn set_status_line_active(&mut self, active: bool) {
+        trace!("Setting status line active: {}", active);
+        self.status_line_active = active && self.status_line_type == StatusLineType::HostWritable;
+
+        if self.status_line_active {
+            self.resume_status_line();
+        } else {
+            self.suspend_status_line();
+        }
+    }
+
+    #[inline]
+    fn report_version(&mut self) {
+        trace!("Reporting terminal version");
+        let text = format!("\x1bP>|alacritty({})\x1b\\", env!("CARGO_PKG_VERSION"));
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
     #[inline]
     fn set_scrolling_region(&mut self, top: usize, bottom: Option<usize>) {
         // Fallback to the last line as default.
@@ -1706,6 +2951,27 @@ impl<T: EventListener> Handler for Term<T> {
         self.goto(Line(0), Column(0));
     }
 
+    #[inline]
+    fn set_left_right_margins(&mut self, left: usize, right: Option<usize>) {
+        if !self.mode.contains(TermMode::LEFT_RIGHT_MARGIN) {
+            self.save_cursor_position();
+            return;
+        }
+
+        // Fallback to the last column as default.
+        let right = cmp::min(right.unwrap_or_else(|| self.columns()), self.columns());
+
+        if left >= right {
+            debug!("Invalid margins: ({};{})", left, right);
+            return;
+        }
+
+        trace!("Setting horizontal margins: ({};{})", left - 1, right);
+
+        self.horizontal_margins = Column(left - 1)..Column(right);
+        self.goto(Line(0), Column(0));
+    }
+
     #[inline]
     fn set_keypad_application_mode(&mut self) {
         trace!("Setting keypad application mode");
@@ -1800,6 +3066,223 @@ impl<T: EventListener> Handler for Term<T> {
         let text = format!("\x1b[8;{};{}t", self.screen_lines(), self.columns());
         self.event_proxy.send_event(Event::PtyWrite(text));
     }
+
+    #[inline]
+    fn cell_size_pixels(&mut self) {
+        self.event_proxy.send_event(Event::TextAreaSizeRequest(Arc::new(move |window_size| {
+            format!("\x1b[6;{};{}t", window_size.cell_height, window_size.cell_width)
+        })));
+    }
+
+    #[inline]
+    fn screen_size_pixels(&mut self) {
+        self.event_proxy.send_event(Event::ScreenSizeRequest(Arc::new(move |width, height| {
+            format!("\x1b[5;{height};{width}t")
+        })));
+    }
+
+    #[inline]
+    fn screen_size_chars(&mut self) {
+        let cell_width = self.graphics.cell_width as u32;
+        let cell_height = self.graphics.cell_height as u32;
+        self.event_proxy.send_event(Event::ScreenSizeRequest(Arc::new(move |width, height| {
+            format!("\x1b[9;{};{}t", height / cell_height, width / cell_width)
+        })));
+    }
+
+    #[inline]
+    fn window_position(&mut self) {
+        self.event_proxy.send_event(Event::WindowPositionRequest(Arc::new(move |x, y| {
+            format!("\x1b[3;{x};{y}t")
+        })));
+    }
+
+    #[inline]
+    fn report_title(&mut self, icon_label: bool) {
+        let kind = if icon_label { 'L' } else { 'l' };
+        self.event_proxy.send_event(Event::TitleRequest(Arc::new(move |title| {
+            // Strip control characters to prevent the title from injecting escape sequences.
+            let title: String = title.chars().filter(|c| !c.is_control()).collect();
+            format!("\x1b]{kind}{title}\x1b\\")
+        })));
+    }
+
+    #[inline]
+    fn window_operation(&mut self, operation: WindowOperation) {
+        trace!("Requesting window operation: {:?}", operation);
+        self.event_proxy.send_event(Event::WindowOperation(operation));
+    }
+
+    #[inline]
+    fn insert_graphic(&mut self, graphic: GraphicData) {
+        let cell_width = self.graphics.cell_width;
+        let cell_height = self.graphics.cell_height;
+        let columns = (graphic.width + cell_width - 1) / cell_width;
+        let lines = (graphic.height + cell_height - 1) / cell_height;
+
+        let area = PixelRect { x: 0, y: 0, width: graphic.width, height: graphic.height };
+        let placement = Arc::new(GraphicPlacement {
+            texture: self.graphics.insert(graphic),
+            source: area,
+            destination: area,
+            cell_width,
+            cell_height,
+            z_index: 0,
+            kitty_id: None,
+        });
+
+        self.place_graphic(placement, columns, lines, true);
+
+        // Move the cursor to the start of the line below the image.
+        self.carriage_return();
+        self.linefeed();
+    }
+
+    #[inline]
+    fn graphics_attribute(&mut self, item: u16, action: u16) {
+        trace!("Reporting graphics attribute {} with action {}", item, action);
+
+        // Attributes can be read or reset, but not modified.
+        let text = match (item, action) {
+            (1, 1 | 2 | 4) => format!("\x1b[?1;0;{}S", MAX_COLOR_REGISTERS),
+            (2, 1 | 2) => {
+                let width = cmp::min(self.columns() * self.graphics.cell_width, MAX_GRAPHIC_WIDTH);
+                let height =
+                    cmp::min(self.screen_lines() * self.graphics.cell_height, MAX_GRAPHIC_HEIGHT);
+                format!("\x1b[?2;0;{};{}S", width, height)
+            },
+            (2, 4) => format!("\x1b[?2;0;{};{}S", MAX_GRAPHIC_WIDTH, MAX_GRAPHIC_HEIGHT),
+            (1 | 2, _) => format!("\x1b[?{};3;0S", item),
+            _ => format!("\x1b[?{};1;0S", item),
+        };
+
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn kitty_graphics_command(&mut self, mut command: kitty::Command) {
+        trace!("Handling kitty graphics command: {:?}", command);
+
+        let (image_id, result) = match command.action {
+            Action::Transmit | Action::TransmitAndDisplay => {
+                match self.kitty_transmit(&mut command) {
+                    Ok(image_id) => (image_id, Ok(())),
+                    Err(err) => (command.image_id, Err(err)),
+                }
+            },
+            Action::Display => match self.kitty_display(&command) {
+                Ok(image_id) => (image_id, Ok(())),
+                Err(err) => (command.image_id, Err(err)),
+            },
+            Action::Query => (command.image_id, command.load().map(|_| ())),
+            Action::Delete => {
+                self.kitty_delete(&command);
+                return;
+            },
+            Action::Unsupported(_) => {
+                (command.image_id, Err(kitty::Error::Invalid("Unsupported action")))
+            },
+        };
+
+        if let Err(err) = &result {
+            debug!("Kitty graphics command failed: {}", err);
+        }
+
+        if let Some(text) = command.response(image_id, &result) {
+            self.event_proxy.send_event(Event::PtyWrite(text));
+        }
+    }
+
+    #[inline]
+    fn push_keyboard_mode(&mut self, mode: KeyboardModes) {
+        trace!("Pushing '{:?}' onto keyboard mode stack", mode);
+
+        if self.keyboard_mode_stack.len() >= KEYBOARD_MODE_STACK_MAX_DEPTH {
+            let removed = self.keyboard_mode_stack.remove(0);
+            trace!(
+                "Removing '{:?}' from bottom of keyboard mode stack that exceeds its maximum depth",
+                removed
+            );
+        }
+
+        self.keyboard_mode_stack.push(mode);
+        self.update_keyboard_mode();
+    }
+
+    #[inline]
+    fn pop_keyboard_modes(&mut self, to_pop: u16) {
+        trace!("Popping {} keyboard modes from stack", to_pop);
+
+        let new_len = self.keyboard_mode_stack.len().saturating_sub(to_pop as usize);
+        self.keyboard_mode_stack.truncate(new_len);
+        self.update_keyboard_mode();
+    }
+
+    #[inline]
+    fn set_keyboard_mode(&mut self, mode: KeyboardModes, behavior: KeyboardModesApplyBehavior) {
+        trace!("Setting keyboard mode to '{:?}' with {:?} behavior", mode, behavior);
+
+        let active_mode =
+            self.keyboard_mode_stack.last().copied().unwrap_or(KeyboardModes::NO_MODE);
+        let new_mode = match behavior {
+            KeyboardModesApplyBehavior::Replace => mode,
+            KeyboardModesApplyBehavior::Union => active_mode | mode,
+            KeyboardModesApplyBehavior::Difference => active_mode - mode,
+        };
+
+        // Modifying the modes without a pushed entry creates one.
+        match self.keyboard_mode_stack.last_mut() {
+            Some(active_mode) => *active_mode = new_mode,
+            None if new_mode.is_empty() => (),
+            None => self.keyboard_mode_stack.push(new_mode),
+        }
+        self.update_keyboard_mode();
+    }
+
+    #[inline]
+    fn report_keyboard_mode(&mut self) {
+        let mode = self.keyboard_mode_stack.last().copied().unwrap_or(KeyboardModes::NO_MODE);
+        let text = format!("\x1b[?{}u", mode.bits());
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+
+    #[inline]
+    fn set_modify_other_keys(&mut self, mode: ModifyOtherKeys) {
+        trace!("Setting modifyOtherKeys to {:?}", mode);
+        self.modify_other_keys = mode;
+    }
+
+    #[inline]
+    fn report_modify_other_keys(&mut self) {
+        let value = match self.modify_other_keys {
+            ModifyOtherKeys::Reset => 0,
+            ModifyOtherKeys::EnableExceptWellDefined => 1,
+            ModifyOtherKeys::EnableAll => 2,
+        };
+        let text = format!("\x1b[>4;{}m", value);
+        self.event_proxy.send_event(Event::PtyWrite(text));
+    }
+}
+
+/// SGR parameters for a foreground (`30`), background (`40`) or underline (`50`) color.
+///
+/// Default colors don't need any parameters, since they're already set by the initial reset.
+fn sgr_color(color: Color, base: u8) -> Option<String> {
+    match color {
+        // Only foreground and background colors have parameters for the 16 named colors.
+        Color::Named(color) if (color as usize) < 8 && base != 50 => {
+            Some((base + color as u8).to_string())
+        },
+        Color::Named(color) if (color as usize) < 16 && base != 50 => {
+            Some((base + 60 + color as u8 - 8).to_string())
+        },
+        Color::Named(color) if (color as usize) < 16 => {
+            Some(format!("{};5;{}", base + 8, color as u8))
+        },
+        Color::Named(_) => None,
+        Color::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        Color::Spec(rgb) => Some(format!("{};2;{};{};{}", base + 8, rgb.r, rgb.g, rgb.b)),
+    }
 }
 
 /// Terminal version for escape sequence reports.
@@ -1823,6 +3306,27 @@ fn version_number(mut version: &str) -> usize {
     version_number
 }
 
+/// Check if a hostname reported by the shell refers to the local machine.
+fn is_local_hostname(hostname: &str) -> bool {
+    hostname.is_empty()
+        || hostname.eq_ignore_ascii_case("localhost")
+        || local_hostname().map_or(false, |local| local.eq_ignore_ascii_case(hostname))
+}
+
+/// Hostname of the local machine.
+#[cfg(unix)]
+fn local_hostname() -> Option<String> {
+    let mut buf = [0; 256];
+    let hostname = nix::unistd::gethostname(&mut buf).ok()?;
+    hostname.to_str().ok().map(ToOwned::to_owned)
+}
+
+/// Hostname of the local machine.
+#[cfg(windows)]
+fn local_hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClipboardType {
     Clipboard,
@@ -1882,13 +3386,15 @@ pub struct RenderableCursor {
 
 impl RenderableCursor {
     fn new<T>(term: &Term<T>) -> Self {
-        let mut point = term.grid.cursor.point;
+        // Cursor position.
+        let vi_mode = term.mode().contains(TermMode::VI);
+        let mut point = if vi_mode { term.vi_mode_cursor.point } else { term.grid.cursor.point };
         if term.grid[point].flags.contains(Flags::WIDE_CHAR_SPACER) {
             point.column -= 1;
         }
 
         // Cursor shape.
-        let shape = if !term.mode().contains(TermMode::SHOW_CURSOR) {
+        let shape = if !vi_mode && !term.mode().contains(TermMode::SHOW_CURSOR) {
             CursorShape::Hidden
         } else {
             term.cursor_style().shape
@@ -2185,6 +3691,30 @@ mod tests {
         assert_eq!(term.grid.display_offset(), 0);
     }
 
+    #[test]
+    fn reset_for_new_child_keeps_history() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // Create 5 lines of scrollback and write to the last visible line.
+        for _ in 0..9 {
+            term.newline();
+        }
+        term.input('a');
+        assert_eq!(term.history_size(), 5);
+
+        term.reset_for_new_child(true);
+
+        // Entire screen up to the cursor was moved into history.
+        assert_eq!(term.history_size(), 10);
+        assert_eq!(term.grid[Line(-1)][Column(0)].c, 'a');
+        assert_eq!(term.grid.cursor.point, Point::new(Line(0), Column(0)));
+        assert_eq!(term.grid[Line(4)][Column(0)].c, ' ');
+
+        term.reset_for_new_child(false);
+        assert_eq!(term.history_size(), 0);
+    }
+
     #[test]
     fn clear_saved_lines() {
         let size = TermSize::new(7, 17);
@@ -2531,6 +4061,505 @@ mod tests {
         assert_eq!(term.title, None);
     }
 
+    #[test]
+    fn mode_state() {
+        let size = TermSize::new(7, 17);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        assert_eq!(term.mode_state(&ansi::Mode::LineWrap), ModeState::Set);
+        assert_eq!(term.mode_state(&ansi::Mode::BracketedPaste), ModeState::Reset);
+        assert_eq!(term.mode_state(&ansi::Mode::ColumnMode), ModeState::PermanentlyReset);
+        assert_eq!(term.mode_state(&ansi::Mode::SyncUpdate), ModeState::Reset);
+
+        term.set_mode(ansi::Mode::BracketedPaste);
+        term.unset_mode(ansi::Mode::LineWrap);
+        term.set_mode(ansi::Mode::BlinkingCursor);
+        assert_eq!(term.mode_state(&ansi::Mode::BracketedPaste), ModeState::Set);
+        assert_eq!(term.mode_state(&ansi::Mode::LineWrap), ModeState::Reset);
+        assert_eq!(term.mode_state(&ansi::Mode::BlinkingCursor), ModeState::Set);
+    }
+
+    #[test]
+    fn status_line_output() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // Output is only routed to host-writable status lines.
+        term.set_status_line_active(true);
+        term.input('a');
+        assert_eq!(term.grid[Line(0)][Column(0)].c, 'a');
+
+        term.set_status_line_type(StatusLineType::HostWritable);
+        term.set_status_line_active(true);
+        term.linefeed();
+        for c in "status line".chars() {
+            term.input(c);
+        }
+        term.set_status_line_active(false);
+        term.input('b');
+
+        // Text beyond the last column overwrites it instead of wrapping.
+        let status_line: String =
+            term.status_line()[Line(0)].into_iter().map(|cell| cell.c).collect();
+        assert_eq!(status_line, "status lie");
+        assert_eq!(term.grid[Line(0)][Column(0)].c, 'a');
+        assert_eq!(term.grid[Line(0)][Column(1)].c, 'b');
+
+        // Leaving the host-writable status line clears it.
+        term.set_status_line_type(StatusLineType::Indicator);
+        assert_eq!(term.status_line()[Line(0)][Column(0)].c, ' ');
+    }
+
+    #[test]
+    fn status_line_operations() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        let status_line =
+            |term: &Term<_>| term.status_line()[Line(0)].into_iter().map(|cell| cell.c).collect();
+
+        term.goto(Line(2), Column(3));
+        term.input('a');
+        term.set_status_line_type(StatusLineType::HostWritable);
+        term.set_status_line_active(true);
+        term.reset_damage();
+
+        // CUU keeps the cursor on the status line.
+        for c in "status".chars() {
+            term.input(c);
+        }
+        term.move_up(1);
+        term.input('!');
+        term.suspend_status_line();
+        let text: String = status_line(&term);
+        assert_eq!(text, "status!   ");
+
+        // IL only clears the status line.
+        term.resume_status_line();
+        term.insert_blank_lines(1);
+        term.suspend_status_line();
+        let text: String = status_line(&term);
+        assert_eq!(text, "          ");
+
+        // ED only clears the status line.
+        term.resume_status_line();
+        term.input('b');
+        term.clear_screen(ansi::ClearMode::All);
+        term.suspend_status_line();
+        let text: String = status_line(&term);
+        assert_eq!(text, "          ");
+
+        // The main display is left untouched.
+        assert_eq!(term.grid[Line(2)][Column(3)].c, 'a');
+        assert_eq!(term.grid.cursor.point, Point::new(Line(2), Column(4)));
+        assert!(term.status_line_damaged());
+        assert!(!term.damage.is_fully_damaged);
+        assert!(term.damage.lines.iter().all(|line| !line.is_damaged()));
+
+        term.resume_status_line();
+        term.set_status_line_active(false);
+        term.reset_damage();
+        assert!(!term.status_line_damaged());
+    }
+
+    #[test]
+    fn status_line_ignores_margins() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.set_mode(ansi::Mode::LeftRightMargin);
+        term.set_left_right_margins(3, Some(5));
+        term.set_scrolling_region(2, Some(4));
+        term.set_status_line_type(StatusLineType::HostWritable);
+        term.set_status_line_active(true);
+
+        // Margins of the main display don't clip the status line.
+        for c in "status line".chars() {
+            term.input(c);
+        }
+        term.carriage_return();
+        term.input('S');
+        term.set_status_line_active(false);
+
+        let status_line: String =
+            term.status_line()[Line(0)].into_iter().map(|cell| cell.c).collect();
+        assert_eq!(status_line, "Status lie");
+        assert_eq!(term.horizontal_margins, Column(2)..Column(5));
+        assert_eq!(term.scroll_region, Line(1)..Line(4));
+    }
+
+    #[test]
+    fn status_line_keeps_mode_changes() {
+        let size = TermSize::new(10, 5);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.set_status_line_type(StatusLineType::HostWritable);
+        term.set_status_line_active(true);
+        term.set_mode(ansi::Mode::BracketedPaste);
+        term.unset_mode(ansi::Mode::LineWrap);
+        term.set_mode(ansi::Mode::SwapScreenAndSetRestoreCursor);
+        term.input('a');
+        term.set_status_line_active(false);
+
+        assert!(term.mode().contains(TermMode::BRACKETED_PASTE | TermMode::ALT_SCREEN));
+        assert!(!term.mode().contains(TermMode::LINE_WRAP));
+        assert_eq!(term.status_line()[Line(0)][Column(0)].c, 'a');
+        assert_eq!(term.grid[Line(0)][Column(0)].c, ' ');
+    }
+
+    #[test]
+    fn mouse_encodings_exclusive() {
+        let size = TermSize::new(7, 17);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.set_mode(ansi::Mode::SgrMouse);
+        term.set_mode(ansi::Mode::UrxvtMouse);
+        assert_eq!(term.mode_state(&ansi::Mode::SgrMouse), ModeState::Reset);
+        assert_eq!(term.mode_state(&ansi::Mode::UrxvtMouse), ModeState::Set);
+        assert!(!term.mode().contains(TermMode::SGR_MOUSE));
+        assert!(term.mode().contains(TermMode::URXVT_MOUSE));
+
+        term.set_mode(ansi::Mode::SgrPixelsMouse);
+        assert_eq!(term.mode_state(&ansi::Mode::UrxvtMouse), ModeState::Reset);
+        assert_eq!(term.mode_state(&ansi::Mode::SgrPixelsMouse), ModeState::Set);
+        assert!(!term.mode().contains(TermMode::URXVT_MOUSE));
+        assert!(term.mode().contains(TermMode::SGR_PIXELS_MOUSE));
+
+        term.unset_mode(ansi::Mode::SgrPixelsMouse);
+        assert!(!term.mode().intersects(TermMode::MOUSE_ENCODING));
+
+        // Highlight tracking is never enabled.
+        let mode = *term.mode();
+        term.set_mode(ansi::Mode::HighlightMouseTracking);
+        assert_eq!(*term.mode(), mode);
+        assert_eq!(
+            term.mode_state(&ansi::Mode::HighlightMouseTracking),
+            ModeState::PermanentlyReset
+        );
+    }
+
+    #[test]
+    fn reverse_video_mode() {
+        let size = TermSize::new(7, 17);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        term.reset_damage();
+
+        term.set_mode(ansi::Mode::ReverseVideo);
+        assert!(term.mode().contains(TermMode::REVERSE_VIDEO));
+        assert_eq!(term.mode_state(&ansi::Mode::ReverseVideo), ModeState::Set);
+        assert!(matches!(term.damage(None), TermDamage::Full));
+        term.reset_damage();
+
+        term.unset_mode(ansi::Mode::ReverseVideo);
+        assert!(!term.mode().contains(TermMode::REVERSE_VIDEO));
+        assert_eq!(term.mode_state(&ansi::Mode::ReverseVideo), ModeState::Reset);
+        assert!(matches!(term.damage(None), TermDamage::Full));
+    }
+
+    #[test]
+    fn double_width_lines() {
+        let size = TermSize::new(6, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // The cursor is moved into the available half of the line.
+        term.goto(Line(0), Column(5));
+        term.set_line_attr(LineAttr::DoubleWidth);
+        assert_eq!(term.grid.cursor.point, Point::new(Line(0), Column(2)));
+
+        // Text wraps after half of the columns.
+        term.goto(Line(0), Column(0));
+        for c in "abcde".chars() {
+            term.input(c);
+        }
+
+        let rows: Vec<String> = (0..2)
+            .map(|line| term.grid[Line(line)][..].iter().map(|cell| cell.c).collect())
+            .collect();
+        assert_eq!(rows, vec!["abc   ", "de    "]);
+
+        // Moving onto a double-width line clamps the cursor.
+        term.goto(Line(1), Column(4));
+        term.move_up(1);
+        assert_eq!(term.grid.cursor.point, Point::new(Line(0), Column(2)));
+
+        // Clearing the screen resets the line attributes.
+        term.clear_screen(ansi::ClearMode::All);
+        assert_eq!(term.grid.row_attrs(Line(0)).line_attr, LineAttr::Single);
+    }
+
+    #[test]
+    fn double_width_line_editing() {
+        let size = TermSize::new(6, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        let row =
+            |term: &Term<_>| -> String { term.grid[Line(0)][..].iter().map(|c| c.c).collect() };
+
+        term.set_line_attr(LineAttr::DoubleWidth);
+        for c in "abc".chars() {
+            term.input(c);
+        }
+
+        // Cells beyond the available columns are never shifted.
+        term.grid[Line(0)][Column(4)].c = 'x';
+
+        // CUF stops at the last available column.
+        term.goto(Line(0), Column(0));
+        term.move_forward(Column(5));
+        assert_eq!(term.grid.cursor.point.column, Column(2));
+
+        // Tabs stop at the last available column.
+        term.goto(Line(0), Column(0));
+        term.put_tab(1);
+        assert_eq!(term.grid.cursor.point.column, Column(2));
+
+        // ICH pushes cells out of the available columns.
+        term.goto(Line(0), Column(0));
+        term.insert_blank(1);
+        assert_eq!(row(&term), " ab x ");
+
+        // DCH pulls in blanks from the last available column.
+        term.delete_chars(1);
+        assert_eq!(row(&term), "ab  x ");
+    }
+
+    #[test]
+    fn horizontal_margins() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // Without DECLRMM, margins can't be set.
+        term.set_left_right_margins(2, Some(4));
+        assert_eq!(term.horizontal_margins, Column(0)..Column(5));
+
+        term.set_mode(ansi::Mode::LeftRightMargin);
+        term.set_left_right_margins(2, Some(4));
+        assert_eq!(term.horizontal_margins, Column(1)..Column(4));
+        assert_eq!(term.grid.cursor.point, Point::new(Line(0), Column(0)));
+
+        // Text wraps at the right margin and continues at the left margin.
+        term.goto(Line(0), Column(1));
+        for c in "abcdefghijk".chars() {
+            term.input(c);
+        }
+
+        // Only the cells within the margins are scrolled.
+        let rows: Vec<String> = (0..3)
+            .map(|line| term.grid[Line(line)][..].iter().map(|cell| cell.c).collect())
+            .collect();
+        assert_eq!(rows, vec![" def ", " ghi ", " jk  "]);
+
+        // Disabling DECLRMM resets the margins.
+        term.unset_mode(ansi::Mode::LeftRightMargin);
+        assert_eq!(term.horizontal_margins, Column(0)..Column(5));
+    }
+
+    #[test]
+    fn insert_graphic() {
+        let size = TermSize::new(5, 3);
+        let mut config = Config::default();
+        config.scrolling.set_history(0);
+        let mut term = Term::new(&config, &size, VoidListener);
+        term.set_cell_size(2, 4);
+
+        // Image covering 3x2 cells, starting in the last line.
+        term.goto(Line(2), Column(1));
+        term.insert_graphic(GraphicData { width: 5, height: 5, pixels: vec![0; 5 * 5 * 4] });
+
+        // The terminal scrolled to fit the image, leaving the cursor below it.
+        assert_eq!(term.grid.cursor.point, Point::new(Line(2), Column(0)));
+        let graphic = term.grid[Line(1)][Column(3)].graphic().unwrap();
+        assert_eq!((graphic.column, graphic.line), (2, 1));
+        assert!(term.grid[Line(0)][Column(0)].graphic().is_none());
+        assert!(term.grid[Line(0)][Column(4)].graphic().is_none());
+
+        let id = graphic.placement.texture.id;
+        let queues = term.graphics_take_queues().unwrap();
+        assert_eq!(queues.pending.len(), 1);
+        assert_eq!(queues.pending[0].0, id);
+
+        // Removing all cells of the image queues its texture for removal.
+        term.goto(Line(0), Column(1));
+        term.input('x');
+        assert!(term.grid[Line(0)][Column(1)].graphic().is_none());
+        assert!(term.graphics_take_queues().is_none());
+
+        term.clear_screen(ansi::ClearMode::All);
+        let queues = term.graphics_take_queues().unwrap();
+        assert_eq!(queues.remove_queue, vec![id]);
+    }
+
+    #[test]
+    fn sgr_status_report() {
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        assert_eq!(term.sgr_parameters(), "0");
+
+        term.terminal_attribute(Attr::Bold);
+        term.terminal_attribute(Attr::Undercurl);
+        term.terminal_attribute(Attr::Foreground(Color::Named(NamedColor::BrightRed)));
+        term.terminal_attribute(Attr::Background(Color::Indexed(123)));
+        let underline_color = Color::Spec(Rgb { r: 1, g: 2, b: 3 });
+        term.terminal_attribute(Attr::UnderlineColor(Some(underline_color)));
+        assert_eq!(term.sgr_parameters(), "0;1;4:3;91;48;5;123;58;2;1;2;3");
+    }
+
+    #[test]
+    fn kitty_graphics() {
+        let size = TermSize::new(5, 3);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        term.set_cell_size(2, 4);
+
+        let command = |data: &str| kitty::Command::parse(data.as_bytes()).unwrap();
+
+        // Transmit a 3x3 image and display it scaled to 2x1 cells.
+        let pixels = base64::encode([0; 3 * 3 * 4]);
+        term.kitty_graphics_command(command(&format!("a=t,f=32,s=3,v=3,i=7;{}", pixels)));
+        term.goto(Line(1), Column(1));
+        term.input('x');
+        term.goto(Line(1), Column(1));
+        term.kitty_graphics_command(command("a=p,i=7,p=1,c=2,r=1"));
+
+        // The cursor moved behind the image, without replacing the text below it.
+        assert_eq!(term.grid.cursor.point, Point::new(Line(1), Column(3)));
+        assert_eq!(term.grid[Line(1)][Column(2)].c, ' ');
+        let cell = &term.grid[Line(1)][Column(1)];
+        assert_eq!(cell.c, 'x');
+        let placement = &cell.graphic().unwrap().placement;
+        assert_eq!(placement.destination, PixelRect { x: 0, y: 0, width: 4, height: 4 });
+        assert!(term.grid[Line(1)][Column(2)].graphic().is_some());
+        assert!(term.grid[Line(1)][Column(3)].graphic().is_none());
+
+        // Text written on top of the image doesn't remove it.
+        term.goto(Line(1), Column(2));
+        term.input('y');
+        assert!(term.grid[Line(1)][Column(2)].graphic().is_some());
+
+        // Placing the image with the same placement ID replaces the old placement.
+        term.goto(Line(0), Column(0));
+        term.kitty_graphics_command(command("a=p,i=7,p=1,C=1"));
+        assert_eq!(term.grid.cursor.point, Point::new(Line(0), Column(0)));
+        assert!(term.grid[Line(1)][Column(2)].graphic().is_none());
+        assert!(term.grid[Line(0)][Column(1)].graphic().is_some());
+
+        // Deleting with an uppercase target frees the image data.
+        term.kitty_graphics_command(command("a=d,d=I,i=7"));
+        assert!(term.grid[Line(0)][Column(1)].graphic().is_none());
+        assert!(term.graphics.kitty_images.get(7).is_none());
+    }
+
+    #[test]
+    fn shell_integration_marks() {
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        // Prompt, command with two lines of output, followed by another prompt.
+        let command = |term: &mut Term<VoidListener>, output_lines: usize, exit_code| {
+            term.prompt_mark(PromptMark::PromptStart);
+            term.input('$');
+            term.prompt_mark(PromptMark::CommandStart);
+            term.carriage_return();
+            term.linefeed();
+            term.prompt_mark(PromptMark::OutputStart);
+            for _ in 0..output_lines {
+                term.input('x');
+                term.carriage_return();
+                term.linefeed();
+            }
+            term.prompt_mark(PromptMark::CommandEnd(exit_code));
+        };
+        command(&mut term, 2, Some(0));
+        command(&mut term, 3, Some(1));
+        term.prompt_mark(PromptMark::PromptStart);
+
+        assert_eq!(term.grid.row_attrs(Line(0)).shell_marks.exit_code, Some(0));
+        assert_eq!(term.grid.row_attrs(Line(3)).shell_marks.exit_code, Some(1));
+
+        assert_eq!(term.prompt_line(Line(9), Direction::Left), Some(Line(7)));
+        assert_eq!(term.prompt_line(Line(7), Direction::Left), Some(Line(3)));
+        assert_eq!(term.prompt_line(Line(3), Direction::Left), Some(Line(0)));
+        assert_eq!(term.prompt_line(Line(0), Direction::Left), None);
+        assert_eq!(term.prompt_line(Line(0), Direction::Right), Some(Line(3)));
+
+        assert_eq!(term.command_output(Line(2)), Some(Line(1)..=Line(2)));
+        assert_eq!(term.command_output(Line(3)), None);
+        assert_eq!(term.command_output(Line(8)), None);
+        assert_eq!(term.last_command_output(), Some(Line(4)..=Line(6)));
+    }
+
+    #[test]
+    fn working_directory_hostname() {
+        let size = TermSize::new(5, 10);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+
+        term.set_working_directory("", PathBuf::from("/tmp"));
+        assert_eq!(term.working_directory(), Some(Path::new("/tmp")));
+
+        term.set_working_directory("localhost", PathBuf::from("/home"));
+        assert_eq!(term.working_directory(), Some(Path::new("/home")));
+
+        // Directories on remote hosts are ignored.
+        term.set_working_directory("remote.invalid", PathBuf::from("/srv"));
+        assert_eq!(term.working_directory(), Some(Path::new("/home")));
+
+        if let Some(hostname) = local_hostname() {
+            term.set_working_directory(&hostname, PathBuf::from("/var"));
+            assert_eq!(term.working_directory(), Some(Path::new("/var")));
+        }
+    }
+
+    #[test]
+    fn keyboard_mode_stack() {
+        let size = TermSize::new(7, 17);
+        let mut term = Term::new(&Config::default(), &size, VoidListener);
+        let protocol_mode =
+            |term: &Term<VoidListener>| term.mode & TermMode::KITTY_KEYBOARD_PROTOCOL;
+
+        // Pushed modes become active.
+        term.push_keyboard_mode(KeyboardModes::DISAMBIGUATE_ESC_CODES);
+        term.push_keyboard_mode(KeyboardModes::REPORT_ALL_KEYS_AS_ESC);
+        assert_eq!(protocol_mode(&term), TermMode::REPORT_ALL_KEYS_AS_ESC);
+
+        // The active modes can be modified in place.
+        term.set_keyboard_mode(
+            KeyboardModes::REPORT_EVENT_TYPES,
+            KeyboardModesApplyBehavior::Union,
+        );
+        assert_eq!(
+            protocol_mode(&term),
+            TermMode::REPORT_ALL_KEYS_AS_ESC | TermMode::REPORT_EVENT_TYPES
+        );
+        term.set_keyboard_mode(
+            KeyboardModes::REPORT_ALL_KEYS_AS_ESC,
+            KeyboardModesApplyBehavior::Difference,
+        );
+        assert_eq!(protocol_mode(&term), TermMode::REPORT_EVENT_TYPES);
+
+        // Popping restores the previous modes.
+        term.pop_keyboard_modes(1);
+        assert_eq!(protocol_mode(&term), TermMode::DISAMBIGUATE_ESC_CODES);
+
+        // The alternate screen has its own stack.
+        term.swap_alt();
+        assert_eq!(protocol_mode(&term), TermMode::empty());
+        term.push_keyboard_mode(KeyboardModes::REPORT_ASSOCIATED_TEXT);
+        term.swap_alt();
+        assert_eq!(protocol_mode(&term), TermMode::DISAMBIGUATE_ESC_CODES);
+
+        // Popping more modes than available empties the stack.
+        term.pop_keyboard_modes(10);
+        assert_eq!(protocol_mode(&term), TermMode::empty());
+
+        // Keyboard mode stack doesn't grow infinitely.
+        for _ in 0..4097 {
+            term.push_keyboard_mode(KeyboardModes::DISAMBIGUATE_ESC_CODES);
+        }
+        assert_eq!(term.keyboard_mode_stack.len(), 4096);
+
+        // Keyboard modes are reset with the terminal state.
+        term.reset_state();
+        assert!(term.keyboard_mode_stack.is_empty());
+        assert_eq!(protocol_mode(&term), TermMode::empty());
+    }
+
     #[test]
     fn parse_cargo_version() {
         assert!(version_number(env!("CARGO_PKG_VERSION")) >= 10_01);
@@ -0,0 +1,216 @@
+//! Terminfo capabilities reported through XTGETTCAP.
+
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::mem;
+use std::str::Chars;
+
+use once_cell::sync::Lazy;
+
+/// Terminfo source of Alacritty's terminal definitions.
+static TERMINFO: &str =
+    include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../extra/alacritty.info"));
+
+/// Capabilities of the reported terminfo entry, including the ones inherited through `use`.
+static CAPABILITIES: Lazy<HashMap<String, Option<Capability>>> = Lazy::new(|| {
+    let mut capabilities = HashMap::new();
+    entry_capabilities(&entries(), TERMINFO_NAME, &mut capabilities);
+    capabilities
+});
+
+/// Name of the terminfo entry used for capability reports.
+pub const TERMINFO_NAME: &str = "alacritty";
+
+/// Value of a terminfo capability.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capability {
+    Boolean,
+    Number(u32),
+    String(String),
+}
+
+impl Capability {
+    /// Textual value of the capability, if it has one.
+    pub fn value(&self) -> Option<String> {
+        match self {
+            Self::Boolean => None,
+            Self::Number(number) => Some(number.to_string()),
+            Self::String(string) => Some(string.clone()),
+        }
+    }
+}
+
+/// Look up a capability of Alacritty's terminfo by its terminfo or termcap name.
+pub fn capability(name: &str) -> Option<Capability> {
+    let name = match name {
+        "TN" | "name" => return Some(Capability::String(TERMINFO_NAME.into())),
+        // Truecolor is always supported, even though it's only part of `alacritty-direct`.
+        "RGB" => return Some(Capability::Boolean),
+        "Co" => "colors",
+        name => name,
+    };
+
+    CAPABILITIES.get(name).cloned().flatten()
+}
+
+/// Split the terminfo source into its entries.
+///
+/// Every entry starts with a line containing its names, all following indented lines are joined
+/// into the capability list of the entry.
+fn entries() -> Vec<String> {
+    let mut entries: Vec<String> = Vec::new();
+    for line in TERMINFO.lines().filter(|line| !line.trim_start().starts_with('#')) {
+        match entries.last_mut() {
+            Some(entry) if line.starts_with(char::is_whitespace) => entry.push_str(line.trim()),
+            _ if !line.trim().is_empty() => entries.push(line.trim().to_owned()),
+            _ => (),
+        }
+    }
+    entries
+}
+
+/// Collect all capabilities of an entry, without overriding existing ones.
+///
+/// Cancelled capabilities are stored as `None`, to prevent inheriting them through `use`.
+fn entry_capabilities(
+    entries: &[String],
+    name: &str,
+    capabilities: &mut HashMap<String, Option<Capability>>,
+) {
+    let entry = entries.iter().map(|entry| fields(entry)).find(|fields| {
+        fields.first().map_or(false, |names| names.split('|').any(|entry| entry == name))
+    });
+    let fields = match entry {
+        Some(fields) => fields,
+        None => return,
+    };
+
+    // Capabilities of the entry itself take precedence over the ones in `use` fragments.
+    let mut fragments = Vec::new();
+    for field in &fields[1..] {
+        if let Some(fragment) = field.strip_prefix("use=") {
+            fragments.push(fragment);
+            continue;
+        }
+
+        let (name, capability) = match field.find(['@', '#', '=']) {
+            Some(index) if field[index..].starts_with('@') => (&field[..index], None),
+            Some(index) if field[index..].starts_with('#') => {
+                (&field[..index], parse_number(&field[index + 1..]).map(Capability::Number))
+            },
+            Some(index) => {
+                (&field[..index], Some(Capability::String(unescape(&field[index + 1..]))))
+            },
+            None => (field.as_str(), Some(Capability::Boolean)),
+        };
+        capabilities.entry(name.to_owned()).or_insert(capability);
+    }
+
+    for fragment in fragments {
+        entry_capabilities(entries, fragment, capabilities);
+    }
+}
+
+/// Split an entry into its comma separated fields.
+fn fields(entry: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = entry.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ',' => fields.push(mem::take(&mut field).trim().to_owned()),
+            '\\' => {
+                field.push(c);
+                field.extend(chars.next());
+            },
+            _ => field.push(c),
+        }
+    }
+    fields.retain(|field| !field.is_empty());
+    fields
+}
+
+/// Parse a decimal, octal or hexadecimal number.
+fn parse_number(number: &str) -> Option<u32> {
+    if let Some(hex) = number.strip_prefix("0x").or_else(|| number.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if number.len() > 1 && number.starts_with('0') {
+        u32::from_str_radix(&number[1..], 8).ok()
+    } else {
+        number.parse().ok()
+    }
+}
+
+/// Resolve the escapes of a terminfo string capability.
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('E' | 'e') => unescaped.push('\x1b'),
+                Some('n' | 'l') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some('t') => unescaped.push('\t'),
+                Some('b') => unescaped.push('\x08'),
+                Some('f') => unescaped.push('\x0c'),
+                Some('s') => unescaped.push(' '),
+                Some(digit @ '0'..='7') => unescaped.push(unescape_octal(digit, &mut chars)),
+                Some(c) => unescaped.push(c),
+                None => (),
+            },
+            '^' => match chars.next() {
+                Some('?') => unescaped.push('\x7f'),
+                Some(c) => unescaped.push((c as u8 & 0x1f) as char),
+                None => unescaped.push('^'),
+            },
+            c => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+/// Resolve an octal escape with up to three digits.
+fn unescape_octal(first_digit: char, chars: &mut Peekable<Chars<'_>>) -> char {
+    let mut value = first_digit as u32 - '0' as u32;
+    for _ in 0..2 {
+        match chars.peek().and_then(|c| c.to_digit(8)) {
+            Some(digit) => value = value * 8 + digit,
+            None => break,
+        }
+        chars.next();
+    }
+
+    // NUL is encoded as `\200`, to avoid terminating the string.
+    match value {
+        0 => '\u{80}',
+        value => char::from_u32(value).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn terminfo_capabilities() {
+        assert_eq!(capability("TN"), Some(Capability::String("alacritty".into())));
+        assert_eq!(capability("Co"), Some(Capability::Number(256)));
+        assert_eq!(capability("colors"), Some(Capability::Number(256)));
+        assert_eq!(capability("RGB"), Some(Capability::Boolean));
+        assert_eq!(capability("am"), Some(Capability::Boolean));
+        assert_eq!(capability("bel"), Some(Capability::String("\x07".into())));
+        assert_eq!(capability("Se"), Some(Capability::String("\x1b[0 q".into())));
+        assert_eq!(capability("rs1"), Some(Capability::String("\x1bc\x1b]104\x07".into())));
+        assert_eq!(
+            capability("setab"),
+            Some(Capability::String(
+                "\x1b[%?%p1%{8}%<%t4%p1%d%e%p1%{16}%<%t10%p1%{8}%-%d%e48;5;%p1%d%;m".into()
+            ))
+        );
+
+        // Capabilities cancelled in the entry are not inherited.
+        assert_eq!(capability("setb"), None);
+        assert_eq!(capability("unknown"), None);
+    }
+}
@@ -1,6 +1,7 @@
 //! TTY related functionality.
 
 use std::path::PathBuf;
+use std::process::ExitStatus;
 use std::{env, io};
 
 use crate::config::Config;
@@ -41,8 +42,8 @@ pub trait EventedReadWrite {
 /// Events concerning TTY child processes.
 #[derive(Debug, PartialEq, Eq)]
 pub enum ChildEvent {
-    /// Indicates the child has exited.
-    Exited,
+    /// Indicates the child has exited, with its exit status if it could be retrieved.
+    Exited(Option<ExitStatus>),
 }
 
 /// A pseudoterminal (or PTY).
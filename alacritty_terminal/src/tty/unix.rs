@@ -376,8 +376,7 @@ impl EventedPty for Pty {
                     error!("Error checking child process termination: {}", e);
                     None
                 },
-                Ok(None) => None,
-                Ok(_) => Some(ChildEvent::Exited),
+                Ok(status) => status.map(|status| ChildEvent::Exited(Some(status))),
             }
         })
     }
@@ -1,25 +1,39 @@
 use std::ffi::c_void;
 use std::io::Error;
+use std::os::windows::process::ExitStatusExt;
+use std::process::ExitStatus;
 use std::sync::atomic::{AtomicPtr, Ordering};
 
 use mio_extras::channel::{channel, Receiver, Sender};
 
 use windows_sys::Win32::Foundation::{BOOLEAN, HANDLE};
 use windows_sys::Win32::System::Threading::{
-    RegisterWaitForSingleObject, UnregisterWait, WT_EXECUTEINWAITTHREAD, WT_EXECUTEONLYONCE,
+    GetExitCodeProcess, RegisterWaitForSingleObject, UnregisterWait, WT_EXECUTEINWAITTHREAD,
+    WT_EXECUTEONLYONCE,
 };
 use windows_sys::Win32::System::WindowsProgramming::INFINITE;
 
 use crate::tty::ChildEvent;
 
+/// Context passed to the child exit callback.
+struct ChildExitSender {
+    event_tx: Sender<ChildEvent>,
+    child_handle: HANDLE,
+}
+
 /// WinAPI callback to run when child process exits.
 extern "system" fn child_exit_callback(ctx: *mut c_void, timed_out: BOOLEAN) {
     if timed_out != 0 {
         return;
     }
 
-    let event_tx: Box<_> = unsafe { Box::from_raw(ctx as *mut Sender<ChildEvent>) };
-    let _ = event_tx.send(ChildEvent::Exited);
+    let sender: Box<_> = unsafe { Box::from_raw(ctx as *mut ChildExitSender) };
+
+    let mut exit_code = 0;
+    let success = unsafe { GetExitCodeProcess(sender.child_handle, &mut exit_code) };
+    let status = (success != 0).then(|| ExitStatus::from_raw(exit_code));
+
+    let _ = sender.event_tx.send(ChildEvent::Exited(status));
 }
 
 pub struct ChildExitWatcher {
@@ -32,7 +46,7 @@ impl ChildExitWatcher {
         let (event_tx, event_rx) = channel::<ChildEvent>();
 
         let mut wait_handle: HANDLE = 0;
-        let sender_ref = Box::new(event_tx);
+        let sender_ref = Box::new(ChildExitSender { event_tx, child_handle });
 
         let success = unsafe {
             RegisterWaitForSingleObject(
@@ -103,6 +117,9 @@ mod tests {
         poll.poll(&mut events, Some(WAIT_TIMEOUT)).unwrap();
         assert_eq!(events.iter().next().unwrap().token(), child_events_token);
         // Verify that at least one `ChildEvent::Exited` was received.
-        assert_eq!(child_exit_watcher.event_rx().try_recv(), Ok(ChildEvent::Exited));
+        assert!(matches!(
+            child_exit_watcher.event_rx().try_recv(),
+            Ok(ChildEvent::Exited(Some(_)))
+        ));
     }
 }
@@ -29,14 +29,18 @@ macro_rules! ref_tests {
 
 ref_tests! {
     alt_reset
+    alt_screen_47
+    alt_screen_1047
     clear_underline
     colored_reset
     colored_underline
     csi_rep
     decaln_reset
     deccolm_reset
+    decslrm
     delete_chars_reset
     delete_lines
+    double_size_lines
     erase_chars_reset
     fish_cc
     grid_reset
@@ -46,14 +50,20 @@ ref_tests! {
     insert_blank_reset
     issue_855
     ll
+    mouse_encodings
     newline_with_cursor_beyond_scroll_region
     region_scroll_down
+    reverse_video
     row_reset
+    save_cursor_1048
     saved_cursor
     saved_cursor_alt
     scroll_up_reset
     selective_erasure
     sgr
+    status_line
+    sync_update
+    sync_update_unterminated
     tab_rendering
     tmux_git_log
     tmux_htop
@@ -15,11 +15,19 @@ complete -c alacritty -n "__fish_use_subcommand" -s v -d 'Increases the level of
 complete -c alacritty -n "__fish_use_subcommand" -l hold -d 'Remain open after child process exit'
 complete -c alacritty -n "__fish_use_subcommand" -f -a "msg" -d 'Send a message to the Alacritty socket'
 complete -c alacritty -n "__fish_use_subcommand" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -s s -l socket -d 'IPC socket connection path override' -r -F
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help information'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
-complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -s s -l socket -d 'IPC socket connection path override' -r -F
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "create-window" -d 'Create a new window in the same Alacritty process'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "config" -d 'Update the Alacritty configuration'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "list-windows" -d 'List all windows with their IDs, titles, sizes and shell PIDs'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "focus-window" -d 'Focus a window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "close-window" -d 'Close a window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "resize-window" -d 'Resize a window to fit the requested terminal dimensions'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "send-text" -d 'Write text to the PTY of a window'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "get-text" -d 'Print the text content of a window\'s terminal'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "subscribe" -d 'Print terminal events as newline-delimited JSON until Alacritty exits'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "restart-shell" -d 'Terminate the shell of a window and start a new one'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and not __fish_seen_subcommand_from create-window; and not __fish_seen_subcommand_from config; and not __fish_seen_subcommand_from list-windows; and not __fish_seen_subcommand_from focus-window; and not __fish_seen_subcommand_from close-window; and not __fish_seen_subcommand_from resize-window; and not __fish_seen_subcommand_from send-text; and not __fish_seen_subcommand_from get-text; and not __fish_seen_subcommand_from subscribe; and not __fish_seen_subcommand_from restart-shell; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -l working-directory -d 'Start the shell in the specified working directory' -r -F
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -s e -l command -d 'Command and args to execute (must be last argument)' -r
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from create-window" -s T -l title -d 'Defines the window title [default: Alacritty]' -r
@@ -29,3 +37,19 @@ complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subco
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s w -l window-id -d 'Window ID for the new config' -r
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s r -l reset -d 'Clear all runtime configuration changes'
 complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from config" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from list-windows" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from focus-window" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from focus-window" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from close-window" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from close-window" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from resize-window" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from resize-window" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from send-text" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -s s -l scrollback -d 'Include the scrollback history instead of just the visible screen'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from get-text" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from subscribe" -s h -l help -d 'Print help information'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from restart-shell" -s w -l window-id -d 'Window ID targeted by the message' -r
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from restart-shell" -s k -l keep-scrollback -d 'Keep the scrollback history of the old shell'
+complete -c alacritty -n "__fish_seen_subcommand_from msg; and __fish_seen_subcommand_from restart-shell" -s h -l help -d 'Print help information'